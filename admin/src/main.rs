@@ -0,0 +1,498 @@
+extern crate log;
+
+mod redact;
+
+use std::fs::OpenOptions;
+use std::io::{
+    BufRead,
+    Write
+};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{
+    Path,
+    PathBuf
+};
+use std::process::ExitCode;
+
+use clap::{
+    Parser,
+    Subcommand
+};
+
+use configuration::{
+    ApplicationConfiguration,
+    JWTKey,
+    KeyAlgorithm
+};
+use data::pg::migrations::SchemaStatus;
+use tokenizer::Tokenizer;
+use tokenizer::keyring::Keyring;
+use common::cache::PermissionCache;
+
+use auth::auth::Auth;
+use roles::roles::Roles;
+use tenants::tenants::Tenants;
+use users::users::Users;
+
+
+/// bootstrapping and operations of an ideas deployment. the configuration
+/// is loaded the same way as the server's, from CFG and IDEAS__ variables
+#[derive(Parser)]
+#[command(name = "ideas-admin")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// manage tenants
+    #[command(subcommand)]
+    Tenant(TenantCommand),
+
+    /// manage users
+    #[command(subcommand)]
+    User(UserCommand),
+
+    /// manage token signing keys
+    #[command(subcommand)]
+    Jwt(JwtCommand),
+
+    /// apply pending database migrations
+    Migrate {
+        /// version the schema of an unversioned database already matches,
+        /// e.g. 5 for a database created with the ideas-postgres scripts.
+        /// the migrations up to it are recorded without running them
+        #[arg(long)]
+        baseline: Option<i32>
+    },
+
+    /// print the effective configuration with secrets redacted
+    Config
+}
+
+#[derive(Subcommand)]
+enum TenantCommand {
+    /// add a tenant with the built-in roles, the owner is assigned the owner role
+    Create {
+        #[arg(long)]
+        name: String,
+
+        #[arg(long)]
+        slug: String,
+
+        #[arg(long, default_value = "")]
+        description: String,
+
+        /// email address of an existing user
+        #[arg(long)]
+        owner: String
+    }
+}
+
+#[derive(Subcommand)]
+enum UserCommand {
+    /// add an active user, the password is read from stdin
+    Create {
+        #[arg(long)]
+        email: String
+    },
+
+    /// activate a user
+    Activate {
+        #[arg(long)]
+        email: String
+    },
+
+    /// add a user to a tenant and assign one of the tenant's roles
+    Assign {
+        #[arg(long)]
+        email: String,
+
+        /// slug of the tenant
+        #[arg(long)]
+        tenant: String,
+
+        /// slug of the role, e.g. owner
+        #[arg(long)]
+        role: String
+    },
+
+    /// set a user's password, read from stdin, and revoke all sessions
+    Password {
+        #[arg(long)]
+        email: String
+    }
+}
+
+#[derive(Subcommand)]
+enum JwtCommand {
+    /// generate an EdDSA key pair and print the jwt configuration signing
+    /// with it, previous keys are kept to verify issued tokens
+    Rotate {
+        /// directory the PEM files are written to
+        #[arg(long)]
+        dir: PathBuf,
+
+        /// key id, defaults to the current date
+        #[arg(long)]
+        kid: Option<String>
+    }
+}
+
+
+/// the modules used by commands working with the database
+struct Modules {
+    data: data::Data,
+    auth: Auth,
+    users: Users,
+    roles: Roles,
+    tenants: Tenants
+}
+
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    env_logger::init();
+    let cli = Cli::parse();
+
+    let cfg = match ApplicationConfiguration::load() {
+        Err(e) => {
+            for problem in e.problems() {
+                eprintln!("configuration: {}", problem);
+            }
+            return ExitCode::FAILURE;
+        }
+        Ok(cfg) => cfg
+    };
+
+    let result = match cli.command {
+        Command::Config => config(&cfg),
+        Command::Jwt(JwtCommand::Rotate { dir, kid }) => jwt_rotate(&cfg, &dir, kid),
+        Command::Migrate { baseline } => migrate(&cfg, baseline).await,
+        Command::Tenant(command) => {
+            match modules(&cfg).await {
+                Err(e) => Err(e),
+                Ok(modules) => tenant(&modules, command).await
+            }
+        }
+        Command::User(command) => {
+            match modules(&cfg).await {
+                Err(e) => Err(e),
+                Ok(modules) => user(&modules, command).await
+            }
+        }
+    };
+
+    if let Err(e) = result {
+        eprintln!("error: {}", e);
+        return ExitCode::FAILURE;
+    }
+    return ExitCode::SUCCESS;
+}
+
+/// initialize the modules, refusing to work with a schema older than the
+/// embedded migrations
+async fn modules(cfg: &ApplicationConfiguration) -> Result<Modules, String> {
+    let tokenizer = match Tokenizer::from_configuration(&cfg.jwt) {
+        Err(e) => {
+            return Err(format!("unable to initialize tokenizer: {}", e));
+        }
+        Ok(tokenizer) => tokenizer
+    };
+    let mailer = mailer::Mailer::new(
+        &cfg.mailer.host,
+        &cfg.mailer.user,
+        &cfg.mailer.password
+    );
+    let data = data::Data::new(cfg.clone());
+
+    match data.schema_status().await {
+        Err(e) => {
+            return Err(format!("unable to check database schema version: {:?}", e));
+        }
+        Ok(SchemaStatus::Behind { current: 0, latest }) => {
+            return Err(format!("database schema version 0 is behind {}, run migrate, or migrate --baseline 5 for a database created with the ideas-postgres scripts", latest));
+        }
+        Ok(SchemaStatus::Behind { current, latest }) => {
+            return Err(format!("database schema version {} is behind {}, run migrate", current, latest));
+        }
+        Ok(_) => {}
+    }
+
+    let permissions = PermissionCache::new();
+    return Ok(Modules {
+        auth: Auth::new(cfg.clone(), mailer.clone(), tokenizer.clone(), data.clone()),
+        users: Users::new(cfg.clone(), mailer.clone(), tokenizer.clone(), data.clone(), permissions.clone()),
+        roles: Roles::new(cfg.clone(), data.clone(), permissions),
+        tenants: Tenants::new(cfg.clone(), data.clone()),
+        data: data
+    });
+}
+
+async fn tenant(modules: &Modules, command: TenantCommand) -> Result<(), String> {
+    match command {
+        TenantCommand::Create { name, slug, description, owner } => {
+            let owner = match modules.users.user_by_email(&owner).await {
+                Err(e) => {
+                    return Err(format!("unable to find owner {}: {:?}", owner, e));
+                }
+                Ok(owner) => owner
+            };
+
+            // the tenant and its roles are added together or not at all
+            let id = uuid::Uuid::new_v4();
+            let uow = match modules.data.begin().await {
+                Err(e) => {
+                    return Err(format!("unable to begin transaction: {:?}", e));
+                }
+                Ok(uow) => uow
+            };
+            if let Err(e) = modules.tenants.tenant_add_in(&uow, id, &name, &slug, &description).await {
+                if let Err(e) = uow.rollback().await {
+                    eprintln!("unable to roll back tenant: {:?}", e);
+                }
+                return Err(format!("unable to add tenant: {:?}", e));
+            }
+            if let Err(e) = modules.roles.tenant_roles_seed(&uow, &id, &owner.id()).await {
+                if let Err(e) = uow.rollback().await {
+                    eprintln!("unable to roll back tenant: {:?}", e);
+                }
+                return Err(format!("unable to add tenant roles: {:?}", e));
+            }
+            if let Err(e) = uow.commit().await {
+                return Err(format!("unable to commit tenant: {:?}", e));
+            }
+
+            println!("added tenant {} ({})", slug, id);
+            return Ok(());
+        }
+    }
+}
+
+async fn user(modules: &Modules, command: UserCommand) -> Result<(), String> {
+    match command {
+        UserCommand::Create { email } => {
+            let pw = password()?;
+            let id = uuid::Uuid::new_v4();
+            if let Err(e) = modules.auth.user_create(&id, &email, &pw).await {
+                return Err(format!("unable to create user {}: {:?}", email, e));
+            }
+
+            println!("created user {} ({})", email, id);
+            return Ok(());
+        }
+        UserCommand::Activate { email } => {
+            let user = user_by_email(modules, &email).await?;
+            if let Err(e) = modules.users.user_set_active(&user.id(), &true).await {
+                return Err(format!("unable to activate user {}: {:?}", email, e));
+            }
+
+            println!("activated user {}", email);
+            return Ok(());
+        }
+        UserCommand::Assign { email, tenant, role } => {
+            let user = user_by_email(modules, &email).await?;
+            let tenant = match modules.tenants.tenant_by_slug(&tenant).await {
+                Err(e) => {
+                    return Err(format!("unable to find tenant {}: {:?}", tenant, e));
+                }
+                Ok(tenant) => tenant
+            };
+            let roles = match modules.roles.roles_fetch(&tenant.id()).await {
+                Err(e) => {
+                    return Err(format!("unable to retrieve roles of tenant {}: {:?}", tenant.slug(), e));
+                }
+                Ok(roles) => roles
+            };
+            let role = match roles.iter().find(|r| r.slug() == role) {
+                None => {
+                    return Err(format!("tenant {} has no role {}", tenant.slug(), role));
+                }
+                Some(role) => role
+            };
+
+            let tenants = match modules.users.user_tenants(&user.id()).await {
+                Err(e) => {
+                    return Err(format!("unable to retrieve tenants of user {}: {:?}", email, e));
+                }
+                Ok(tenants) => tenants
+            };
+            if !tenants.iter().any(|t| t.id() == tenant.id()) {
+                if let Err(e) = modules.users.user_tenant_add(&user.id(), &tenant.id()).await {
+                    return Err(format!("unable to add user {} to tenant {}: {:?}", email, tenant.slug(), e));
+                }
+            }
+            if let Err(e) = modules.roles.user_role_add(&tenant.id(), &user.id(), &role.id()).await {
+                return Err(format!("unable to assign role {}: {:?}", role.slug(), e));
+            }
+
+            println!("assigned role {} of tenant {} to user {}", role.slug(), tenant.slug(), email);
+            return Ok(());
+        }
+        UserCommand::Password { email } => {
+            let user = user_by_email(modules, &email).await?;
+            let pw = password()?;
+            // no current session, so every session is revoked
+            if let Err(e) = modules.users.user_set_password(&user.id(), &email, &pw, &uuid::Uuid::nil()).await {
+                return Err(format!("unable to set password of user {}: {:?}", email, e));
+            }
+
+            println!("set password of user {}", email);
+            return Ok(());
+        }
+    }
+}
+
+async fn user_by_email(modules: &Modules, email: &str) -> Result<common::user::User, String> {
+    match modules.users.user_by_email(&email).await {
+        Err(e) => {
+            return Err(format!("unable to find user {}: {:?}", email, e));
+        }
+        Ok(user) => {
+            return Ok(user);
+        }
+    }
+}
+
+/// read a password from the first line of stdin
+fn password() -> Result<String, String> {
+    eprint!("password: ");
+    let mut line = String::new();
+    if let Err(e) = std::io::stdin().lock().read_line(&mut line) {
+        return Err(format!("unable to read password: {}", e));
+    }
+    let pw = line.trim_end_matches(['\r', '\n']);
+    if pw.is_empty() {
+        return Err(String::from("no password given"));
+    }
+    return Ok(String::from(pw));
+}
+
+fn jwt_rotate(
+    cfg: &ApplicationConfiguration,
+    dir: &Path,
+    kid: Option<String>
+) -> Result<(), String> {
+    let kid = kid.unwrap_or_else(|| chrono::Utc::now().format("%Y-%m-%d").to_string());
+    if cfg.jwt.keys.iter().any(|k| k.kid == kid) {
+        return Err(format!("a key with kid {} is already configured, choose another with --kid", kid));
+    }
+
+    let (private_pem, public_pem) = match Keyring::generate_eddsa() {
+        Err(e) => {
+            return Err(format!("unable to generate key pair: {}", e));
+        }
+        Ok(pems) => pems
+    };
+    let private_key = dir.join(format!("jwt-{}.pem", kid));
+    let public_key = dir.join(format!("jwt-{}.pub.pem", kid));
+    write_new(&private_key, &private_pem, 0o600)?;
+    write_new(&public_key, &public_pem, 0o644)?;
+
+    let mut jwt = cfg.jwt.clone();
+    // the legacy secret is only used while no keys are configured
+    if jwt.keys.is_empty() {
+        if let Some(secret) = jwt.secret.take() {
+            jwt.keys.push(JWTKey {
+                kid: String::from("default"),
+                algorithm: KeyAlgorithm::HS256,
+                secret: Some(secret),
+                private_key: None,
+                public_key: None
+            });
+        }
+    }
+    jwt.keys.push(JWTKey {
+        kid: kid.clone(),
+        algorithm: KeyAlgorithm::EdDSA,
+        secret: None,
+        private_key: Some(private_key.display().to_string()),
+        public_key: Some(public_key.display().to_string())
+    });
+    jwt.signing_key = Some(kid.clone());
+
+    let mut value = match serde_json::to_value(&jwt) {
+        Err(e) => {
+            return Err(format!("unable to serialize jwt configuration: {}", e));
+        }
+        Ok(value) => value
+    };
+    redact::redact(&mut value);
+
+    eprintln!("wrote {} and {}", private_key.display(), public_key.display());
+    eprintln!("replace the jwt configuration with the following, keeping the current values of redacted secrets.");
+    eprintln!("previous keys can be removed once tokens signed with them have expired.");
+    println!("{}", serde_json::to_string_pretty(&serde_json::json!({ "jwt": value })).unwrap());
+    return Ok(());
+}
+
+/// write a file that must not exist yet
+fn write_new(path: &Path, contents: &str, mode: u32) -> Result<(), String> {
+    let file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(mode)
+        .open(path);
+    match file {
+        Err(e) => {
+            return Err(format!("unable to create {}: {}", path.display(), e));
+        }
+        Ok(mut file) => {
+            if let Err(e) = file.write_all(contents.as_bytes()) {
+                return Err(format!("unable to write {}: {}", path.display(), e));
+            }
+            return Ok(());
+        }
+    }
+}
+
+async fn migrate(cfg: &ApplicationConfiguration, baseline: Option<i32>) -> Result<(), String> {
+    let data = data::Data::new(cfg.clone());
+    match data.migrate(baseline).await {
+        Err(e) => {
+            return Err(format!("unable to migrate database schema: {:?}", e));
+        }
+        Ok(applied) if applied.is_empty() => {
+            println!("database schema is up to date");
+            return Ok(());
+        }
+        Ok(applied) => {
+            println!("applied migrations: {:?}", applied);
+            return Ok(());
+        }
+    }
+}
+
+fn config(cfg: &ApplicationConfiguration) -> Result<(), String> {
+    let mut value = match serde_json::to_value(cfg) {
+        Err(e) => {
+            return Err(format!("unable to serialize configuration: {}", e));
+        }
+        Ok(value) => value
+    };
+    redact::redact(&mut value);
+    println!("{}", serde_json::to_string_pretty(&value).unwrap());
+    return Ok(());
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_cli() {
+        Cli::command().debug_assert();
+
+        let cli = Cli::try_parse_from([
+            "ideas-admin", "user", "assign", "--email", "a@b.io", "--tenant", "acme", "--role", "owner"
+        ]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::User(UserCommand::Assign { ref tenant, ref role, .. }) if tenant == "acme" && role == "owner"
+        ));
+        assert!(Cli::try_parse_from(["ideas-admin", "tenant", "create", "--name", "Acme"]).is_err());
+    }
+}
@@ -0,0 +1,90 @@
+use serde::{Serialize, Deserialize};
+
+use chrono::prelude::*;
+
+
+/// machine credential scoped to a tenant. only the digest of the key is
+/// stored, the prefix identifies the key when listing keys
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ApiKey {
+    id: uuid::Uuid,
+    tenant_id: uuid::Uuid,
+    name: String,
+    prefix: String,
+    /// permissions the key is restricted to. keys created before scopes were
+    /// required have none and are granted no permission
+    permissions: Option<Vec<String>>,
+    created: DateTime<Utc>,
+    expires: Option<DateTime<Utc>>,
+    last_used: Option<DateTime<Utc>>,
+    revoked: bool
+}
+
+
+impl ApiKey {
+
+    pub fn new(
+        id: &uuid::Uuid,
+        tenant_id: &uuid::Uuid,
+        name: &str,
+        prefix: &str,
+        permissions: Option<Vec<String>>,
+        created: &DateTime<Utc>,
+        expires: Option<DateTime<Utc>>,
+        last_used: Option<DateTime<Utc>>,
+        revoked: bool
+    ) -> Self {
+        return Self {
+            id: id.clone(),
+            tenant_id: tenant_id.clone(),
+            name: String::from(name),
+            prefix: String::from(prefix),
+            permissions: permissions,
+            created: created.clone(),
+            expires: expires,
+            last_used: last_used,
+            revoked: revoked
+        };
+    }
+
+    pub fn id(&self) -> uuid::Uuid {
+        return self.id.clone();
+    }
+
+    pub fn tenant_id(&self) -> uuid::Uuid {
+        return self.tenant_id.clone();
+    }
+
+    pub fn name(&self) -> String {
+        return self.name.clone();
+    }
+
+    pub fn prefix(&self) -> String {
+        return self.prefix.clone();
+    }
+
+    pub fn permissions(&self) -> Option<Vec<String>> {
+        return self.permissions.clone();
+    }
+
+    pub fn created(&self) -> DateTime<Utc> {
+        return self.created.clone();
+    }
+
+    pub fn expires(&self) -> Option<DateTime<Utc>> {
+        return self.expires.clone();
+    }
+
+    pub fn last_used(&self) -> Option<DateTime<Utc>> {
+        return self.last_used.clone();
+    }
+
+    pub fn revoked(&self) -> bool {
+        return self.revoked;
+    }
+
+    /// check if the key can be used to authenticate
+    pub fn is_valid(&self, now: &DateTime<Utc>) -> bool {
+        return !self.revoked && self.expires.is_none_or(|expires| expires > *now);
+    }
+}
@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{
+    Arc,
+    RwLock
+};
+use std::time::{
+    Duration,
+    Instant
+};
+
+
+/// number of entries above which expired entries are pruned on insert
+const PRUNE_THRESHOLD: usize = 10_000;
+
+
+/// in-memory cache whose entries expire a fixed time after they are inserted.
+/// clones share the same entries
+#[derive(Debug, Clone)]
+pub struct TtlCache<K, V> {
+    ttl: Duration,
+    entries: Arc<RwLock<HashMap<K, (Instant, V)>>>
+}
+
+impl<K, V> TtlCache<K, V>
+where
+    K: Eq + Hash + Clone,
+    V: Clone
+{
+
+    pub fn new(ttl: Duration) -> Self {
+        return Self {
+            ttl: ttl,
+            entries: Arc::new(RwLock::new(HashMap::new()))
+        };
+    }
+
+    /// retrieve an entry that has not expired
+    pub fn get(&self, key: &K) -> Option<V> {
+        let entries = match self.entries.read() {
+            Err(_) => {
+                return None;
+            }
+            Ok(entries) => entries
+        };
+        match entries.get(key) {
+            Some((inserted, value)) if inserted.elapsed() < self.ttl => {
+                return Some(value.clone());
+            }
+            _ => {
+                return None;
+            }
+        }
+    }
+
+    pub fn insert(&self, key: &K, value: &V) {
+        if let Ok(mut entries) = self.entries.write() {
+            if entries.len() >= PRUNE_THRESHOLD {
+                let ttl = self.ttl;
+                entries.retain(|_, (inserted, _)| inserted.elapsed() < ttl);
+            }
+            entries.insert(key.clone(), (Instant::now(), value.clone()));
+        }
+    }
+
+    pub fn invalidate(&self, key: &K) {
+        if let Ok(mut entries) = self.entries.write() {
+            entries.remove(key);
+        }
+    }
+
+    /// remove every entry matching the predicate
+    pub fn invalidate_where<F: Fn(&K, &V) -> bool>(&self, predicate: F) {
+        if let Ok(mut entries) = self.entries.write() {
+            entries.retain(|k, (_, v)| !predicate(k, v));
+        }
+    }
+}
+
+
+/// number of seconds permissions granted to a user in a tenant are cached
+const PERMISSIONS_TTL_SECONDS: u64 = 30;
+
+
+/// permissions granted to users by tenant, keyed by user and tenant. shared
+/// by the modules that read permissions and those that change them, so that
+/// changes to memberships and roles take effect immediately
+#[derive(Debug, Clone)]
+pub struct PermissionCache {
+    entries: TtlCache<(uuid::Uuid, uuid::Uuid), Vec<String>>
+}
+
+impl PermissionCache {
+
+    pub fn new() -> Self {
+        return Self {
+            entries: TtlCache::new(Duration::from_secs(PERMISSIONS_TTL_SECONDS))
+        };
+    }
+
+    pub fn get(&self, user_id: &uuid::Uuid, tenant_id: &uuid::Uuid) -> Option<Vec<String>> {
+        return self.entries.get(&(*user_id, *tenant_id));
+    }
+
+    pub fn insert(&self, user_id: &uuid::Uuid, tenant_id: &uuid::Uuid, permissions: &Vec<String>) {
+        self.entries.insert(&(*user_id, *tenant_id), &permissions);
+    }
+
+    /// discard the permissions of a user in a tenant
+    pub fn invalidate(&self, user_id: &uuid::Uuid, tenant_id: &uuid::Uuid) {
+        self.entries.invalidate(&(*user_id, *tenant_id));
+    }
+
+    /// discard the permissions of a user in every tenant
+    pub fn invalidate_user(&self, user_id: &uuid::Uuid) {
+        self.entries.invalidate_where(|(user, _), _| user == user_id);
+    }
+
+    /// discard the permissions of every user in a tenant
+    pub fn invalidate_tenant(&self, tenant_id: &uuid::Uuid) {
+        self.entries.invalidate_where(|(_, tenant), _| tenant == tenant_id);
+    }
+}
+
+impl Default for PermissionCache {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get() {
+        let cache: TtlCache<String, i32> = TtlCache::new(Duration::from_secs(60));
+        assert_eq!(cache.get(&String::from("a")), None);
+
+        cache.insert(&String::from("a"), &1);
+        assert_eq!(cache.get(&String::from("a")), Some(1));
+
+        // clones share entries
+        let clone = cache.clone();
+        clone.insert(&String::from("b"), &2);
+        assert_eq!(cache.get(&String::from("b")), Some(2));
+    }
+
+    #[test]
+    fn test_expiry() {
+        let cache: TtlCache<String, i32> = TtlCache::new(Duration::ZERO);
+        cache.insert(&String::from("a"), &1);
+        assert_eq!(cache.get(&String::from("a")), None);
+    }
+
+    #[test]
+    fn test_invalidate() {
+        let cache: TtlCache<(i32, i32), i32> = TtlCache::new(Duration::from_secs(60));
+        cache.insert(&(1, 1), &1);
+        cache.insert(&(1, 2), &2);
+        cache.insert(&(2, 1), &3);
+
+        cache.invalidate(&(2, 1));
+        assert_eq!(cache.get(&(2, 1)), None);
+
+        cache.invalidate_where(|k, _| k.0 == 1);
+        assert_eq!(cache.get(&(1, 1)), None);
+        assert_eq!(cache.get(&(1, 2)), None);
+    }
+
+    #[test]
+    fn test_permission_cache() {
+        let (user, other_user) = (uuid::Uuid::new_v4(), uuid::Uuid::new_v4());
+        let (tenant, other_tenant) = (uuid::Uuid::new_v4(), uuid::Uuid::new_v4());
+        let permissions = vec![String::from("roles.read")];
+
+        let cache = PermissionCache::new();
+        let clone = cache.clone();
+        cache.insert(&user, &tenant, &permissions);
+        cache.insert(&user, &other_tenant, &permissions);
+        cache.insert(&other_user, &tenant, &permissions);
+        assert_eq!(clone.get(&user, &tenant), Some(permissions.clone()));
+
+        clone.invalidate_tenant(&tenant);
+        assert_eq!(cache.get(&user, &tenant), None);
+        assert_eq!(cache.get(&other_user, &tenant), None);
+        assert_eq!(cache.get(&user, &other_tenant), Some(permissions.clone()));
+
+        clone.invalidate_user(&user);
+        assert_eq!(cache.get(&user, &other_tenant), None);
+    }
+}
@@ -0,0 +1,199 @@
+use std::fmt::Display;
+use std::collections::BTreeMap;
+
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+
+use actix_web::{
+    HttpResponse,
+    ResponseError,
+    http::StatusCode
+};
+
+use crate::response::ApiResponse;
+
+
+/// stable machine readable error codes, clients should branch on these
+/// rather than on messages
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    NotFound,
+    Conflict,
+    Validation,
+    Unauthorized,
+    Forbidden,
+    TooManyRequests,
+    Unavailable,
+    Internal
+}
+
+impl ErrorCode {
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::NotFound => "not_found",
+            ErrorCode::Conflict => "conflict",
+            ErrorCode::Validation => "validation",
+            ErrorCode::Unauthorized => "unauthorized",
+            ErrorCode::Forbidden => "forbidden",
+            ErrorCode::TooManyRequests => "too_many_requests",
+            ErrorCode::Unavailable => "unavailable",
+            ErrorCode::Internal => "internal"
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ErrorCode::NotFound => StatusCode::NOT_FOUND,
+            ErrorCode::Conflict => StatusCode::CONFLICT,
+            ErrorCode::Validation => StatusCode::UNPROCESSABLE_ENTITY,
+            ErrorCode::Unauthorized => StatusCode::UNAUTHORIZED,
+            ErrorCode::Forbidden => StatusCode::FORBIDDEN,
+            ErrorCode::TooManyRequests => StatusCode::TOO_MANY_REQUESTS,
+            ErrorCode::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+            ErrorCode::Internal => StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+impl Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return write!(f, "{}", self.as_str());
+    }
+}
+
+
+/// error shared by the modules. the message is returned to the client so it
+/// must not carry internal details
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    ConfigurationError(String),
+    ValidationError(String),
+    /// messages of the failing fields keyed by field name
+    InvalidFields(BTreeMap<String, Vec<String>>),
+    NotFound(String),
+    Conflict(String),
+    Unauthorized(String),
+    Forbidden(String),
+    TooManyRequests(String),
+    Unavailable(String),
+    Internal(String)
+}
+
+impl Error {
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Error::ConfigurationError(_) => ErrorCode::Internal,
+            Error::ValidationError(_) => ErrorCode::Validation,
+            Error::InvalidFields(_) => ErrorCode::Validation,
+            Error::NotFound(_) => ErrorCode::NotFound,
+            Error::Conflict(_) => ErrorCode::Conflict,
+            Error::Unauthorized(_) => ErrorCode::Unauthorized,
+            Error::Forbidden(_) => ErrorCode::Forbidden,
+            Error::TooManyRequests(_) => ErrorCode::TooManyRequests,
+            Error::Unavailable(_) => ErrorCode::Unavailable,
+            Error::Internal(_) => ErrorCode::Internal
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            Error::ConfigurationError(m)
+            | Error::ValidationError(m)
+            | Error::NotFound(m)
+            | Error::Conflict(m)
+            | Error::Unauthorized(m)
+            | Error::Forbidden(m)
+            | Error::TooManyRequests(m)
+            | Error::Unavailable(m)
+            | Error::Internal(m) => m.clone(),
+            Error::InvalidFields(_) => String::from("request is not valid")
+        }
+    }
+
+    /// error response with details of the error in data, e.g. the rules a
+    /// password failed
+    pub fn error_response_with(&self, data: Value) -> HttpResponse {
+        return HttpResponse::build(self.status_code())
+            .json(ApiResponse::error(self).with_data(data));
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return write!(f, "{}: {}", self.code(), self.message());
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl ResponseError for Error {
+    fn status_code(&self) -> StatusCode {
+        return self.code().status();
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        return HttpResponse::build(self.status_code())
+            .json(ApiResponse::error(self));
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_codes() {
+        let e = Error::Conflict(String::from("tenant slug is already in use"));
+        assert_eq!(e.code(), ErrorCode::Conflict);
+        assert_eq!(e.status_code(), StatusCode::CONFLICT);
+        assert_eq!(e.to_string(), "conflict: tenant slug is already in use");
+
+        let e = Error::ValidationError(String::from("name is required"));
+        assert_eq!(e.status_code(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            serde_json::to_value(e.code()).unwrap(),
+            serde_json::json!("validation")
+        );
+    }
+
+    #[test]
+    fn test_error_response() {
+        let e = Error::NotFound(String::from("organization not found"));
+        let response = e.error_response();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let body = serde_json::to_value(ApiResponse::error(&e)).unwrap();
+        assert_eq!(body["success"], false);
+        assert_eq!(body["code"], "not_found");
+        assert_eq!(body["message"], "organization not found");
+    }
+
+    #[test]
+    fn test_invalid_fields() {
+        let mut fields = BTreeMap::new();
+        fields.insert(String::from("slug"), vec![String::from("invalid slug")]);
+        let e = Error::InvalidFields(fields);
+        assert_eq!(e.status_code(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let body = serde_json::to_value(ApiResponse::error(&e)).unwrap();
+        assert_eq!(body["code"], "validation");
+        assert_eq!(body["data"]["fields"]["slug"][0], "invalid slug");
+    }
+
+    #[test]
+    fn test_error_response_with() {
+        let e = Error::TooManyRequests(String::from("too many failed sign in attempts"));
+        let response = e.error_response_with(serde_json::json!({ "retry_after": 30 }));
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        let body = serde_json::to_value(
+            ApiResponse::error(&e).with_data(serde_json::json!({ "retry_after": 30 }))
+        ).unwrap();
+        assert_eq!(body["code"], "too_many_requests");
+        assert_eq!(body["data"]["retry_after"], 30);
+    }
+}
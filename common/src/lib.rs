@@ -3,14 +3,22 @@ pub mod country;
 pub mod user;
 // pub mod client;
 pub mod tenant;
+pub mod organization;
+pub mod session;
+pub mod api_key;
+pub mod role;
+pub mod permission;
+pub mod password;
+pub mod cache;
 pub mod hr;
 
-#[derive(Debug)]
-pub enum Error {
-    ToBeImplemented(String),
-    ConfigurationError(String),
-    ValidationError(String)
-}
+pub mod error;
+pub mod response;
+
+pub use error::{
+    Error,
+    ErrorCode
+};
 
 
 #[cfg(test)]
@@ -0,0 +1,50 @@
+use serde::{Serialize, Deserialize};
+
+
+/// organization belonging to a tenant
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Organization {
+    id: uuid::Uuid,
+    tenant_id: uuid::Uuid,
+    active: bool,
+    name: String,
+    description: String
+}
+
+impl Organization {
+    pub fn new(
+        id: &uuid::Uuid,
+        tenant_id: &uuid::Uuid,
+        active: &bool,
+        name: &str,
+        description: &str
+    ) -> Self {
+        return Self {
+            id: id.clone(),
+            tenant_id: tenant_id.clone(),
+            active: active.clone(),
+            name: String::from(name),
+            description: String::from(description)
+        };
+    }
+
+    pub fn id(&self) -> uuid::Uuid {
+        return self.id.clone();
+    }
+
+    pub fn tenant_id(&self) -> uuid::Uuid {
+        return self.tenant_id.clone();
+    }
+
+    pub fn active(&self) -> bool {
+        return self.active.clone();
+    }
+
+    pub fn name(&self) -> String {
+        return self.name.clone();
+    }
+
+    pub fn description(&self) -> String {
+        return self.description.clone();
+    }
+}
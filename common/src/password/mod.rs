@@ -0,0 +1,169 @@
+use serde::{
+    Serialize,
+    Deserialize
+};
+
+use configuration::PasswordPolicy;
+
+
+/// bundled list of common passwords, one per line, lowercase
+const COMMON_PASSWORDS: &str = include_str!("common-passwords.txt");
+
+
+/// password policy rule a password failed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "rule")]
+pub enum PasswordRule {
+    MinLength { min: usize },
+    Lowercase,
+    Uppercase,
+    Digit,
+    Symbol,
+    Common,
+    ContainsEmail
+}
+
+
+#[derive(Debug, Clone)]
+pub struct Password {
+    policy: PasswordPolicy
+}
+
+impl Password {
+
+    pub fn new(policy: &PasswordPolicy) -> Self {
+        return Self {
+            policy: policy.clone()
+        };
+    }
+
+    /// validate a password against the policy. returns every rule the
+    /// password failed
+    pub fn validate(&self, pw: &str, email: &str) -> Result<(), Vec<PasswordRule>> {
+        let mut failed: Vec<PasswordRule> = Vec::new();
+
+        if pw.chars().count() < self.policy.min_length {
+            failed.push(PasswordRule::MinLength { min: self.policy.min_length });
+        }
+        if self.policy.require_lowercase && !pw.chars().any(|c| c.is_lowercase()) {
+            failed.push(PasswordRule::Lowercase);
+        }
+        if self.policy.require_uppercase && !pw.chars().any(|c| c.is_uppercase()) {
+            failed.push(PasswordRule::Uppercase);
+        }
+        if self.policy.require_digit && !pw.chars().any(|c| c.is_numeric()) {
+            failed.push(PasswordRule::Digit);
+        }
+        if self.policy.require_symbol && pw.chars().all(|c| c.is_alphanumeric()) {
+            failed.push(PasswordRule::Symbol);
+        }
+        if self.policy.deny_common && Self::is_common(pw) {
+            failed.push(PasswordRule::Common);
+        }
+        if Self::contains_email(pw, email) {
+            failed.push(PasswordRule::ContainsEmail);
+        }
+
+        if failed.is_empty() {
+            return Ok(());
+        }
+        return Err(failed);
+    }
+
+    /// common passwords with digits or symbols appended, e.g. "Password123!",
+    /// are treated as common as well
+    fn is_common(pw: &str) -> bool {
+        let pw = pw.to_lowercase();
+        let base = pw.trim_end_matches(|c: char| !c.is_alphabetic());
+        return COMMON_PASSWORDS.lines()
+            .map(|l| l.trim())
+            .filter(|l| !l.is_empty())
+            .any(|l| l == pw || l == base);
+    }
+
+    /// check if the password contains the email address or its local part
+    fn contains_email(pw: &str, email: &str) -> bool {
+        let pw = pw.to_lowercase();
+        let email = email.trim().to_lowercase();
+        if email.is_empty() {
+            return false;
+        }
+        if pw.contains(&email) {
+            return true;
+        }
+
+        // ignore very short local parts, e.g. "jo@example.com"
+        match email.split('@').next() {
+            Some(local) if local.chars().count() >= 3 => {
+                return pw.contains(local);
+            }
+            _ => {
+                return false;
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn password() -> Password {
+        return Password::new(&PasswordPolicy::default());
+    }
+
+    #[test]
+    fn test_valid() {
+        assert!(password().validate("correct Horse 7 battery", "testing@mailinator.com").is_ok());
+    }
+
+    #[test]
+    fn test_character_classes() {
+        match password().validate("a", "testing@mailinator.com") {
+            Ok(_) => {
+                assert!(false, "password should be invalid");
+            }
+            Err(failed) => {
+                assert_eq!(failed, vec![
+                    PasswordRule::MinLength { min: 10 },
+                    PasswordRule::Uppercase,
+                    PasswordRule::Digit
+                ]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_symbol() {
+        let password = Password::new(&PasswordPolicy {
+            require_symbol: true,
+            ..PasswordPolicy::default()
+        });
+        assert_eq!(
+            password.validate("Tr0ubadorHorse", "testing@mailinator.com"),
+            Err(vec![PasswordRule::Symbol])
+        );
+        assert!(password.validate("Tr0ubador&Horse", "testing@mailinator.com").is_ok());
+    }
+
+    #[test]
+    fn test_common() {
+        assert_eq!(
+            password().validate("Password123!", "testing@mailinator.com"),
+            Err(vec![PasswordRule::Common])
+        );
+    }
+
+    #[test]
+    fn test_contains_email() {
+        assert_eq!(
+            password().validate("Testing@Mailinator.com1", "testing@mailinator.com"),
+            Err(vec![PasswordRule::ContainsEmail])
+        );
+        assert_eq!(
+            password().validate("myTesting2024pw", "testing@mailinator.com"),
+            Err(vec![PasswordRule::ContainsEmail])
+        );
+    }
+}
@@ -0,0 +1,30 @@
+use serde::{Serialize, Deserialize};
+
+
+/// permission from the permission catalog. endpoints declare the permission
+/// name they require
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Permission {
+    name: String,
+    description: String
+}
+
+impl Permission {
+    pub fn new(
+        name: &str,
+        description: &str
+    ) -> Self {
+        return Self {
+            name: String::from(name),
+            description: String::from(description)
+        };
+    }
+
+    pub fn name(&self) -> String {
+        return self.name.clone();
+    }
+
+    pub fn description(&self) -> String {
+        return self.description.clone();
+    }
+}
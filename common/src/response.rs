@@ -0,0 +1,59 @@
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+use serde_json::{
+    Value,
+    json
+};
+
+use crate::error::Error;
+
+
+/// envelope of every api response. failed responses carry the error code
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ApiResponse {
+    success: bool,
+    message: String,
+    /// machine readable error code of failed responses
+    #[serde(skip_serializing_if = "Option::is_none")]
+    code: Option<String>,
+    data: Option<Value>
+}
+
+
+impl ApiResponse {
+
+    pub fn new(
+        success: bool,
+        message: &str,
+        data: Option<Value>
+    ) -> Self {
+        return Self {
+            success: success,
+            message: message.to_string(),
+            code: None,
+            data: data
+        };
+    }
+
+    /// failed response for an error. invalid fields are listed in data
+    pub fn error(e: &Error) -> Self {
+        let data = match e {
+            Error::InvalidFields(fields) => Some(json!({
+                "fields": fields
+            })),
+            _ => None
+        };
+        return Self {
+            success: false,
+            message: e.message(),
+            code: Some(e.code().as_str().to_string()),
+            data: data
+        };
+    }
+
+    /// replace the data of a response
+    pub fn with_data(mut self, data: Value) -> Self {
+        self.data = Some(data);
+        return self;
+    }
+}
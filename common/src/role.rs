@@ -1,41 +1,48 @@
-use log::{
-    info,
-    debug,
-    error
-};
 use serde::{Serialize, Deserialize};
 
 
-#[derive(Debug, Serialize, Deserialize)]
+/// named set of permissions within a tenant. built-in roles are seeded
+/// when a tenant is added and cannot be removed
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Role {
     id: uuid::Uuid,
+    tenant_id: uuid::Uuid,
     active: bool,
     name: String,
     slug: String,
-    description: String
+    description: String,
+    built_in: bool
 }
 
 impl Role {
     pub fn new(
         id: &uuid::Uuid,
+        tenant_id: &uuid::Uuid,
         active: &bool,
         name: &str,
         slug: &str,
-        description: &str
-    ) {
+        description: &str,
+        built_in: &bool
+    ) -> Self {
         return Self {
             id: id.clone(),
+            tenant_id: tenant_id.clone(),
             active: active.clone(),
             name: String::from(name),
             slug: String::from(slug),
-            description: String::from(description)
-        }
+            description: String::from(description),
+            built_in: built_in.clone()
+        };
     }
 
     pub fn id(&self) -> uuid::Uuid {
         return self.id.clone();
     }
 
+    pub fn tenant_id(&self) -> uuid::Uuid {
+        return self.tenant_id.clone();
+    }
+
     pub fn active(&self) -> bool {
         return self.active.clone();
     }
@@ -51,4 +58,8 @@ impl Role {
     pub fn description(&self) -> String {
         return self.description.clone();
     }
-}
\ No newline at end of file
+
+    pub fn built_in(&self) -> bool {
+        return self.built_in.clone();
+    }
+}
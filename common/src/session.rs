@@ -0,0 +1,54 @@
+use serde::{Serialize, Deserialize};
+
+use chrono::prelude::*;
+
+
+/// a signed in session, backed by a refresh token family
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Session {
+    id: uuid::Uuid,
+    tenant_id: uuid::Uuid,
+    created: DateTime<Utc>,
+    last_used: DateTime<Utc>,
+    expires: DateTime<Utc>
+}
+
+
+impl Session {
+
+    pub fn new(
+        id: &uuid::Uuid,
+        tenant_id: &uuid::Uuid,
+        created: &DateTime<Utc>,
+        last_used: &DateTime<Utc>,
+        expires: &DateTime<Utc>
+    ) -> Self {
+        return Self {
+            id: id.clone(),
+            tenant_id: tenant_id.clone(),
+            created: created.clone(),
+            last_used: last_used.clone(),
+            expires: expires.clone()
+        };
+    }
+
+    pub fn id(&self) -> uuid::Uuid {
+        return self.id.clone();
+    }
+
+    pub fn tenant_id(&self) -> uuid::Uuid {
+        return self.tenant_id.clone();
+    }
+
+    pub fn created(&self) -> DateTime<Utc> {
+        return self.created.clone();
+    }
+
+    pub fn last_used(&self) -> DateTime<Utc> {
+        return self.last_used.clone();
+    }
+
+    pub fn expires(&self) -> DateTime<Utc> {
+        return self.expires.clone();
+    }
+}
@@ -0,0 +1,312 @@
+use std::fs;
+use std::path::Path;
+
+use serde_json::{
+    json,
+    Map,
+    Value
+};
+
+
+/// prefix of environment variables overriding configuration values, e.g.
+/// IDEAS__JWT__SECRET or IDEAS__PROVIDERS__0__URL__0
+pub const ENV_PREFIX: &str = "IDEAS__";
+
+/// key of an object whose value is the path to a file holding a secret,
+/// e.g. "secret": { "file": "/run/secrets/jwt" } or IDEAS__JWT__SECRET__FILE
+pub const SECRET_FILE: &str = "file";
+
+/// keys that are not the camel case of their environment variable segment
+const IRREGULAR_KEYS: [&str; 1] = ["baseURL"];
+
+
+/// values used when neither the file nor the environment set them
+pub fn defaults() -> Value {
+    return json!({
+        "bindHost": "0.0.0.0",
+        "bindPort": 8081,
+        "providers": [],
+        "oidc": []
+    });
+}
+
+/// parse a configuration file, TOML when its extension is .toml and JSON
+/// otherwise
+pub fn file(path: &Path) -> Result<Value, String> {
+    let contents = match fs::read_to_string(path) {
+        Err(e) => {
+            return Err(format!("{}: unable to read configuration file: {}", path.display(), e));
+        }
+        Ok(contents) => contents
+    };
+
+    if path.extension().is_some_and(|e| e == "toml") {
+        match toml::from_str::<Value>(&contents) {
+            Err(e) => {
+                return Err(format!("{}: unable to parse configuration file: {}", path.display(), e));
+            }
+            Ok(value) => {
+                return Ok(value);
+            }
+        }
+    }
+    match serde_json::from_str::<Value>(&contents) {
+        Err(e) => {
+            return Err(format!("{}: unable to parse configuration file: {}", path.display(), e));
+        }
+        Ok(value) => {
+            return Ok(value);
+        }
+    }
+}
+
+/// merge a layer into the layers below it. objects are merged key by key,
+/// any other value replaces the one below
+pub fn merge(base: &mut Value, layer: Value) {
+    match (base, layer) {
+        (Value::Object(base), Value::Object(layer)) => {
+            for (key, value) in layer {
+                match base.get_mut(&key) {
+                    None => {
+                        base.insert(key, value);
+                    }
+                    Some(existing) => {
+                        merge(existing, value);
+                    }
+                }
+            }
+        }
+        (base, layer) => {
+            *base = layer;
+        }
+    }
+}
+
+/// apply the environment variables starting with ENV_PREFIX. segments match
+/// keys ignoring case and underscores, numeric segments index arrays
+pub fn environment(
+    base: &mut Value,
+    vars: impl Iterator<Item = (String, String)>
+) -> Vec<String> {
+    let mut overrides: Vec<(Vec<String>, String, String)> = vars
+        .filter_map(|(name, value)| {
+            let segments = name.strip_prefix(ENV_PREFIX)?
+                .split("__")
+                .map(String::from)
+                .collect::<Vec<String>>();
+            return Some((segments, name, value));
+        })
+        .collect();
+    // array entries are set in index order
+    overrides.sort_by_key(|(segments, _, _)| {
+        segments.iter()
+            .map(|s| (s.parse::<usize>().unwrap_or(usize::MAX), s.clone()))
+            .collect::<Vec<(usize, String)>>()
+    });
+
+    let mut problems = Vec::new();
+    for (segments, name, value) in overrides {
+        if segments.iter().any(|s| s.is_empty()) {
+            problems.push(format!("{}: empty key segment", name));
+            continue;
+        }
+        if let Err(problem) = set(base, &segments, &value, &name) {
+            problems.push(problem);
+        }
+    }
+    return problems;
+}
+
+fn set(
+    target: &mut Value,
+    segments: &[String],
+    raw: &str,
+    name: &str
+) -> Result<(), String> {
+    let (segment, rest) = segments.split_first().unwrap();
+
+    let entry = if let Ok(index) = segment.parse::<usize>() {
+        if !target.is_array() {
+            *target = Value::Array(Vec::new());
+        }
+        let items = target.as_array_mut().unwrap();
+        if index > items.len() {
+            return Err(format!("{}: index {} skips array entries, the array has {}", name, index, items.len()));
+        }
+        if index == items.len() {
+            items.push(Value::Null);
+        }
+        &mut items[index]
+    } else {
+        if !target.is_object() {
+            *target = Value::Object(Map::new());
+        }
+        let map = target.as_object_mut().unwrap();
+        let key = map.keys()
+            .map(|k| k.as_str())
+            .chain(IRREGULAR_KEYS)
+            .find(|k| normalize(k) == normalize(segment))
+            .map(String::from)
+            .unwrap_or_else(|| camel_case(segment));
+        map.entry(key).or_insert(Value::Null)
+    };
+
+    if rest.is_empty() {
+        *entry = scalar(entry, raw);
+        return Ok(());
+    }
+    return set(entry, rest, raw, name);
+}
+
+/// value of an environment variable, a string where the layers below have
+/// one and parsed as JSON elsewhere when it is valid JSON
+fn scalar(current: &Value, raw: &str) -> Value {
+    if current.is_string() {
+        return Value::String(String::from(raw));
+    }
+    match serde_json::from_str::<Value>(raw) {
+        Err(_) => {
+            return Value::String(String::from(raw));
+        }
+        Ok(value) => {
+            return value;
+        }
+    }
+}
+
+fn normalize(key: &str) -> String {
+    return key.chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .flat_map(|c| c.to_lowercase())
+        .collect();
+}
+
+/// key for a segment not present in the layers below, SIGN_IN becomes signIn
+fn camel_case(segment: &str) -> String {
+    let mut key = String::new();
+    for (i, word) in segment.split('_').filter(|w| !w.is_empty()).enumerate() {
+        let word = word.to_lowercase();
+        if i == 0 {
+            key.push_str(&word);
+        } else {
+            let mut chars = word.chars();
+            if let Some(first) = chars.next() {
+                key.extend(first.to_uppercase());
+                key.push_str(chars.as_str());
+            }
+        }
+    }
+    return key;
+}
+
+/// replace secret file references with the contents of the files, a
+/// trailing line break is removed
+pub fn secrets(
+    value: &mut Value,
+    path: &str,
+    problems: &mut Vec<String>
+) {
+    match value {
+        Value::Object(map) => {
+            if map.len() == 1 {
+                if let Some(Value::String(file)) = map.get(SECRET_FILE) {
+                    match fs::read_to_string(file) {
+                        Err(e) => {
+                            problems.push(format!("{}: unable to read secret file {}: {}", path, file, e));
+                        }
+                        Ok(contents) => {
+                            let secret = contents.strip_suffix('\n').unwrap_or(&contents);
+                            let secret = secret.strip_suffix('\r').unwrap_or(secret);
+                            *value = Value::String(String::from(secret));
+                        }
+                    }
+                    return;
+                }
+            }
+            for (key, value) in map.iter_mut() {
+                let path = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                secrets(value, &path, problems);
+            }
+        }
+        Value::Array(items) => {
+            for (i, value) in items.iter_mut().enumerate() {
+                secrets(value, &format!("{}[{}]", path, i), problems);
+            }
+        }
+        _ => {}
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(vars: &[(&str, &str)]) -> impl Iterator<Item = (String, String)> {
+        return vars.iter()
+            .map(|(k, v)| (String::from(*k), String::from(*v)))
+            .collect::<Vec<(String, String)>>()
+            .into_iter();
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut base = json!({ "a": { "b": 1, "c": [1, 2] }, "d": "x" });
+        merge(&mut base, json!({ "a": { "c": [3] }, "e": true }));
+        assert_eq!(base, json!({ "a": { "b": 1, "c": [3] }, "d": "x", "e": true }));
+    }
+
+    #[test]
+    fn test_environment() {
+        let mut base = json!({
+            "bindPort": 8081,
+            "signIn": { "maxAttempts": 5 },
+            "jwt": { "secret": "file" }
+        });
+        let problems = environment(&mut base, vars(&[
+            ("IDEAS__BIND_PORT", "9000"),
+            ("IDEAS__BASE_URL", "https://example.com"),
+            ("IDEAS__SIGN_IN__MAX_ATTEMPTS", "3"),
+            ("IDEAS__SIGN_IN__LOCKOUT_SECONDS", "60"),
+            ("IDEAS__JWT__SECRET", "1234"),
+            ("IDEAS__PROVIDERS__1__URL__0", "postgres://replica"),
+            ("IDEAS__PROVIDERS__0__NAME", "default"),
+            ("IDEAS__PROVIDERS__0__URL__0", "postgres://primary"),
+            ("IDEAS__OIDC__2__ID", "skipped"),
+            ("OTHER__BIND_PORT", "1")
+        ]));
+
+        assert_eq!(base["bindPort"], json!(9000));
+        // not present below
+        assert_eq!(base["baseURL"], json!("https://example.com"));
+        assert_eq!(base["signIn"], json!({ "maxAttempts": 3, "lockoutSeconds": 60 }));
+        // replaces a string, so stays a string
+        assert_eq!(base["jwt"]["secret"], json!("1234"));
+        assert_eq!(base["providers"], json!([
+            { "name": "default", "url": ["postgres://primary"] },
+            { "url": ["postgres://replica"] }
+        ]));
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].starts_with("IDEAS__OIDC__2__ID"));
+    }
+
+    #[test]
+    fn test_secrets() {
+        let dir = std::env::temp_dir().join(format!("ideas-secrets-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("jwt");
+        fs::write(&file, "s3cret\n").unwrap();
+
+        let mut value = json!({
+            "jwt": { "secret": { "file": file.to_str().unwrap() } },
+            "keys": [{ "secret": { "file": dir.join("missing").to_str().unwrap() } }]
+        });
+        let mut problems = Vec::new();
+        secrets(&mut value, "", &mut problems);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(value["jwt"]["secret"], json!("s3cret"));
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].starts_with("keys[0].secret: unable to read secret file"));
+    }
+}
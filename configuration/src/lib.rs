@@ -1,12 +1,16 @@
+mod layers;
+mod validation;
+
 use log::{
-    // info,
+    info,
     // debug,
     error
 };
 
 use std::{
     env,
-    fs
+    fmt,
+    path::Path
 };
 
 use serde::{
@@ -23,12 +27,82 @@ pub enum ProviderType {
 }
 
 
+/// connection pool sizing and timeouts of a provider
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PoolSettings {
+    #[serde(rename = "maxSize", default = "PoolSettings::default_max_size")]
+    pub max_size: usize,
+
+    /// milliseconds to wait for a free connection
+    #[serde(rename = "waitTimeoutMs", default)]
+    pub wait_timeout_ms: Option<u64>,
+
+    /// milliseconds to wait for a new connection to be established
+    #[serde(rename = "createTimeoutMs", default)]
+    pub create_timeout_ms: Option<u64>,
+
+    /// milliseconds to wait for a connection to be checked before reuse
+    #[serde(rename = "recycleTimeoutMs", default)]
+    pub recycle_timeout_ms: Option<u64>
+}
+
+impl PoolSettings {
+    fn default_max_size() -> usize {
+        return 4;
+    }
+}
+
+impl Default for PoolSettings {
+    fn default() -> Self {
+        return Self {
+            max_size: Self::default_max_size(),
+            wait_timeout_ms: None,
+            create_timeout_ms: None,
+            recycle_timeout_ms: None
+        };
+    }
+}
+
+
+/// tls for a provider's connections. the server certificate is verified
+/// against the ca bundle and the host name, as with sslmode verify-full
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProviderTls {
+    /// path to the PEM encoded ca bundle
+    #[serde(rename = "caFile")]
+    pub ca_file: String,
+
+    /// path to a PEM encoded client certificate chain
+    #[serde(rename = "certFile", default)]
+    pub cert_file: Option<String>,
+
+    /// path to the PEM encoded private key of the client certificate
+    #[serde(rename = "keyFile", default)]
+    pub key_file: Option<String>
+}
+
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Provider {
+    /// selects the provider, modules use the one named "default"
     pub name: String,
     #[serde(rename = "type")]
     pub provider_type: ProviderType,
-    pub url: Vec<String>
+
+    /// url of the primary, only the first entry is used
+    pub url: Vec<String>,
+
+    /// urls of read replicas, read-only queries fall back to the primary
+    /// when no replica is reachable
+    #[serde(default)]
+    pub replicas: Vec<String>,
+
+    #[serde(default)]
+    pub pool: PoolSettings,
+
+    /// connections are unencrypted when not set
+    #[serde(default)]
+    pub tls: Option<ProviderTls>
 }
 
 
@@ -49,9 +123,263 @@ pub struct Mail {
 
 
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub enum KeyAlgorithm {
+    HS256,
+    RS256,
+    EdDSA
+}
+
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JWTKey {
+    pub kid: String,
+    pub algorithm: KeyAlgorithm,
+
+    /// shared secret, HS256 only
+    #[serde(default)]
+    pub secret: Option<String>,
+
+    /// path to a PEM encoded private key. keys without one are only used for verification
+    #[serde(rename = "privateKey", default)]
+    pub private_key: Option<String>,
+
+    /// path to a PEM encoded public key
+    #[serde(rename = "publicKey", default)]
+    pub public_key: Option<String>
+}
+
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct JWT {
-    pub secret: String
+    /// shared secret used when no keys are configured
+    #[serde(default)]
+    pub secret: Option<String>,
+
+    /// kid of the key used to sign new tokens
+    #[serde(rename = "signingKey", default)]
+    pub signing_key: Option<String>,
+
+    /// keys accepted when verifying tokens
+    #[serde(default)]
+    pub keys: Vec<JWTKey>,
+
+    #[serde(default = "JWT::default_issuer")]
+    pub issuer: String,
+
+    #[serde(default = "JWT::default_audience")]
+    pub audience: String,
+
+    /// number of seconds an access token remains valid
+    #[serde(default = "JWT::default_expiry")]
+    pub expiry: i64,
+
+    /// number of seconds a refresh token remains valid, extended on every refresh
+    #[serde(rename = "refreshExpiry", default = "JWT::default_refresh_expiry")]
+    pub refresh_expiry: i64
+}
+
+impl JWT {
+    fn default_issuer() -> String {
+        return String::from("ideas");
+    }
+
+    fn default_audience() -> String {
+        return String::from("ideas");
+    }
+
+    fn default_expiry() -> i64 {
+        return 3600;
+    }
+
+    fn default_refresh_expiry() -> i64 {
+        return 30 * 24 * 3600;
+    }
+}
+
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PasswordPolicy {
+    #[serde(rename = "minLength", default = "PasswordPolicy::default_min_length")]
+    pub min_length: usize,
+
+    #[serde(rename = "requireLowercase", default = "PasswordPolicy::default_true")]
+    pub require_lowercase: bool,
+
+    #[serde(rename = "requireUppercase", default = "PasswordPolicy::default_true")]
+    pub require_uppercase: bool,
+
+    #[serde(rename = "requireDigit", default = "PasswordPolicy::default_true")]
+    pub require_digit: bool,
+
+    #[serde(rename = "requireSymbol", default)]
+    pub require_symbol: bool,
+
+    /// reject passwords found in the bundled list of common passwords
+    #[serde(rename = "denyCommon", default = "PasswordPolicy::default_true")]
+    pub deny_common: bool
+}
+
+impl PasswordPolicy {
+    fn default_min_length() -> usize {
+        return 10;
+    }
+
+    fn default_true() -> bool {
+        return true;
+    }
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        return Self {
+            min_length: Self::default_min_length(),
+            require_lowercase: true,
+            require_uppercase: true,
+            require_digit: true,
+            require_symbol: false,
+            deny_common: true
+        };
+    }
+}
+
+
+/// sign in throttling and account lockout thresholds
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SignInPolicy {
+    /// number of failed attempts for an email before the account is locked
+    #[serde(rename = "maxAttempts", default = "SignInPolicy::default_max_attempts")]
+    pub max_attempts: i64,
+
+    /// number of failed attempts from a client ip before further attempts are refused
+    #[serde(rename = "ipMaxAttempts", default = "SignInPolicy::default_ip_max_attempts")]
+    pub ip_max_attempts: i64,
+
+    /// number of seconds failed attempts are counted and an account remains locked
+    #[serde(rename = "lockoutSeconds", default = "SignInPolicy::default_lockout_seconds")]
+    pub lockout_seconds: i64,
+
+    /// delay applied after the first failed attempt, doubled on every further failure
+    #[serde(rename = "delayBaseMs", default = "SignInPolicy::default_delay_base_ms")]
+    pub delay_base_ms: u64,
+
+    #[serde(rename = "delayMaxMs", default = "SignInPolicy::default_delay_max_ms")]
+    pub delay_max_ms: u64
+}
+
+impl SignInPolicy {
+    fn default_max_attempts() -> i64 {
+        return 5;
+    }
+
+    fn default_ip_max_attempts() -> i64 {
+        return 50;
+    }
+
+    fn default_lockout_seconds() -> i64 {
+        return 900;
+    }
+
+    fn default_delay_base_ms() -> u64 {
+        return 250;
+    }
+
+    fn default_delay_max_ms() -> u64 {
+        return 4000;
+    }
+}
+
+impl Default for SignInPolicy {
+    fn default() -> Self {
+        return Self {
+            max_attempts: Self::default_max_attempts(),
+            ip_max_attempts: Self::default_ip_max_attempts(),
+            lockout_seconds: Self::default_lockout_seconds(),
+            delay_base_ms: Self::default_delay_base_ms(),
+            delay_max_ms: Self::default_delay_max_ms()
+        };
+    }
+}
+
+
+/// external openid connect identity provider a tenant's members sign in with
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OidcProvider {
+    /// identifies the provider in sign in requests
+    pub id: String,
+
+    #[serde(rename = "tenantId")]
+    pub tenant_id: uuid::Uuid,
+
+    /// issuer url, provider metadata is discovered from {issuer}/.well-known/openid-configuration
+    pub issuer: String,
+
+    #[serde(rename = "clientId")]
+    pub client_id: String,
+
+    #[serde(rename = "clientSecret", default)]
+    pub client_secret: Option<String>,
+
+    #[serde(default = "OidcProvider::default_scopes")]
+    pub scopes: Vec<String>,
+
+    /// url the provider redirects to after sign in. defaults to {baseURL}/oidc/callback
+    #[serde(rename = "redirectUri", default)]
+    pub redirect_uri: Option<String>
+}
+
+impl OidcProvider {
+    fn default_scopes() -> Vec<String> {
+        return vec![
+            String::from("openid"),
+            String::from("email"),
+            String::from("profile")
+        ];
+    }
+}
+
+
+/// certificate of the http listener
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ListenerTls {
+    /// path to the PEM encoded certificate chain
+    #[serde(rename = "certFile")]
+    pub cert_file: String,
+
+    /// path to the PEM encoded private key
+    #[serde(rename = "keyFile")]
+    pub key_file: String
+}
+
+
+/// listener serving the prometheus metrics, apart from the api so that
+/// it is not exposed to the clients of the api
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MetricsListener {
+    #[serde(rename = "bindHost", default = "MetricsListener::default_bind_host")]
+    pub bind_host: String,
+
+    #[serde(rename = "bindPort", default = "MetricsListener::default_bind_port")]
+    pub bind_port: i32
+}
+
+impl MetricsListener {
+    fn default_bind_host() -> String {
+        return String::from("127.0.0.1");
+    }
+
+    fn default_bind_port() -> i32 {
+        return 9091;
+    }
+}
+
+impl Default for MetricsListener {
+    fn default() -> Self {
+        return Self {
+            bind_host: Self::default_bind_host(),
+            bind_port: Self::default_bind_port()
+        };
+    }
 }
 
 
@@ -67,38 +395,126 @@ pub struct ApplicationConfiguration {
     #[serde(rename = "baseURL")]
     pub base_url: String,
 
+    /// serve https instead of http when set
+    #[serde(default)]
+    pub tls: Option<ListenerTls>,
+
+    /// addresses or cidr ranges of reverse proxies trusted to name the
+    /// client in X-Forwarded-For. the header is ignored from anyone else
+    #[serde(rename = "trustedProxies", default)]
+    pub trusted_proxies: Vec<String>,
+
+    /// listener serving GET /metrics, loopback only unless configured
+    #[serde(default)]
+    pub metrics: MetricsListener,
+
     pub providers: Vec<Provider>,
     pub mailer: Mail,
 
-    pub jwt: JWT
+    pub jwt: JWT,
+
+    #[serde(rename = "passwordPolicy", default)]
+    pub password_policy: PasswordPolicy,
+
+    #[serde(rename = "signIn", default)]
+    pub sign_in: SignInPolicy,
+
+    #[serde(default)]
+    pub oidc: Vec<OidcProvider>
+}
+
+
+/// problems found while loading the configuration
+#[derive(Debug, Clone)]
+pub struct ConfigurationError {
+    problems: Vec<String>
+}
+
+impl ConfigurationError {
+    pub fn problems(&self) -> &Vec<String> {
+        return &self.problems;
+    }
+}
+
+impl fmt::Display for ConfigurationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid configuration")?;
+        for problem in &self.problems {
+            write!(f, "\n  - {}", problem)?;
+        }
+        return Ok(());
+    }
 }
 
 
 impl ApplicationConfiguration {
+
+    /// load the configuration, logging every problem found
     pub fn get() -> Option<ApplicationConfiguration> {
-        if let Ok(cfg) = env::var("CFG") {
-            match fs::read_to_string(cfg) {
-                Err(e) => {
-                    error!("unable to read contents of file: {:?}", e);
-                    return None;
+        match Self::load() {
+            Err(e) => {
+                for problem in e.problems() {
+                    error!("configuration: {}", problem);
                 }
-                Ok(contents) => {
-                    let result: Result<ApplicationConfiguration, serde_json::Error> = serde_json::from_str(&contents);
-                    match result {
-                        Err(e) => {
-                            error!("unable to parse contents of file: {:?}", e);
-                            return None;
-                        }
-                        Ok(config) => {
-                            return Some(config);
-                        }
-                    }
+                return None;
+            }
+            Ok(config) => {
+                return Some(config);
+            }
+        }
+    }
+
+    /// load the configuration from the file named by the CFG environment
+    /// variable and the IDEAS__ environment variables
+    pub fn load() -> Result<ApplicationConfiguration, ConfigurationError> {
+        let file = env::var("CFG").ok();
+        if file.is_none() {
+            info!("CFG is not set, configuring from the environment only");
+        }
+        return Self::from_sources(file.as_ref().map(Path::new), env::vars());
+    }
+
+    /// layer the defaults, the configuration file (JSON, or TOML when its
+    /// extension is .toml) and the environment variables, then read secret
+    /// files and validate the result
+    pub fn from_sources(
+        file: Option<&Path>,
+        vars: impl Iterator<Item = (String, String)>
+    ) -> Result<ApplicationConfiguration, ConfigurationError> {
+        let mut problems = Vec::new();
+
+        let mut value = layers::defaults();
+        if let Some(file) = file {
+            match layers::file(file) {
+                Err(problem) => {
+                    problems.push(problem);
+                }
+                Ok(layer) => {
+                    layers::merge(&mut value, layer);
                 }
             }
-        } else {
-            error!("missing environment variable 'CFG'");
-            return None;
         }
+        problems.extend(layers::environment(&mut value, vars));
+        layers::secrets(&mut value, "", &mut problems);
+        if !problems.is_empty() {
+            return Err(ConfigurationError { problems: problems });
+        }
+
+        let config: ApplicationConfiguration = match serde_path_to_error::deserialize(value) {
+            Err(e) => {
+                let path = e.path().to_string();
+                return Err(ConfigurationError {
+                    problems: vec![format!("{}: {}", path, e.into_inner())]
+                });
+            }
+            Ok(config) => config
+        };
+
+        let problems = config.validate();
+        if !problems.is_empty() {
+            return Err(ConfigurationError { problems: problems });
+        }
+        return Ok(config);
     }
 }
 
@@ -124,4 +540,103 @@ mod tests {
             }
         }
     }
+
+    fn vars(vars: &[(&str, &str)]) -> impl Iterator<Item = (String, String)> {
+        return vars.iter()
+            .map(|(k, v)| (String::from(*k), String::from(*v)))
+            .collect::<Vec<(String, String)>>()
+            .into_iter();
+    }
+
+    #[test]
+    fn test_layers() {
+        let dir = std::env::temp_dir().join(format!("ideas-configuration-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("config.toml");
+        std::fs::write(&file, r#"
+            baseURL = "http://localhost:4200"
+
+            [[providers]]
+            type = "postgres"
+            name = "default"
+            url = ["postgres://app@localhost/app"]
+
+            [mailer]
+            host = "smtp.example.com"
+            user = "app"
+            password = "replace_me"
+            defaults = { from = "app@example.com" }
+
+            [jwt]
+            secret = "replace_me"
+        "#).unwrap();
+        let secret = dir.join("jwt");
+        std::fs::write(&secret, "from-file\n").unwrap();
+
+        let result = ApplicationConfiguration::from_sources(Some(&file), vars(&[
+            ("IDEAS__BIND_PORT", "9090"),
+            ("IDEAS__JWT__SECRET__FILE", secret.to_str().unwrap()),
+            ("IDEAS__PROVIDERS__0__POOL__MAX_SIZE", "8")
+        ]));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let config = result.unwrap();
+        assert_eq!(config.bind_host, "0.0.0.0");
+        assert_eq!(config.bind_port, 9090);
+        assert_eq!(config.metrics.bind_host, "127.0.0.1");
+        assert_eq!(config.metrics.bind_port, 9091);
+        assert_eq!(config.jwt.secret, Some(String::from("from-file")));
+        assert_eq!(config.jwt.expiry, 3600);
+        assert_eq!(config.providers[0].pool.max_size, 8);
+    }
+
+    /// the example configuration shipped with the repository starts a
+    /// development server as is
+    #[test]
+    fn test_shipped_configuration() {
+        let file = Path::new(env!("CARGO_MANIFEST_DIR")).join("../config.json");
+        let result = ApplicationConfiguration::from_sources(Some(&file), vars(&[]));
+        assert!(result.is_ok(), "{:?}", result.err().map(|e| e.to_string()));
+    }
+
+    #[test]
+    fn test_problems() {
+        // jwt is missing
+        let error = ApplicationConfiguration::from_sources(None, vars(&[
+            ("IDEAS__BASE_URL", "http://localhost"),
+            ("IDEAS__MAILER__HOST", "smtp.example.com"),
+            ("IDEAS__MAILER__USER", "app"),
+            ("IDEAS__MAILER__PASSWORD", "replace_me"),
+            ("IDEAS__MAILER__DEFAULTS__FROM", "app@example.com")
+        ])).unwrap_err();
+        assert_eq!(error.problems(), &vec![String::from(".: missing field `jwt`")]);
+
+        // every validation problem is listed
+        let error = ApplicationConfiguration::from_sources(None, vars(&[
+            ("IDEAS__BIND_PORT", "0"),
+            ("IDEAS__METRICS__BIND_PORT", "70000"),
+            ("IDEAS__BASE_URL", "localhost"),
+            ("IDEAS__MAILER__HOST", ""),
+            ("IDEAS__MAILER__USER", "app"),
+            ("IDEAS__MAILER__PASSWORD", "replace_me"),
+            ("IDEAS__MAILER__DEFAULTS__FROM", "app@example.com"),
+            ("IDEAS__JWT__SIGNING_KEY", "missing"),
+            ("IDEAS__JWT__SECRET", "replace_me"),
+            ("IDEAS__TRUSTED_PROXIES__0", "10.0.0.0/8"),
+            ("IDEAS__TRUSTED_PROXIES__1", "proxy.local")
+        ])).unwrap_err();
+        assert_eq!(error.problems(), &vec![
+            String::from("bindPort: 0 is not between 1 and 65535"),
+            String::from("baseURL: localhost is not an http or https url"),
+            String::from("metrics.bindPort: 70000 is not between 1 and 65535"),
+            String::from("trustedProxies[1]: proxy.local is not an ip address or cidr range"),
+            String::from("providers: at least one database provider is required"),
+            String::from("mailer.host: must not be empty"),
+            String::from("jwt.signingKey: no key with kid missing")
+        ]);
+        assert!(error.to_string().starts_with("invalid configuration\n  - bindPort"));
+
+        let error = ApplicationConfiguration::from_sources(Some(Path::new("/nonexistent/config.json")), vars(&[])).unwrap_err();
+        assert!(error.problems()[0].starts_with("/nonexistent/config.json: unable to read configuration file"));
+    }
 }
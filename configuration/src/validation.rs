@@ -0,0 +1,183 @@
+use std::collections::HashSet;
+use std::net::IpAddr;
+use std::path::Path;
+
+use ipnet::IpNet;
+
+use crate::{
+    ApplicationConfiguration,
+    KeyAlgorithm
+};
+
+
+impl ApplicationConfiguration {
+
+    /// every problem of the configuration that would prevent the server
+    /// from starting or running correctly
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        if self.bind_host.trim().is_empty() {
+            problems.push(String::from("bindHost: must not be empty"));
+        }
+        if !(1..=65535).contains(&self.bind_port) {
+            problems.push(format!("bindPort: {} is not between 1 and 65535", self.bind_port));
+        }
+        if !is_url(&self.base_url) {
+            problems.push(format!("baseURL: {} is not an http or https url", self.base_url));
+        }
+        if let Some(tls) = &self.tls {
+            file_exists(&mut problems, "tls.certFile", &tls.cert_file);
+            file_exists(&mut problems, "tls.keyFile", &tls.key_file);
+        }
+        if self.metrics.bind_host.trim().is_empty() {
+            problems.push(String::from("metrics.bindHost: must not be empty"));
+        }
+        if !(1..=65535).contains(&self.metrics.bind_port) {
+            problems.push(format!("metrics.bindPort: {} is not between 1 and 65535", self.metrics.bind_port));
+        }
+        if self.metrics.bind_port == self.bind_port {
+            problems.push(String::from("metrics.bindPort: must differ from bindPort"));
+        }
+        for (i, proxy) in self.trusted_proxies.iter().enumerate() {
+            if proxy.parse::<IpNet>().is_err() && proxy.parse::<IpAddr>().is_err() {
+                problems.push(format!("trustedProxies[{}]: {} is not an ip address or cidr range", i, proxy));
+            }
+        }
+
+        self.validate_providers(&mut problems);
+
+        if self.mailer.host.trim().is_empty() {
+            problems.push(String::from("mailer.host: must not be empty"));
+        }
+
+        self.validate_jwt(&mut problems);
+
+        if self.password_policy.min_length == 0 {
+            problems.push(String::from("passwordPolicy.minLength: must be greater than 0"));
+        }
+        if self.sign_in.max_attempts <= 0 {
+            problems.push(String::from("signIn.maxAttempts: must be greater than 0"));
+        }
+        if self.sign_in.ip_max_attempts <= 0 {
+            problems.push(String::from("signIn.ipMaxAttempts: must be greater than 0"));
+        }
+        if self.sign_in.lockout_seconds <= 0 {
+            problems.push(String::from("signIn.lockoutSeconds: must be greater than 0"));
+        }
+        if self.sign_in.delay_base_ms > self.sign_in.delay_max_ms {
+            problems.push(String::from("signIn.delayBaseMs: must not exceed signIn.delayMaxMs"));
+        }
+
+        let mut ids = HashSet::new();
+        for (i, p) in self.oidc.iter().enumerate() {
+            if !ids.insert(&p.id) {
+                problems.push(format!("oidc[{}].id: {} is used by another provider", i, p.id));
+            }
+            if !is_url(&p.issuer) {
+                problems.push(format!("oidc[{}].issuer: {} is not an http or https url", i, p.issuer));
+            }
+            if p.client_id.trim().is_empty() {
+                problems.push(format!("oidc[{}].clientId: must not be empty", i));
+            }
+        }
+
+        return problems;
+    }
+
+    fn validate_providers(&self, problems: &mut Vec<String>) {
+        if self.providers.is_empty() {
+            problems.push(String::from("providers: at least one database provider is required"));
+        }
+
+        let mut names = HashSet::new();
+        for (i, p) in self.providers.iter().enumerate() {
+            if !names.insert(&p.name) {
+                problems.push(format!("providers[{}].name: {} is used by another provider", i, p.name));
+            }
+            if p.url.first().is_none_or(|url| url.trim().is_empty()) {
+                problems.push(format!("providers[{}].url: the url of the primary is required", i));
+            }
+            if p.pool.max_size == 0 {
+                problems.push(format!("providers[{}].pool.maxSize: must be greater than 0", i));
+            }
+            if let Some(tls) = &p.tls {
+                file_exists(problems, &format!("providers[{}].tls.caFile", i), &tls.ca_file);
+                match (&tls.cert_file, &tls.key_file) {
+                    (None, None) => {}
+                    (Some(cert_file), Some(key_file)) => {
+                        file_exists(problems, &format!("providers[{}].tls.certFile", i), &cert_file);
+                        file_exists(problems, &format!("providers[{}].tls.keyFile", i), &key_file);
+                    }
+                    _ => {
+                        problems.push(format!("providers[{}].tls: certFile and keyFile must be set together", i));
+                    }
+                }
+            }
+        }
+    }
+
+    fn validate_jwt(&self, problems: &mut Vec<String>) {
+        let jwt = &self.jwt;
+        if jwt.keys.is_empty() && jwt.secret.as_ref().is_none_or(|s| s.is_empty()) {
+            problems.push(String::from("jwt: a secret or keys are required"));
+        }
+        if jwt.expiry <= 0 {
+            problems.push(String::from("jwt.expiry: must be greater than 0"));
+        }
+        if jwt.refresh_expiry <= 0 {
+            problems.push(String::from("jwt.refreshExpiry: must be greater than 0"));
+        }
+
+        let mut kids = HashSet::new();
+        for (i, k) in jwt.keys.iter().enumerate() {
+            if k.kid.trim().is_empty() {
+                problems.push(format!("jwt.keys[{}].kid: must not be empty", i));
+            } else if !kids.insert(&k.kid) {
+                problems.push(format!("jwt.keys[{}].kid: {} is used by another key", i, k.kid));
+            }
+            match k.algorithm {
+                KeyAlgorithm::HS256 => {
+                    if k.secret.as_ref().is_none_or(|s| s.is_empty()) {
+                        problems.push(format!("jwt.keys[{}].secret: required for HS256", i));
+                    }
+                }
+                KeyAlgorithm::RS256 | KeyAlgorithm::EdDSA => {
+                    if k.private_key.is_none() && k.public_key.is_none() {
+                        problems.push(format!("jwt.keys[{}]: a privateKey or publicKey is required", i));
+                    }
+                    if let Some(path) = &k.private_key {
+                        file_exists(problems, &format!("jwt.keys[{}].privateKey", i), &path);
+                    }
+                    if let Some(path) = &k.public_key {
+                        file_exists(problems, &format!("jwt.keys[{}].publicKey", i), &path);
+                    }
+                }
+            }
+        }
+
+        if let Some(kid) = &jwt.signing_key {
+            match jwt.keys.iter().find(|k| &k.kid == kid) {
+                None => {
+                    problems.push(format!("jwt.signingKey: no key with kid {}", kid));
+                }
+                Some(k) => {
+                    if !matches!(k.algorithm, KeyAlgorithm::HS256) && k.private_key.is_none() {
+                        problems.push(format!("jwt.signingKey: key {} has no privateKey to sign with", kid));
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn is_url(url: &str) -> bool {
+    return (url.starts_with("http://") || url.starts_with("https://"))
+        && url.split("://").nth(1).is_some_and(|rest| !rest.is_empty());
+}
+
+fn file_exists(problems: &mut Vec<String>, key: &str, path: &str) {
+    if !Path::new(path).is_file() {
+        problems.push(format!("{}: file {} does not exist", key, path));
+    }
+}
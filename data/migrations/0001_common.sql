@@ -0,0 +1,64 @@
+-- extensions, domains and reference data shared by the modules
+
+create extension if not exists pgcrypto;
+
+
+create domain email_address as text
+    check (value ~ '^[^@\s]+@[^@\s]+$');
+
+-- lowercase letters and digits separated by single hyphens
+create domain slug_text as text
+    check (value ~ '^[a-z0-9]+(-[a-z0-9]+)*$' and length(value) <= 100);
+
+
+create schema common;
+
+create table common.currencies (
+    id integer primary key,
+    name text not null,
+    unit text not null,
+    symbol text
+);
+
+create table common.countries (
+    id integer primary key,
+    name text not null,
+    iso_3166_1_alpha_2 text not null unique,
+    iso_3166_1_alpha_3 text not null unique,
+    iso_4217_currency_numeric_code integer references common.currencies (id)
+);
+
+
+create function common.currencies_fetch()
+returns table (
+    id integer,
+    name text,
+    unit text,
+    symbol text
+)
+language sql stable
+as $$
+    select c.id, c.name, c.unit, c.symbol
+    from common.currencies c
+    order by c.name;
+$$;
+
+create function common.countries_fetch()
+returns table (
+    id integer,
+    name text,
+    iso_3166_1_alpha_2 text,
+    iso_3166_1_alpha_3 text,
+    iso_4217_currency_numeric_code integer
+)
+language sql stable
+as $$
+    select
+        c.id,
+        c.name,
+        c.iso_3166_1_alpha_2,
+        c.iso_3166_1_alpha_3,
+        c.iso_4217_currency_numeric_code
+    from common.countries c
+    order by c.name;
+$$;
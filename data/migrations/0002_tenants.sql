@@ -0,0 +1,277 @@
+-- tenants and their organizations
+
+create schema tenants;
+
+create table tenants.tenants (
+    id uuid primary key,
+    active boolean not null default true,
+    name text not null,
+    slug slug_text not null unique,
+    description text not null default '',
+    mfa_required boolean not null default false,
+    -- tenant new users are added to when they complete their registration
+    is_default boolean not null default false,
+    created timestamptz not null default now()
+);
+
+create unique index tenants_default_idx on tenants.tenants (is_default) where is_default;
+
+
+create function tenants.tenant_by_id(
+    p_tenant_id uuid
+)
+returns table (
+    id uuid,
+    active boolean,
+    name text,
+    slug text,
+    description text
+)
+language sql stable
+as $$
+    select t.id, t.active, t.name, t.slug, t.description
+    from tenants.tenants t
+    where t.id = p_tenant_id;
+$$;
+
+create function tenants.tenant_by_name(
+    p_name text
+)
+returns table (
+    id uuid,
+    active boolean,
+    name text,
+    slug text,
+    description text
+)
+language sql stable
+as $$
+    select t.id, t.active, t.name, t.slug, t.description
+    from tenants.tenants t
+    where t.name = p_name;
+$$;
+
+create function tenants.tenant_by_slug(
+    p_slug slug_text
+)
+returns table (
+    id uuid,
+    active boolean,
+    name text,
+    slug text,
+    description text
+)
+language sql stable
+as $$
+    select t.id, t.active, t.name, t.slug, t.description
+    from tenants.tenants t
+    where t.slug = p_slug;
+$$;
+
+create function tenants.tenants_fetch()
+returns table (
+    id uuid,
+    active boolean,
+    name text,
+    slug text,
+    description text
+)
+language sql stable
+as $$
+    select t.id, t.active, t.name, t.slug, t.description
+    from tenants.tenants t
+    order by t.name;
+$$;
+
+create function tenants.tenant_default_fetch()
+returns table (
+    id uuid,
+    active boolean,
+    name text,
+    slug text,
+    description text
+)
+language sql stable
+as $$
+    select t.id, t.active, t.name, t.slug, t.description
+    from tenants.tenants t
+    where t.is_default;
+$$;
+
+create procedure tenants.tenant_add(
+    p_tenant_id uuid,
+    p_name text,
+    p_slug slug_text,
+    p_description text
+)
+language sql
+as $$
+    insert into tenants.tenants (id, name, slug, description)
+    values (p_tenant_id, p_name, p_slug, p_description);
+$$;
+
+create procedure tenants.tenant_update(
+    p_tenant_id uuid,
+    p_name text,
+    p_slug slug_text,
+    p_description text
+)
+language plpgsql
+as $$
+begin
+    update tenants.tenants set
+        name = p_name,
+        slug = p_slug,
+        description = p_description
+    where id = p_tenant_id;
+    if not found then
+        raise exception 'tenant not found' using errcode = 'no_data_found';
+    end if;
+end
+$$;
+
+-- null arguments keep the current value
+create procedure tenants.tenant_patch(
+    p_tenant_id uuid,
+    p_name text,
+    p_slug slug_text,
+    p_description text
+)
+language plpgsql
+as $$
+begin
+    update tenants.tenants set
+        name = coalesce(p_name, name),
+        slug = coalesce(p_slug, slug),
+        description = coalesce(p_description, description)
+    where id = p_tenant_id;
+    if not found then
+        raise exception 'tenant not found' using errcode = 'no_data_found';
+    end if;
+end
+$$;
+
+create procedure tenants.set_active(
+    p_tenant_id uuid,
+    p_active boolean
+)
+language plpgsql
+as $$
+begin
+    update tenants.tenants set
+        active = p_active
+    where id = p_tenant_id;
+    if not found then
+        raise exception 'tenant not found' using errcode = 'no_data_found';
+    end if;
+end
+$$;
+
+create procedure tenants.set_mfa_required(
+    p_tenant_id uuid,
+    p_required boolean
+)
+language plpgsql
+as $$
+begin
+    update tenants.tenants set
+        mfa_required = p_required
+    where id = p_tenant_id;
+    if not found then
+        raise exception 'tenant not found' using errcode = 'no_data_found';
+    end if;
+end
+$$;
+
+
+create table tenants.organizations (
+    id uuid primary key,
+    tenant_id uuid not null references tenants.tenants (id),
+    active boolean not null default true,
+    name text not null,
+    description text not null default '',
+    created timestamptz not null default now(),
+    unique (tenant_id, name)
+);
+
+
+create procedure tenants.organization_add(
+    p_tenant_id uuid,
+    p_organization_id uuid,
+    p_name text,
+    p_description text
+)
+language sql
+as $$
+    insert into tenants.organizations (id, tenant_id, name, description)
+    values (p_organization_id, p_tenant_id, p_name, p_description);
+$$;
+
+create procedure tenants.organization_set_active(
+    p_organization_id uuid,
+    p_active boolean
+)
+language plpgsql
+as $$
+begin
+    update tenants.organizations set
+        active = p_active
+    where id = p_organization_id;
+    if not found then
+        raise exception 'organization not found' using errcode = 'no_data_found';
+    end if;
+end
+$$;
+
+create function tenants.organizations_fetch(
+    p_tenant_id uuid
+)
+returns table (
+    id uuid,
+    tenant_id uuid,
+    active boolean,
+    name text,
+    description text
+)
+language sql stable
+as $$
+    select o.id, o.tenant_id, o.active, o.name, o.description
+    from tenants.organizations o
+    where o.tenant_id = p_tenant_id
+    order by o.name;
+$$;
+
+create function tenants.organization_get(
+    p_tenant_id uuid,
+    p_organization_id uuid
+)
+returns table (
+    id uuid,
+    tenant_id uuid,
+    active boolean,
+    name text,
+    description text
+)
+language sql stable
+as $$
+    select o.id, o.tenant_id, o.active, o.name, o.description
+    from tenants.organizations o
+    where o.tenant_id = p_tenant_id
+        and o.id = p_organization_id;
+$$;
+
+create procedure tenants.organization_remove(
+    p_tenant_id uuid,
+    p_organization_id uuid
+)
+language plpgsql
+as $$
+begin
+    delete from tenants.organizations
+    where tenant_id = p_tenant_id
+        and id = p_organization_id;
+    if not found then
+        raise exception 'organization not found' using errcode = 'no_data_found';
+    end if;
+end
+$$;
@@ -0,0 +1,1222 @@
+-- users, tenant memberships, authentication and roles
+
+create schema iam;
+
+
+create table iam.users (
+    id uuid primary key,
+    active boolean not null default true,
+    email email_address not null,
+    -- bcrypt hash, null for users who only sign in through an identity provider
+    pw text,
+    given_name text not null default '',
+    middle_name text not null default '',
+    family_name text not null default '',
+    created timestamptz not null default now()
+);
+
+create unique index users_email_idx on iam.users (lower(email));
+
+
+create function iam.user_by_id(
+    p_user_id uuid
+)
+returns table (
+    id uuid,
+    active boolean,
+    email text,
+    given_name text,
+    middle_name text,
+    family_name text
+)
+language sql stable
+as $$
+    select u.id, u.active, u.email, u.given_name, u.middle_name, u.family_name
+    from iam.users u
+    where u.id = p_user_id;
+$$;
+
+create function iam.user_by_email(
+    p_email email_address
+)
+returns table (
+    id uuid,
+    active boolean,
+    email text,
+    given_name text,
+    middle_name text,
+    family_name text
+)
+language sql stable
+as $$
+    select u.id, u.active, u.email, u.given_name, u.middle_name, u.family_name
+    from iam.users u
+    where lower(u.email) = lower(p_email);
+$$;
+
+create procedure iam.user_active(
+    p_user_id uuid,
+    p_active boolean
+)
+language plpgsql
+as $$
+begin
+    update iam.users set
+        active = p_active
+    where id = p_user_id;
+    if not found then
+        raise exception 'user not found' using errcode = 'no_data_found';
+    end if;
+end
+$$;
+
+create procedure iam.user_set_pw(
+    p_user_id uuid,
+    p_pw text
+)
+language plpgsql
+as $$
+begin
+    update iam.users set
+        pw = crypt(p_pw, gen_salt('bf'))
+    where id = p_user_id;
+    if not found then
+        raise exception 'user not found' using errcode = 'no_data_found';
+    end if;
+end
+$$;
+
+create function iam.user_authenticate(
+    p_email email_address,
+    p_pw text
+)
+returns boolean
+language sql stable
+as $$
+    select exists (
+        select 1
+        from iam.users u
+        where lower(u.email) = lower(p_email)
+            and u.active
+            and u.pw is not null
+            and u.pw = crypt(p_pw, u.pw)
+    );
+$$;
+
+
+-- tenant memberships
+create table iam.user_tenants (
+    user_id uuid not null references iam.users (id),
+    tenant_id uuid not null references tenants.tenants (id),
+    active boolean not null default true,
+    is_default boolean not null default false,
+    created timestamptz not null default now(),
+    primary key (user_id, tenant_id)
+);
+
+create unique index user_tenants_default_idx on iam.user_tenants (user_id) where is_default;
+
+
+-- the first tenant a user is added to becomes the user's default tenant
+create procedure iam.user_tenant_add(
+    p_user_id uuid,
+    p_tenant_id uuid
+)
+language sql
+as $$
+    insert into iam.user_tenants (user_id, tenant_id, is_default)
+    values (
+        p_user_id,
+        p_tenant_id,
+        not exists (
+            select 1
+            from iam.user_tenants ut
+            where ut.user_id = p_user_id
+                and ut.is_default
+        )
+    )
+    on conflict (user_id, tenant_id) do nothing;
+$$;
+
+create procedure iam.user_tenant_set_active(
+    p_user_id uuid,
+    p_tenant_id uuid,
+    p_active boolean
+)
+language plpgsql
+as $$
+begin
+    update iam.user_tenants set
+        active = p_active
+    where user_id = p_user_id
+        and tenant_id = p_tenant_id;
+    if not found then
+        raise exception 'user is not a member of the tenant' using errcode = 'no_data_found';
+    end if;
+end
+$$;
+
+create procedure iam.user_tenant_set_default(
+    p_user_id uuid,
+    p_tenant_id uuid
+)
+language plpgsql
+as $$
+begin
+    if not exists (
+        select 1
+        from iam.user_tenants ut
+        where ut.user_id = p_user_id
+            and ut.tenant_id = p_tenant_id
+    ) then
+        raise exception 'user is not a member of the tenant' using errcode = 'no_data_found';
+    end if;
+
+    update iam.user_tenants set
+        is_default = false
+    where user_id = p_user_id
+        and is_default;
+
+    update iam.user_tenants set
+        is_default = true
+    where user_id = p_user_id
+        and tenant_id = p_tenant_id;
+end
+$$;
+
+create function iam.user_tenants_fetch(
+    p_user_id uuid
+)
+returns table (
+    tenant_id uuid,
+    active boolean,
+    name text,
+    slug text
+)
+language sql stable
+as $$
+    select t.id, t.active, t.name, t.slug
+    from iam.user_tenants ut
+        join tenants.tenants t on t.id = ut.tenant_id
+    where ut.user_id = p_user_id
+        and ut.active
+    order by t.name;
+$$;
+
+create function iam.user_tenant_fetch_default(
+    p_user_id uuid
+)
+returns table (
+    tenant_id uuid,
+    active boolean,
+    name text,
+    slug text
+)
+language sql stable
+as $$
+    select t.id, t.active, t.name, t.slug
+    from iam.user_tenants ut
+        join tenants.tenants t on t.id = ut.tenant_id
+    where ut.user_id = p_user_id
+        and ut.is_default;
+$$;
+
+create function tenants.tenant_users_fetch(
+    p_tenant_id uuid
+)
+returns table (
+    id uuid,
+    email text,
+    active boolean
+)
+language sql stable
+as $$
+    select u.id, u.email, ut.active
+    from iam.user_tenants ut
+        join iam.users u on u.id = ut.user_id
+    where ut.tenant_id = p_tenant_id
+    order by u.email;
+$$;
+
+
+create table iam.registrations (
+    id uuid primary key,
+    email email_address not null,
+    token text not null unique,
+    created timestamptz not null default now(),
+    completed timestamptz
+);
+
+
+create procedure iam.register(
+    p_id uuid,
+    p_email email_address,
+    p_token text
+)
+language sql
+as $$
+    insert into iam.registrations (id, email, token)
+    values (p_id, p_email, p_token);
+$$;
+
+create function iam.register_get_info(
+    p_token text
+)
+returns table (
+    token text,
+    email text,
+    created timestamptz
+)
+language sql stable
+as $$
+    select r.token, r.email, r.created
+    from iam.registrations r
+    where r.token = p_token
+        and r.completed is null;
+$$;
+
+
+create table iam.permissions (
+    name text primary key,
+    description text not null default ''
+);
+
+create table iam.roles (
+    id uuid primary key,
+    tenant_id uuid not null references tenants.tenants (id),
+    active boolean not null default true,
+    name text not null,
+    slug slug_text not null,
+    description text not null default '',
+    built_in boolean not null default false,
+    created timestamptz not null default now(),
+    unique (tenant_id, slug)
+);
+
+create table iam.role_permissions (
+    role_id uuid not null references iam.roles (id) on delete cascade,
+    permission text not null references iam.permissions (name),
+    primary key (role_id, permission)
+);
+
+create table iam.user_roles (
+    tenant_id uuid not null references tenants.tenants (id),
+    user_id uuid not null references iam.users (id),
+    role_id uuid not null references iam.roles (id) on delete cascade,
+    created timestamptz not null default now(),
+    primary key (user_id, role_id)
+);
+
+
+-- create the user, add it to the default tenant with the member role and
+-- close the registration
+create procedure iam.register_complete(
+    p_token text,
+    p_pw text
+)
+language plpgsql
+as $$
+declare
+    v_id uuid;
+    v_email email_address;
+    v_tenant_id uuid;
+begin
+    update iam.registrations set
+        completed = now()
+    where token = p_token
+        and completed is null
+    returning id, email into v_id, v_email;
+    if not found then
+        raise exception 'registration not found' using errcode = 'no_data_found';
+    end if;
+
+    insert into iam.users (id, email, pw)
+    values (v_id, v_email, crypt(p_pw, gen_salt('bf')));
+
+    select t.id into v_tenant_id
+    from tenants.tenants t
+    where t.is_default;
+    if v_tenant_id is not null then
+        call iam.user_tenant_add(v_id, v_tenant_id);
+
+        insert into iam.user_roles (tenant_id, user_id, role_id)
+        select r.tenant_id, v_id, r.id
+        from iam.roles r
+        where r.tenant_id = v_tenant_id
+            and r.slug = 'member';
+    end if;
+end
+$$;
+
+
+-- refresh tokens are grouped in families, one per session
+create table iam.refresh_tokens (
+    digest text primary key,
+    family_id uuid not null,
+    user_id uuid not null references iam.users (id),
+    tenant_id uuid not null,
+    created timestamptz not null default now(),
+    expires timestamptz not null,
+    used timestamptz,
+    revoked timestamptz
+);
+
+create index refresh_tokens_family_idx on iam.refresh_tokens (family_id);
+create index refresh_tokens_user_idx on iam.refresh_tokens (user_id);
+
+
+create procedure iam.refresh_token_add(
+    p_family_id uuid,
+    p_user_id uuid,
+    p_tenant_id uuid,
+    p_digest text,
+    p_expires timestamptz
+)
+language sql
+as $$
+    insert into iam.refresh_tokens (digest, family_id, user_id, tenant_id, expires)
+    values (p_digest, p_family_id, p_user_id, p_tenant_id, p_expires);
+$$;
+
+-- returns the state of the token prior to the call
+create function iam.refresh_token_use(
+    p_digest text
+)
+returns table (
+    family_id uuid,
+    user_id uuid,
+    tenant_id uuid,
+    used boolean,
+    revoked boolean,
+    expires timestamptz
+)
+language sql
+as $$
+    with token as (
+        select
+            r.family_id,
+            r.user_id,
+            r.tenant_id,
+            r.used is not null as used,
+            r.revoked is not null as revoked,
+            r.expires
+        from iam.refresh_tokens r
+        where r.digest = p_digest
+    ), mark as (
+        update iam.refresh_tokens set
+            used = now()
+        where digest = p_digest
+            and used is null
+    )
+    select * from token;
+$$;
+
+create procedure iam.refresh_token_family_revoke(
+    p_family_id uuid
+)
+language sql
+as $$
+    update iam.refresh_tokens set
+        revoked = now()
+    where family_id = p_family_id
+        and revoked is null;
+$$;
+
+create function iam.user_sessions_fetch(
+    p_user_id uuid
+)
+returns table (
+    family_id uuid,
+    tenant_id uuid,
+    created timestamptz,
+    last_used timestamptz,
+    expires timestamptz
+)
+language sql stable
+as $$
+    -- the latest token of a family carries the state of the session
+    select s.family_id, s.tenant_id, s.created, s.last_used, s.expires
+    from (
+        select distinct on (r.family_id)
+            r.family_id,
+            r.tenant_id,
+            min(r.created) over (partition by r.family_id) as created,
+            r.created as last_used,
+            r.expires
+        from iam.refresh_tokens r
+        where r.user_id = p_user_id
+            and r.revoked is null
+        order by r.family_id, r.created desc
+    ) s
+    where s.expires > now()
+    order by s.last_used desc;
+$$;
+
+create procedure iam.user_session_revoke(
+    p_user_id uuid,
+    p_family_id uuid
+)
+language plpgsql
+as $$
+begin
+    update iam.refresh_tokens set
+        revoked = now()
+    where user_id = p_user_id
+        and family_id = p_family_id
+        and revoked is null;
+    if not found then
+        raise exception 'session not found' using errcode = 'no_data_found';
+    end if;
+end
+$$;
+
+-- revoke every session of the user except for the given one, pass the nil
+-- uuid to revoke all sessions
+create procedure iam.user_sessions_revoke(
+    p_user_id uuid,
+    p_family_id uuid
+)
+language sql
+as $$
+    update iam.refresh_tokens set
+        revoked = now()
+    where user_id = p_user_id
+        and family_id <> p_family_id
+        and revoked is null;
+$$;
+
+create function iam.session_active(
+    p_family_id uuid
+)
+returns boolean
+language sql stable
+as $$
+    select exists (
+        select 1
+        from iam.refresh_tokens r
+        where r.family_id = p_family_id
+            and r.revoked is null
+            and r.expires > now()
+    );
+$$;
+
+
+create table iam.password_resets (
+    digest text primary key,
+    user_id uuid not null references iam.users (id),
+    created timestamptz not null default now(),
+    expires timestamptz not null,
+    used timestamptz
+);
+
+
+-- returns false if there is no user with the email address
+create function iam.password_reset_add(
+    p_email email_address,
+    p_digest text,
+    p_expires timestamptz
+)
+returns boolean
+language plpgsql
+as $$
+begin
+    insert into iam.password_resets (digest, user_id, expires)
+    select p_digest, u.id, p_expires
+    from iam.users u
+    where lower(u.email) = lower(p_email);
+    return found;
+end
+$$;
+
+create function iam.password_reset_get_info(
+    p_digest text
+)
+returns table (
+    email text
+)
+language sql stable
+as $$
+    select u.email
+    from iam.password_resets r
+        join iam.users u on u.id = r.user_id
+    where r.digest = p_digest
+        and r.used is null
+        and r.expires > now();
+$$;
+
+create function iam.password_reset_complete(
+    p_digest text,
+    p_pw text
+)
+returns table (
+    user_id uuid
+)
+language plpgsql
+as $$
+declare
+    v_user_id uuid;
+begin
+    update iam.password_resets r set
+        used = now()
+    where r.digest = p_digest
+        and r.used is null
+        and r.expires > now()
+    returning r.user_id into v_user_id;
+    if not found then
+        return;
+    end if;
+
+    call iam.user_set_pw(v_user_id, p_pw);
+    return query select v_user_id;
+end
+$$;
+
+
+create table iam.sign_in_attempts (
+    id bigint generated always as identity primary key,
+    email email_address not null,
+    ip text,
+    success boolean not null,
+    created timestamptz not null default now()
+);
+
+create index sign_in_attempts_email_idx on iam.sign_in_attempts (lower(email), created);
+create index sign_in_attempts_ip_idx on iam.sign_in_attempts (ip, created);
+
+
+create procedure iam.sign_in_attempt_add(
+    p_email email_address,
+    p_ip text,
+    p_success boolean
+)
+language sql
+as $$
+    insert into iam.sign_in_attempts (email, ip, success)
+    values (p_email, p_ip, p_success);
+$$;
+
+-- failures before the last successful sign in or unlock of the email are
+-- not counted against the email
+create function iam.sign_in_failures(
+    p_email email_address,
+    p_ip text,
+    p_since timestamptz
+)
+returns table (
+    email_failures bigint,
+    ip_failures bigint,
+    last_failure timestamptz
+)
+language sql stable
+as $$
+    with reset as (
+        select coalesce(max(a.created), '-infinity') as created
+        from iam.sign_in_attempts a
+        where lower(a.email) = lower(p_email)
+            and a.success
+    ), email as (
+        select count(*) as failures, max(a.created) as last_failure
+        from iam.sign_in_attempts a, reset
+        where lower(a.email) = lower(p_email)
+            and not a.success
+            and a.created >= p_since
+            and a.created > reset.created
+    ), ip as (
+        select count(*) as failures
+        from iam.sign_in_attempts a
+        where a.ip = p_ip
+            and not a.success
+            and a.created >= p_since
+    )
+    select email.failures, ip.failures, email.last_failure
+    from email, ip;
+$$;
+
+
+create table iam.account_unlocks (
+    digest text primary key,
+    user_id uuid not null references iam.users (id),
+    created timestamptz not null default now(),
+    expires timestamptz not null,
+    used timestamptz
+);
+
+
+-- returns false if there is no user with the email address
+create function iam.account_unlock_add(
+    p_email email_address,
+    p_digest text,
+    p_expires timestamptz
+)
+returns boolean
+language plpgsql
+as $$
+begin
+    insert into iam.account_unlocks (digest, user_id, expires)
+    select p_digest, u.id, p_expires
+    from iam.users u
+    where lower(u.email) = lower(p_email);
+    return found;
+end
+$$;
+
+-- a successful attempt is recorded so earlier failures no longer count
+create function iam.account_unlock(
+    p_digest text
+)
+returns boolean
+language plpgsql
+as $$
+declare
+    v_user_id uuid;
+begin
+    update iam.account_unlocks a set
+        used = now()
+    where a.digest = p_digest
+        and a.used is null
+        and a.expires > now()
+    returning a.user_id into v_user_id;
+    if not found then
+        return false;
+    end if;
+
+    insert into iam.sign_in_attempts (email, success)
+    select u.email, true
+    from iam.users u
+    where u.id = v_user_id;
+    return true;
+end
+$$;
+
+
+create table iam.user_mfa (
+    user_id uuid primary key references iam.users (id),
+    -- base32 encoded totp secret
+    secret text not null,
+    confirmed boolean not null default false,
+    -- time step of the last verified code
+    last_step bigint,
+    created timestamptz not null default now()
+);
+
+create table iam.user_mfa_recovery_codes (
+    user_id uuid not null references iam.users (id),
+    digest text not null,
+    used timestamptz,
+    primary key (user_id, digest)
+);
+
+
+create function iam.user_mfa_fetch(
+    p_user_id uuid
+)
+returns table (
+    secret text,
+    confirmed boolean
+)
+language sql stable
+as $$
+    select m.secret, m.confirmed
+    from iam.user_mfa m
+    where m.user_id = p_user_id;
+$$;
+
+create function iam.user_mfa_required(
+    p_user_id uuid
+)
+returns boolean
+language sql stable
+as $$
+    select exists (
+        select 1
+        from iam.user_tenants ut
+            join tenants.tenants t on t.id = ut.tenant_id
+        where ut.user_id = p_user_id
+            and ut.active
+            and t.mfa_required
+    );
+$$;
+
+create procedure iam.user_mfa_enroll(
+    p_user_id uuid,
+    p_secret text
+)
+language sql
+as $$
+    insert into iam.user_mfa (user_id, secret)
+    values (p_user_id, p_secret)
+    on conflict (user_id) do update set
+        secret = excluded.secret,
+        last_step = null,
+        created = now()
+    where not iam.user_mfa.confirmed;
+$$;
+
+create procedure iam.user_mfa_confirm(
+    p_user_id uuid,
+    p_recovery_codes text[]
+)
+language plpgsql
+as $$
+begin
+    update iam.user_mfa set
+        confirmed = true
+    where user_id = p_user_id;
+    if not found then
+        raise exception 'user has not enrolled' using errcode = 'no_data_found';
+    end if;
+
+    delete from iam.user_mfa_recovery_codes
+    where user_id = p_user_id;
+
+    insert into iam.user_mfa_recovery_codes (user_id, digest)
+    select p_user_id, c
+    from unnest(p_recovery_codes) c;
+end
+$$;
+
+-- returns false if a code for the same or a later time step has been used
+create function iam.user_mfa_step_use(
+    p_user_id uuid,
+    p_step bigint
+)
+returns boolean
+language plpgsql
+as $$
+begin
+    update iam.user_mfa set
+        last_step = p_step
+    where user_id = p_user_id
+        and (last_step is null or last_step < p_step);
+    return found;
+end
+$$;
+
+create function iam.user_mfa_recovery_use(
+    p_user_id uuid,
+    p_digest text
+)
+returns boolean
+language plpgsql
+as $$
+begin
+    update iam.user_mfa_recovery_codes set
+        used = now()
+    where user_id = p_user_id
+        and digest = p_digest
+        and used is null;
+    return found;
+end
+$$;
+
+create procedure iam.user_mfa_disable(
+    p_user_id uuid
+)
+language plpgsql
+as $$
+begin
+    delete from iam.user_mfa_recovery_codes
+    where user_id = p_user_id;
+
+    delete from iam.user_mfa
+    where user_id = p_user_id;
+end
+$$;
+
+
+-- pending openid connect authorization requests keyed by the digest of
+-- the state
+create table iam.oidc_requests (
+    state text primary key,
+    provider_id text not null,
+    code_verifier text not null,
+    nonce text not null,
+    expires timestamptz not null,
+    created timestamptz not null default now()
+);
+
+create table iam.user_identities (
+    issuer text not null,
+    subject text not null,
+    user_id uuid not null references iam.users (id),
+    created timestamptz not null default now(),
+    primary key (issuer, subject)
+);
+
+
+create procedure iam.oidc_request_add(
+    p_state text,
+    p_provider_id text,
+    p_code_verifier text,
+    p_nonce text,
+    p_expires timestamptz
+)
+language sql
+as $$
+    insert into iam.oidc_requests (state, provider_id, code_verifier, nonce, expires)
+    values (p_state, p_provider_id, p_code_verifier, p_nonce, p_expires);
+$$;
+
+create function iam.oidc_request_use(
+    p_state text
+)
+returns table (
+    provider_id text,
+    code_verifier text,
+    nonce text,
+    expires timestamptz
+)
+language sql
+as $$
+    delete from iam.oidc_requests r
+    where r.state = p_state
+    returning r.provider_id, r.code_verifier, r.nonce, r.expires;
+$$;
+
+create function iam.user_identity_fetch(
+    p_issuer text,
+    p_subject text
+)
+returns table (
+    user_id uuid
+)
+language sql stable
+as $$
+    select i.user_id
+    from iam.user_identities i
+    where i.issuer = p_issuer
+        and i.subject = p_subject;
+$$;
+
+-- link the identity to the user with the email address, creating the user
+-- if there is none, and add the user to the tenant of the identity provider
+create function iam.user_identity_provision(
+    p_id uuid,
+    p_email email_address,
+    p_given_name text,
+    p_family_name text,
+    p_issuer text,
+    p_subject text,
+    p_tenant_id uuid
+)
+returns table (
+    user_id uuid
+)
+language plpgsql
+as $$
+declare
+    v_user_id uuid;
+begin
+    select u.id into v_user_id
+    from iam.users u
+    where lower(u.email) = lower(p_email);
+
+    if v_user_id is null then
+        v_user_id := p_id;
+        insert into iam.users (id, email, given_name, family_name)
+        values (v_user_id, p_email, p_given_name, p_family_name);
+    end if;
+
+    insert into iam.user_identities (issuer, subject, user_id)
+    values (p_issuer, p_subject, v_user_id)
+    on conflict (issuer, subject) do nothing;
+
+    if exists (select 1 from tenants.tenants t where t.id = p_tenant_id) then
+        call iam.user_tenant_add(v_user_id, p_tenant_id);
+    end if;
+
+    return query select v_user_id;
+end
+$$;
+
+
+create function iam.permissions_fetch()
+returns table (
+    name text,
+    description text
+)
+language sql stable
+as $$
+    select p.name, p.description
+    from iam.permissions p
+    order by p.name;
+$$;
+
+create procedure iam.role_add(
+    p_role_id uuid,
+    p_tenant_id uuid,
+    p_name text,
+    p_slug text,
+    p_description text,
+    p_built_in boolean
+)
+language sql
+as $$
+    insert into iam.roles (id, tenant_id, name, slug, description, built_in)
+    values (p_role_id, p_tenant_id, p_name, p_slug::slug_text, p_description, p_built_in);
+$$;
+
+create function iam.roles_fetch(
+    p_tenant_id uuid
+)
+returns table (
+    id uuid,
+    tenant_id uuid,
+    active boolean,
+    name text,
+    slug text,
+    description text,
+    built_in boolean
+)
+language sql stable
+as $$
+    select r.id, r.tenant_id, r.active, r.name, r.slug, r.description, r.built_in
+    from iam.roles r
+    where r.tenant_id = p_tenant_id
+    order by r.name;
+$$;
+
+create function iam.role_get(
+    p_tenant_id uuid,
+    p_role_id uuid
+)
+returns table (
+    id uuid,
+    tenant_id uuid,
+    active boolean,
+    name text,
+    slug text,
+    description text,
+    built_in boolean
+)
+language sql stable
+as $$
+    select r.id, r.tenant_id, r.active, r.name, r.slug, r.description, r.built_in
+    from iam.roles r
+    where r.tenant_id = p_tenant_id
+        and r.id = p_role_id;
+$$;
+
+create procedure iam.role_update(
+    p_tenant_id uuid,
+    p_role_id uuid,
+    p_name text,
+    p_description text
+)
+language plpgsql
+as $$
+begin
+    update iam.roles set
+        name = p_name,
+        description = p_description
+    where tenant_id = p_tenant_id
+        and id = p_role_id;
+    if not found then
+        raise exception 'role not found' using errcode = 'no_data_found';
+    end if;
+end
+$$;
+
+create procedure iam.role_set_active(
+    p_tenant_id uuid,
+    p_role_id uuid,
+    p_active boolean
+)
+language plpgsql
+as $$
+begin
+    update iam.roles set
+        active = p_active
+    where tenant_id = p_tenant_id
+        and id = p_role_id;
+    if not found then
+        raise exception 'role not found' using errcode = 'no_data_found';
+    end if;
+end
+$$;
+
+-- permissions and assignments of the role are removed with it
+create procedure iam.role_remove(
+    p_tenant_id uuid,
+    p_role_id uuid
+)
+language plpgsql
+as $$
+begin
+    delete from iam.roles
+    where tenant_id = p_tenant_id
+        and id = p_role_id;
+    if not found then
+        raise exception 'role not found' using errcode = 'no_data_found';
+    end if;
+end
+$$;
+
+create function iam.role_permissions_fetch(
+    p_tenant_id uuid,
+    p_role_id uuid
+)
+returns table (
+    name text
+)
+language sql stable
+as $$
+    select rp.permission
+    from iam.role_permissions rp
+        join iam.roles r on r.id = rp.role_id
+    where r.tenant_id = p_tenant_id
+        and r.id = p_role_id
+    order by rp.permission;
+$$;
+
+create procedure iam.role_permissions_set(
+    p_tenant_id uuid,
+    p_role_id uuid,
+    p_permissions text[]
+)
+language plpgsql
+as $$
+begin
+    if not exists (
+        select 1
+        from iam.roles r
+        where r.tenant_id = p_tenant_id
+            and r.id = p_role_id
+    ) then
+        raise exception 'role not found' using errcode = 'no_data_found';
+    end if;
+
+    delete from iam.role_permissions
+    where role_id = p_role_id;
+
+    insert into iam.role_permissions (role_id, permission)
+    select distinct p_role_id, p
+    from unnest(p_permissions) p;
+end
+$$;
+
+-- the role is assigned to a member of the tenant. a tenant without members
+-- accepts the first user it assigns a role to as a member, which is how
+-- the user who added the tenant becomes its owner
+create procedure iam.user_role_add(
+    p_tenant_id uuid,
+    p_user_id uuid,
+    p_role_id uuid
+)
+language plpgsql
+as $$
+begin
+    if not exists (
+        select 1
+        from iam.roles r
+        where r.tenant_id = p_tenant_id
+            and r.id = p_role_id
+    ) then
+        raise exception 'role not found' using errcode = 'no_data_found';
+    end if;
+
+    if not exists (
+        select 1
+        from iam.user_tenants ut
+        where ut.tenant_id = p_tenant_id
+    ) then
+        call iam.user_tenant_add(p_user_id, p_tenant_id);
+    elsif not exists (
+        select 1
+        from iam.user_tenants ut
+        where ut.tenant_id = p_tenant_id
+            and ut.user_id = p_user_id
+    ) then
+        raise exception 'user is not a member of the tenant' using errcode = 'no_data_found';
+    end if;
+
+    insert into iam.user_roles (tenant_id, user_id, role_id)
+    values (p_tenant_id, p_user_id, p_role_id)
+    on conflict (user_id, role_id) do nothing;
+end
+$$;
+
+create procedure iam.user_role_remove(
+    p_tenant_id uuid,
+    p_user_id uuid,
+    p_role_id uuid
+)
+language sql
+as $$
+    delete from iam.user_roles
+    where tenant_id = p_tenant_id
+        and user_id = p_user_id
+        and role_id = p_role_id;
+$$;
+
+create function iam.user_roles_fetch(
+    p_tenant_id uuid,
+    p_user_id uuid
+)
+returns table (
+    id uuid,
+    tenant_id uuid,
+    active boolean,
+    name text,
+    slug text,
+    description text,
+    built_in boolean
+)
+language sql stable
+as $$
+    select r.id, r.tenant_id, r.active, r.name, r.slug, r.description, r.built_in
+    from iam.user_roles ur
+        join iam.roles r on r.id = ur.role_id
+    where ur.tenant_id = p_tenant_id
+        and ur.user_id = p_user_id
+    order by r.name;
+$$;
+
+-- permissions granted through the active roles of an active membership
+create function iam.user_permissions_fetch(
+    p_user_id uuid,
+    p_tenant_id uuid
+)
+returns table (
+    name text
+)
+language sql stable
+as $$
+    select distinct rp.permission
+    from iam.user_tenants ut
+        join iam.user_roles ur on ur.user_id = ut.user_id and ur.tenant_id = ut.tenant_id
+        join iam.roles r on r.id = ur.role_id
+        join iam.role_permissions rp on rp.role_id = r.id
+    where ut.user_id = p_user_id
+        and ut.tenant_id = p_tenant_id
+        and ut.active
+        and r.active
+    order by rp.permission;
+$$;
+
+
+insert into iam.permissions (name, description) values
+    ('tenants.add', 'add tenants'),
+    ('tenants.read', 'view the tenant'),
+    ('tenants.update', 'update the tenant'),
+    ('tenants.set_active', 'enable or disable the tenant'),
+    ('tenants.set_mfa', 'require two-factor authentication for members of the tenant'),
+    ('tenants.members.read', 'view members of the tenant'),
+    ('tenants.members.set_active', 'enable or disable members of the tenant'),
+    ('organizations.add', 'add organizations'),
+    ('organizations.read', 'view organizations'),
+    ('organizations.remove', 'remove organizations'),
+    ('api_keys.manage', 'add, view and revoke api keys'),
+    ('roles.add', 'add roles'),
+    ('roles.read', 'view roles and permissions'),
+    ('roles.update', 'update roles and their permissions'),
+    ('roles.remove', 'remove roles'),
+    ('roles.assign', 'assign roles to members of the tenant'),
+    ('accounting.accounts.add', 'add accounts'),
+    ('hr.employees.add', 'add employees'),
+    ('hr.employees.read', 'view employees'),
+    ('crms.people.add', 'add contacts'),
+    ('clients.add', 'add clients'),
+    ('clients.read', 'view clients'),
+    ('clients.set_active', 'enable or disable clients'),
+    ('clients.members.read', 'view members of clients');
@@ -0,0 +1,106 @@
+-- tenant api keys
+
+create table tenants.api_keys (
+    id uuid primary key,
+    tenant_id uuid not null references tenants.tenants (id),
+    name text not null,
+    -- leading characters of the key, shown to identify it
+    prefix text not null,
+    digest text not null unique,
+    -- null grants every permission of the tenant
+    permissions text[],
+    created timestamptz not null default now(),
+    expires timestamptz,
+    last_used timestamptz,
+    revoked boolean not null default false,
+    created_by uuid not null references iam.users (id)
+);
+
+create index api_keys_tenant_idx on tenants.api_keys (tenant_id);
+
+
+create procedure tenants.api_key_add(
+    p_api_key_id uuid,
+    p_tenant_id uuid,
+    p_name text,
+    p_prefix text,
+    p_digest text,
+    p_permissions text[],
+    p_expires timestamptz,
+    p_created_by uuid
+)
+language sql
+as $$
+    insert into tenants.api_keys (id, tenant_id, name, prefix, digest, permissions, expires, created_by)
+    values (p_api_key_id, p_tenant_id, p_name, p_prefix, p_digest, p_permissions, p_expires, p_created_by);
+$$;
+
+create function tenants.api_keys_fetch(
+    p_tenant_id uuid
+)
+returns table (
+    id uuid,
+    tenant_id uuid,
+    name text,
+    prefix text,
+    permissions text[],
+    created timestamptz,
+    expires timestamptz,
+    last_used timestamptz,
+    revoked boolean
+)
+language sql stable
+as $$
+    select k.id, k.tenant_id, k.name, k.prefix, k.permissions, k.created, k.expires, k.last_used, k.revoked
+    from tenants.api_keys k
+    where k.tenant_id = p_tenant_id
+    order by k.created desc;
+$$;
+
+create procedure tenants.api_key_revoke(
+    p_tenant_id uuid,
+    p_api_key_id uuid
+)
+language plpgsql
+as $$
+begin
+    update tenants.api_keys set
+        revoked = true
+    where tenant_id = p_tenant_id
+        and id = p_api_key_id;
+    if not found then
+        raise exception 'api key not found' using errcode = 'no_data_found';
+    end if;
+end
+$$;
+
+-- only uses of keys that are neither revoked nor expired are recorded
+create function tenants.api_key_use(
+    p_digest text
+)
+returns table (
+    id uuid,
+    tenant_id uuid,
+    name text,
+    prefix text,
+    permissions text[],
+    created timestamptz,
+    expires timestamptz,
+    last_used timestamptz,
+    revoked boolean
+)
+language sql
+as $$
+    with k as (
+        select k.id, k.tenant_id, k.name, k.prefix, k.permissions, k.created, k.expires, k.last_used, k.revoked
+        from tenants.api_keys k
+        where k.digest = p_digest
+    ), mark as (
+        update tenants.api_keys set
+            last_used = now()
+        where digest = p_digest
+            and not revoked
+            and (expires is null or expires > now())
+    )
+    select * from k;
+$$;
@@ -0,0 +1,156 @@
+-- people and employees
+
+create schema people;
+
+create table people.people (
+    id uuid primary key,
+    tenant_id uuid not null references tenants.tenants (id),
+    active boolean not null default true,
+    given_name text not null,
+    middle_name text not null default '',
+    family_name text not null,
+    prefix text not null default '',
+    suffix text not null default '',
+    gender_id smallint not null,
+    ethnicity_id smallint not null,
+    marital_state_id smallint not null,
+    created timestamptz not null default now()
+);
+
+create index people_tenant_idx on people.people (tenant_id);
+
+
+create procedure people.people_add(
+    p_tenant_id uuid,
+    p_people_id uuid,
+    p_given_name text,
+    p_middle_name text,
+    p_family_name text,
+    p_prefix text,
+    p_suffix text,
+    p_gender_id smallint,
+    p_ethnicity_id smallint,
+    p_marital_state_id smallint
+)
+language sql
+as $$
+    insert into people.people (
+        id,
+        tenant_id,
+        given_name,
+        middle_name,
+        family_name,
+        prefix,
+        suffix,
+        gender_id,
+        ethnicity_id,
+        marital_state_id
+    ) values (
+        p_people_id,
+        p_tenant_id,
+        p_given_name,
+        p_middle_name,
+        p_family_name,
+        p_prefix,
+        p_suffix,
+        p_gender_id,
+        p_ethnicity_id,
+        p_marital_state_id
+    );
+$$;
+
+create function people.people_get_by_id(
+    p_people_id uuid
+)
+returns table (
+    id uuid,
+    tenant_id uuid,
+    active boolean,
+    given_name text,
+    middle_name text,
+    family_name text,
+    prefix text,
+    suffix text,
+    gender_id smallint,
+    ethnicity_id smallint,
+    marital_state_id smallint
+)
+language sql stable
+as $$
+    select
+        p.id,
+        p.tenant_id,
+        p.active,
+        p.given_name,
+        p.middle_name,
+        p.family_name,
+        p.prefix,
+        p.suffix,
+        p.gender_id,
+        p.ethnicity_id,
+        p.marital_state_id
+    from people.people p
+    where p.id = p_people_id;
+$$;
+
+
+create schema hr;
+
+create table hr.employees (
+    id uuid primary key,
+    tenant_id uuid not null references tenants.tenants (id),
+    people_id uuid not null unique references people.people (id),
+    active boolean not null default true,
+    created timestamptz not null default now()
+);
+
+create index employees_tenant_idx on hr.employees (tenant_id);
+
+
+create procedure hr.employee_add(
+    p_tenant_id uuid,
+    p_employee_id uuid,
+    p_people_id uuid
+)
+language sql
+as $$
+    insert into hr.employees (id, tenant_id, people_id)
+    values (p_employee_id, p_tenant_id, p_people_id);
+$$;
+
+-- ordered so that pages of the result are stable
+create function hr.employee_fetch(
+    p_tenant_id uuid
+)
+returns table (
+    id uuid,
+    tenant_id uuid,
+    people_id uuid,
+    given_name text,
+    middle_name text,
+    family_name text,
+    prefix text,
+    suffix text,
+    gender_id smallint,
+    ethnicity_id smallint,
+    marital_status_id smallint
+)
+language sql stable
+as $$
+    select
+        e.id,
+        e.tenant_id,
+        e.people_id,
+        p.given_name,
+        p.middle_name,
+        p.family_name,
+        p.prefix,
+        p.suffix,
+        p.gender_id,
+        p.ethnicity_id,
+        p.marital_state_id
+    from hr.employees e
+        join people.people p on p.id = e.people_id
+    where e.tenant_id = p_tenant_id
+    order by p.family_name, p.given_name, e.id;
+$$;
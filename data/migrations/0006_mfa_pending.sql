@@ -0,0 +1,40 @@
+-- mfa pending tokens that have been exchanged for a session
+
+create table iam.mfa_pending_used (
+    jti uuid primary key,
+    -- expiry of the token, the row is of no use afterwards
+    expires timestamptz not null
+);
+
+
+create function iam.mfa_pending_used(
+    p_jti uuid
+)
+returns boolean
+language sql stable
+as $$
+    select exists (
+        select 1
+        from iam.mfa_pending_used
+        where jti = p_jti
+    );
+$$;
+
+-- returns false if the token has already been used
+create function iam.mfa_pending_use(
+    p_jti uuid,
+    p_expires timestamptz
+)
+returns boolean
+language plpgsql
+as $$
+begin
+    delete from iam.mfa_pending_used
+    where expires < now();
+
+    insert into iam.mfa_pending_used (jti, expires)
+    values (p_jti, p_expires)
+    on conflict (jti) do nothing;
+    return found;
+end
+$$;
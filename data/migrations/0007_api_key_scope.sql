@@ -0,0 +1,12 @@
+-- api keys are granted the permissions listed in their scope only. keys
+-- without a scope no longer grant anything and are revoked
+
+update tenants.api_keys set
+    revoked = true
+where permissions is null
+    or cardinality(permissions) = 0;
+
+alter table tenants.api_keys
+    add constraint api_keys_permissions_check
+    check (permissions is not null and cardinality(permissions) > 0)
+    not valid;
@@ -0,0 +1,51 @@
+-- identities are only linked automatically to existing users who are members
+-- of the tenant of the identity provider. returns a null user id when an
+-- account with the email address exists outside of the tenant
+
+create or replace function iam.user_identity_provision(
+    p_id uuid,
+    p_email email_address,
+    p_given_name text,
+    p_family_name text,
+    p_issuer text,
+    p_subject text,
+    p_tenant_id uuid
+)
+returns table (
+    user_id uuid
+)
+language plpgsql
+as $$
+declare
+    v_user_id uuid;
+begin
+    select u.id into v_user_id
+    from iam.users u
+    where lower(u.email) = lower(p_email);
+
+    if v_user_id is null then
+        v_user_id := p_id;
+        insert into iam.users (id, email, given_name, family_name)
+        values (v_user_id, p_email, p_given_name, p_family_name);
+
+        if exists (select 1 from tenants.tenants t where t.id = p_tenant_id) then
+            call iam.user_tenant_add(v_user_id, p_tenant_id);
+        end if;
+    elsif not exists (
+        select 1
+        from iam.user_tenants ut
+        where ut.user_id = v_user_id
+            and ut.tenant_id = p_tenant_id
+            and ut.active
+    ) then
+        return query select null::uuid;
+        return;
+    end if;
+
+    insert into iam.user_identities (issuer, subject, user_id)
+    values (p_issuer, p_subject, v_user_id)
+    on conflict (issuer, subject) do nothing;
+
+    return query select v_user_id;
+end
+$$;
@@ -0,0 +1,44 @@
+-- marking the token is the one atomic step: of concurrent requests using
+-- the same token only one updates the row, the others find it used and
+-- the family is revoked. returns the state of the token prior to the call
+
+create or replace function iam.refresh_token_use(
+    p_digest text
+)
+returns table (
+    family_id uuid,
+    user_id uuid,
+    tenant_id uuid,
+    used boolean,
+    revoked boolean,
+    expires timestamptz
+)
+language sql
+as $$
+    with mark as (
+        update iam.refresh_tokens r set
+            used = now()
+        where r.digest = p_digest
+            and r.used is null
+        returning
+            r.family_id,
+            r.user_id,
+            r.tenant_id,
+            false as used,
+            r.revoked is not null as revoked,
+            r.expires
+    )
+    select * from mark
+    union all
+    -- the token exists but was not marked, it has been used before
+    select
+        r.family_id,
+        r.user_id,
+        r.tenant_id,
+        true as used,
+        r.revoked is not null as revoked,
+        r.expires
+    from iam.refresh_tokens r
+    where r.digest = p_digest
+        and not exists (select 1 from mark);
+$$;
@@ -0,0 +1,49 @@
+-- a tenant keeps at least one owner. returns false, removing nothing, when
+-- the user is the last owner of the tenant and the role is the owner role.
+-- the owner assignments of the tenant are locked so that concurrent
+-- removals cannot leave it without an owner
+
+drop procedure iam.user_role_remove(uuid, uuid, uuid);
+
+create function iam.user_role_remove(
+    p_tenant_id uuid,
+    p_user_id uuid,
+    p_role_id uuid
+)
+returns boolean
+language plpgsql
+as $$
+declare
+    v_owners bigint;
+    v_owner boolean;
+begin
+    if exists (
+        select 1
+        from iam.roles r
+        where r.tenant_id = p_tenant_id
+            and r.id = p_role_id
+            and r.built_in
+            and r.slug = 'owner'
+    ) then
+        perform 1
+        from iam.user_roles ur
+        where ur.tenant_id = p_tenant_id
+            and ur.role_id = p_role_id
+        for update;
+
+        select count(*), bool_or(ur.user_id = p_user_id) into v_owners, v_owner
+        from iam.user_roles ur
+        where ur.tenant_id = p_tenant_id
+            and ur.role_id = p_role_id;
+        if v_owner and v_owners = 1 then
+            return false;
+        end if;
+    end if;
+
+    delete from iam.user_roles
+    where tenant_id = p_tenant_id
+        and user_id = p_user_id
+        and role_id = p_role_id;
+    return true;
+end
+$$;
@@ -5,31 +5,24 @@ use log::{
     debug,
     error
 };
-use std::str::FromStr;
+use std::collections::HashMap;
 
 use configuration::{
     ApplicationConfiguration,
     ProviderType
 };
 
-// use deadpool::managed::Object;
-use deadpool_postgres::{ 
-    Manager, 
-    ManagerConfig, 
-    Pool, 
-    RecyclingMethod
-};
-
-use tokio_postgres::{
-    NoTls,
-    config::Config
-};
+use pg::pools::PgPools;
 
 // use pg::DataError;
 
+/// name of the provider modules use
+pub const DEFAULT_PROVIDER: &str = "default";
+
 #[derive(Debug, Clone)]
 pub struct Data {
-    pg_pool: Option<deadpool_postgres::Pool>
+    pg_pools: HashMap<String, PgPools>,
+    default: Option<String>
 }
 
 impl Data {
@@ -39,57 +32,117 @@ impl Data {
     ) -> Self {
 
         let mut data = Self {
-            pg_pool: None
+            pg_pools: HashMap::new(),
+            default: None
         };
 
         for p in &cfg.providers {
-            match (p.provider_type) {
+            match p.provider_type {
                 ProviderType::Postgres => {
-                    for url in &p.url {
-                        match Config::from_str(&url) {
-                            Err(e) => {
-                                error!("error: {:?}", e);
-                            }
-                            Ok(c) => {
-                                let mgr = Manager::from_config(
-                                    c, 
-                                    NoTls, 
-                                    ManagerConfig { recycling_method: RecyclingMethod::Fast }
-                                );
-                                match Pool::builder(mgr)
-                                    .max_size(4)
-                                    .build() {
-                                        Err(e) => {
-                                            error!("error: {:?}", e);
-                                        }
-                                        Ok(pool) => {
-                                            data.set_pg_pool(pool);
-                                        }
-                                    }
-                            }
+                    if data.pg_pools.contains_key(&p.name) {
+                        error!("provider {} is configured more than once", p.name);
+                        continue;
+                    }
+                    match PgPools::new(&p) {
+                        Err(e) => {
+                            error!("error: {:?}", e);
+                        }
+                        Ok(pools) => {
+                            info!("provider {} with {} replicas", p.name, pools.replicas());
+                            data.set_pg_pools(&p.name, pools);
                         }
                     }
                 }
-                _ => {
-                    error!("unknown provider type: {:?}", p.provider_type);
-                }
             }
         }
 
         return data;
     }
 
-    fn set_pg_pool(
+    fn set_pg_pools(
         &mut self,
-        pg_pool: deadpool_postgres::Pool
+        name: &str,
+        pg_pools: PgPools
     ) {
-        self.pg_pool = Some(pg_pool);
+        // the provider named default, otherwise the first one configured
+        if self.default.is_none() || name == DEFAULT_PROVIDER {
+            self.default = Some(String::from(name));
+        }
+        self.pg_pools.insert(String::from(name), pg_pools);
     }
 
+    /// pool of the default provider's primary
     pub fn get_pg_pool(
         &self
     ) -> Option<deadpool_postgres::Pool> {
-        return self.pg_pool.clone();
+        return self.get_pg_pools().map(|p| p.primary());
+    }
+
+    /// pools of the default provider
+    pub fn get_pg_pools(
+        &self
+    ) -> Option<PgPools> {
+        return self.default.as_ref().and_then(|name| self.get_named_pg_pools(&name));
+    }
+
+    /// pools of the provider with the given name
+    pub fn get_named_pg_pools(
+        &self,
+        name: &str
+    ) -> Option<PgPools> {
+        return self.pg_pools.get(name).cloned();
+    }
+
+    /// record the state of every provider's pools as metrics
+    pub fn observe_pools(&self) {
+        for (name, pools) in &self.pg_pools {
+            pools.observe(&name);
+        }
+    }
+
+    /// start a unit of work for data calls that must succeed or fail together
+    pub async fn begin(
+        &self
+    ) -> Result<pg::unit_of_work::UnitOfWork, pg::DataError> {
+        match self.get_pg_pool() {
+            None => {
+                return Err(pg::DataError::ConfigurationError);
+            }
+            Some(pool) => {
+                return pg::unit_of_work::UnitOfWork::begin(&pool).await;
+            }
+        }
+    }
+
+    /// apply pending database migrations and return their versions. the
+    /// migrations up to a baseline are recorded as applied without running
+    /// them, to adopt a database whose schema already matches that version
+    pub async fn migrate(
+        &self,
+        baseline: Option<i32>
+    ) -> Result<Vec<i32>, pg::DataError> {
+        match self.get_pg_pool() {
+            None => {
+                return Err(pg::DataError::ConfigurationError);
+            }
+            Some(pool) => {
+                return pg::migrations::migrate(&pool, baseline).await;
+            }
+        }
+    }
+
+    /// compare the database schema version to the embedded migrations
+    pub async fn schema_status(
+        &self
+    ) -> Result<pg::migrations::SchemaStatus, pg::DataError> {
+        match self.get_pg_pool() {
+            None => {
+                return Err(pg::DataError::ConfigurationError);
+            }
+            Some(pool) => {
+                return pg::migrations::status(&pool).await;
+            }
+        }
     }
 }
 
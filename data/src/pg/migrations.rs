@@ -0,0 +1,351 @@
+use log::{
+    info,
+    error
+};
+
+use deadpool_postgres::Pool;
+use tokio_postgres::GenericClient;
+
+use crate::pg::DataError;
+
+
+/// key of the advisory lock held while migrating so that concurrent
+/// servers apply each migration once
+const MIGRATION_LOCK: i64 = 0x0069_6465_6173;
+
+
+/// versioned sql script embedded in the server
+#[derive(Debug)]
+pub struct Migration {
+    version: i32,
+    name: &'static str,
+    sql: &'static str
+}
+
+impl Migration {
+
+    pub fn version(&self) -> i32 {
+        return self.version;
+    }
+
+    pub fn name(&self) -> &'static str {
+        return self.name;
+    }
+
+    pub fn sql(&self) -> &'static str {
+        return self.sql;
+    }
+}
+
+
+/// migrations in the order they are applied
+pub const MIGRATIONS: [Migration; 10] = [
+    Migration {
+        version: 1,
+        name: "common",
+        sql: include_str!("../../migrations/0001_common.sql")
+    },
+    Migration {
+        version: 2,
+        name: "tenants",
+        sql: include_str!("../../migrations/0002_tenants.sql")
+    },
+    Migration {
+        version: 3,
+        name: "iam",
+        sql: include_str!("../../migrations/0003_iam.sql")
+    },
+    Migration {
+        version: 4,
+        name: "api_keys",
+        sql: include_str!("../../migrations/0004_api_keys.sql")
+    },
+    Migration {
+        version: 5,
+        name: "people_hr",
+        sql: include_str!("../../migrations/0005_people_hr.sql")
+    },
+    Migration {
+        version: 6,
+        name: "mfa_pending",
+        sql: include_str!("../../migrations/0006_mfa_pending.sql")
+    },
+    Migration {
+        version: 7,
+        name: "api_key_scope",
+        sql: include_str!("../../migrations/0007_api_key_scope.sql")
+    },
+    Migration {
+        version: 8,
+        name: "oidc_link",
+        sql: include_str!("../../migrations/0008_oidc_link.sql")
+    },
+    Migration {
+        version: 9,
+        name: "refresh_token_use",
+        sql: include_str!("../../migrations/0009_refresh_token_use.sql")
+    },
+    Migration {
+        version: 10,
+        name: "user_role_remove",
+        sql: include_str!("../../migrations/0010_user_role_remove.sql")
+    }
+];
+
+
+/// version of the newest embedded migration
+pub fn latest() -> i32 {
+    return MIGRATIONS.iter().map(|m| m.version).max().unwrap_or(0);
+}
+
+
+/// database schema version compared to the embedded migrations
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaStatus {
+    Current(i32),
+    Behind { current: i32, latest: i32 },
+    Ahead { current: i32, latest: i32 }
+}
+
+impl SchemaStatus {
+
+    pub fn new(current: i32, latest: i32) -> Self {
+        if current < latest {
+            return SchemaStatus::Behind { current: current, latest: latest };
+        }
+        if current > latest {
+            return SchemaStatus::Ahead { current: current, latest: latest };
+        }
+        return SchemaStatus::Current(current);
+    }
+}
+
+
+/// retrieve the version of the database schema, 0 if no migration has
+/// been applied
+async fn version<C: GenericClient>(
+    client: &C
+) -> Result<i32, DataError> {
+    let row = match client.query_one(
+        "select to_regclass('public.schema_migrations') is not null",
+        &[]
+    ).await {
+        Err(e) => {
+            error!("unable to check schema version table: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        Ok(row) => row
+    };
+    let exists: bool = row.get(0);
+    if !exists {
+        return Ok(0);
+    }
+
+    match client.query_one(
+        "select coalesce(max(version), 0) from public.schema_migrations",
+        &[]
+    ).await {
+        Err(e) => {
+            error!("unable to retrieve schema version: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        Ok(row) => {
+            return Ok(row.get(0));
+        }
+    }
+}
+
+/// compare the database schema version to the embedded migrations
+pub async fn status(pool: &Pool) -> Result<SchemaStatus, DataError> {
+    info!("migrations::status()");
+
+    let result = pool.get().await;
+    if let Err(e) = result {
+        error!("unable to retrieve database client: {:?}", e);
+        return Err(DataError::Unavailable);
+    }
+    let client = result.unwrap();
+
+    let current = version(&**client).await?;
+    return Ok(SchemaStatus::new(current, latest()));
+}
+
+/// apply pending migrations in a single transaction and return their
+/// versions. a database created before the migrations were embedded is
+/// adopted with a baseline, the version its schema already matches: the
+/// migrations up to it are recorded as applied without running them
+pub async fn migrate(pool: &Pool, baseline: Option<i32>) -> Result<Vec<i32>, DataError> {
+    info!("migrations::migrate()");
+
+    let result = pool.get().await;
+    if let Err(e) = result {
+        error!("unable to retrieve database client: {:?}", e);
+        return Err(DataError::Unavailable);
+    }
+    let mut client = result.unwrap();
+
+    let tx = match client.transaction().await {
+        Err(e) => {
+            error!("unable to start transaction: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        Ok(tx) => tx
+    };
+
+    if let Err(e) = tx.execute("select pg_advisory_xact_lock($1)", &[&MIGRATION_LOCK]).await {
+        error!("unable to acquire migration lock: {:?}", e);
+        return Err(DataError::from(&e));
+    }
+
+    if let Err(e) = tx.batch_execute(
+        "create table if not exists public.schema_migrations (
+            version integer primary key,
+            name text not null,
+            applied timestamptz not null default now()
+        )"
+    ).await {
+        error!("unable to create schema version table: {:?}", e);
+        return Err(DataError::from(&e));
+    }
+
+    let mut current = version(&*tx).await?;
+    if let Some(baseline) = baseline {
+        if current != 0 {
+            error!("schema version {} is recorded, a baseline is only recorded for an unversioned database", current);
+            return Err(DataError::Conflict);
+        }
+        if baseline < 1 || baseline > latest() {
+            error!("baseline {} is not a migration version", baseline);
+            return Err(DataError::Validation);
+        }
+        for m in MIGRATIONS.iter().filter(|m| m.version <= baseline) {
+            info!("recording migration {} {} as applied", m.version, m.name);
+            if let Err(e) = tx.execute(
+                "insert into public.schema_migrations (version, name) values ($1, $2)",
+                &[&m.version, &m.name]
+            ).await {
+                error!("unable to record migration {}: {:?}", m.version, e);
+                return Err(DataError::from(&e));
+            }
+        }
+        current = baseline;
+    }
+
+    let mut applied = Vec::new();
+    for m in MIGRATIONS.iter().filter(|m| m.version > current) {
+        info!("applying migration {} {}", m.version, m.name);
+        if let Err(e) = tx.batch_execute(m.sql).await {
+            error!("unable to apply migration {} {}: {:?}", m.version, m.name, e);
+            return Err(DataError::from(&e));
+        }
+        if let Err(e) = tx.execute(
+            "insert into public.schema_migrations (version, name) values ($1, $2)",
+            &[&m.version, &m.name]
+        ).await {
+            error!("unable to record migration {}: {:?}", m.version, e);
+            return Err(DataError::from(&e));
+        }
+        applied.push(m.version);
+    }
+
+    if let Err(e) = tx.commit().await {
+        error!("unable to commit migrations: {:?}", e);
+        return Err(DataError::from(&e));
+    }
+    return Ok(applied);
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::path::{
+        Path,
+        PathBuf
+    };
+
+    #[test]
+    fn test_versions_sequential() {
+        for (i, m) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(m.version(), i as i32 + 1);
+            assert!(!m.sql().trim().is_empty(), "migration {} is empty", m.name());
+        }
+        assert_eq!(latest(), MIGRATIONS.len() as i32);
+    }
+
+    #[test]
+    fn test_schema_status() {
+        assert_eq!(SchemaStatus::new(5, 5), SchemaStatus::Current(5));
+        assert_eq!(SchemaStatus::new(0, 5), SchemaStatus::Behind { current: 0, latest: 5 });
+        assert_eq!(SchemaStatus::new(6, 5), SchemaStatus::Ahead { current: 6, latest: 5 });
+    }
+
+    /// source files compiled into a crate, following the mod declarations
+    /// from the crate root
+    fn sources(file: &Path, files: &mut Vec<PathBuf>) {
+        let source = match std::fs::read_to_string(file) {
+            Err(_) => return,
+            Ok(source) => source
+        };
+        files.push(file.to_path_buf());
+
+        let stem = file.file_stem().unwrap().to_str().unwrap();
+        let dir = if matches!(stem, "lib" | "main" | "mod") {
+            file.parent().unwrap().to_path_buf()
+        } else {
+            file.parent().unwrap().join(stem)
+        };
+        for line in source.lines().map(|l| l.trim()) {
+            let decl = line.strip_prefix("pub ").unwrap_or(line);
+            if let Some(name) = decl.strip_prefix("mod ").and_then(|d| d.strip_suffix(';')) {
+                let file = dir.join(format!("{}.rs", name));
+                if file.exists() {
+                    sources(&file, files);
+                } else {
+                    sources(&dir.join(name).join("mod.rs"), files);
+                }
+            }
+        }
+    }
+
+    /// every procedure called by a workspace member must be created by a
+    /// migration
+    #[test]
+    fn test_procedures_defined() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+        let manifest = std::fs::read_to_string(root.join("Cargo.toml")).unwrap();
+        let members = &manifest[manifest.find("members").unwrap()..];
+        let members = &members[members.find('[').unwrap() + 1..members.find(']').unwrap()];
+
+        let mut files = Vec::new();
+        for member in members.split(',').map(|m| m.trim().trim_matches('"')).filter(|m| !m.is_empty()) {
+            for entry in ["src/lib.rs", "src/main.rs"] {
+                sources(&root.join(member).join(entry), &mut files);
+            }
+        }
+        assert!(!files.is_empty());
+
+        let sql: String = MIGRATIONS.iter().map(|m| m.sql()).collect();
+        let mut missing = Vec::new();
+        for file in files {
+            let source = std::fs::read_to_string(&file).unwrap();
+            for prefix in ["\"call ", "\"select * from "] {
+                for part in source.split(prefix).skip(1) {
+                    let name = &part[..part.find('(').unwrap_or(0)];
+                    let identifier = name.split('.').count() == 2
+                        && name.split('.').all(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_lowercase() || c == '_'));
+                    if !identifier {
+                        continue;
+                    }
+                    let defined = sql.contains(&format!("create procedure {}(", name))
+                        || sql.contains(&format!("create function {}(", name));
+                    if !defined {
+                        missing.push(format!("{} ({})", name, file.display()));
+                    }
+                }
+            }
+        }
+        assert!(missing.is_empty(), "procedures without a migration: {:?}", missing);
+    }
+}
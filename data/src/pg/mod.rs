@@ -1,9 +1,131 @@
 pub mod email;
+pub mod migrations;
+pub mod pools;
+pub mod sessions;
 pub mod slug;
+pub mod tls;
+pub mod unit_of_work;
 
-#[derive(Debug)]
+use std::future::Future;
+use std::time::Instant;
+
+use tokio_postgres::error::SqlState;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum DataError {
-    ToBeImplemented(String),
     ConfigurationError,
-    DatabaseError
-}
\ No newline at end of file
+    DatabaseError,
+    NotFound,
+    Conflict,
+    Validation,
+    Unavailable
+}
+
+/// map a statement error by its sqlstate
+impl From<&tokio_postgres::Error> for DataError {
+    fn from(e: &tokio_postgres::Error) -> Self {
+        match e.code() {
+            None if e.is_closed() => {
+                return DataError::Unavailable;
+            }
+            None => {
+                return DataError::DatabaseError;
+            }
+            Some(code) => {
+                return DataError::from(code);
+            }
+        }
+    }
+}
+
+impl From<&SqlState> for DataError {
+    fn from(code: &SqlState) -> Self {
+        // class 08 connection exceptions, class 53 insufficient resources,
+        // class 57 operator intervention and class 40 transaction rollbacks
+        // are transient
+        let class = &code.code()[..2];
+        if matches!(class, "08" | "53" | "57" | "40") {
+            return DataError::Unavailable;
+        }
+
+        if *code == SqlState::UNIQUE_VIOLATION
+            || *code == SqlState::FOREIGN_KEY_VIOLATION
+            || *code == SqlState::EXCLUSION_VIOLATION {
+            return DataError::Conflict;
+        }
+        if *code == SqlState::NO_DATA_FOUND {
+            return DataError::NotFound;
+        }
+        // class 22 data exceptions and the remaining class 23 integrity
+        // constraint violations are caused by invalid input
+        if matches!(class, "22" | "23") {
+            return DataError::Validation;
+        }
+        return DataError::DatabaseError;
+    }
+}
+
+impl From<DataError> for common::Error {
+    fn from(e: DataError) -> Self {
+        match e {
+            DataError::ConfigurationError => {
+                return common::Error::ConfigurationError(String::from("database is not configured"));
+            }
+            DataError::DatabaseError => {
+                return common::Error::Internal(String::from("database error"));
+            }
+            DataError::NotFound => {
+                return common::Error::NotFound(String::from("record not found"));
+            }
+            DataError::Conflict => {
+                return common::Error::Conflict(String::from("record conflicts with an existing record"));
+            }
+            DataError::Validation => {
+                return common::Error::ValidationError(String::from("record is not valid"));
+            }
+            DataError::Unavailable => {
+                return common::Error::Unavailable(String::from("database is unavailable"));
+            }
+        }
+    }
+}
+
+/// execute a statement, recording its duration under the name of the
+/// function or procedure it calls, e.g. iam.user_by_email
+pub async fn timed<T>(
+    statement: &str,
+    query: impl Future<Output = Result<T, tokio_postgres::Error>>
+) -> Result<T, tokio_postgres::Error> {
+    let start = Instant::now();
+    let result = query.await;
+    metrics::statement(statement, result.is_ok(), start.elapsed().as_secs_f64());
+    return result;
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sqlstate_mapping() {
+        assert_eq!(DataError::from(&SqlState::UNIQUE_VIOLATION), DataError::Conflict);
+        assert_eq!(DataError::from(&SqlState::FOREIGN_KEY_VIOLATION), DataError::Conflict);
+        assert_eq!(DataError::from(&SqlState::NOT_NULL_VIOLATION), DataError::Validation);
+        assert_eq!(DataError::from(&SqlState::CHECK_VIOLATION), DataError::Validation);
+        assert_eq!(DataError::from(&SqlState::INVALID_TEXT_REPRESENTATION), DataError::Validation);
+        assert_eq!(DataError::from(&SqlState::NO_DATA_FOUND), DataError::NotFound);
+        assert_eq!(DataError::from(&SqlState::CONNECTION_FAILURE), DataError::Unavailable);
+        assert_eq!(DataError::from(&SqlState::TOO_MANY_CONNECTIONS), DataError::Unavailable);
+        assert_eq!(DataError::from(&SqlState::T_R_SERIALIZATION_FAILURE), DataError::Unavailable);
+        assert_eq!(DataError::from(&SqlState::UNDEFINED_FUNCTION), DataError::DatabaseError);
+    }
+
+    #[test]
+    fn test_common_error() {
+        let e: common::Error = DataError::Conflict.into();
+        assert_eq!(e.code(), common::error::ErrorCode::Conflict);
+        let e: common::Error = DataError::Unavailable.into();
+        assert_eq!(e.code(), common::error::ErrorCode::Unavailable);
+    }
+}
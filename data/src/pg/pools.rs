@@ -0,0 +1,210 @@
+use log::{
+    warn,
+    error
+};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::{
+    AtomicUsize,
+    Ordering
+};
+use std::time::Duration;
+
+use deadpool_postgres::{
+    Client,
+    Manager,
+    ManagerConfig,
+    Pool,
+    PoolError,
+    RecyclingMethod,
+    Runtime
+};
+use tokio_postgres::{
+    NoTls,
+    config::{
+        Config,
+        SslMode
+    }
+};
+
+use configuration::Provider;
+
+use crate::pg::DataError;
+use crate::pg::tls;
+
+
+/// connection pools of a provider, a primary for writes and replicas
+/// read-only queries are spread across
+#[derive(Debug, Clone)]
+pub struct PgPools {
+    primary: Pool,
+    replicas: Vec<Pool>,
+    next: Arc<AtomicUsize>
+}
+
+impl PgPools {
+
+    pub fn new(provider: &Provider) -> Result<Self, DataError> {
+        let url = match provider.url.first() {
+            None => {
+                error!("provider {} has no url", provider.name);
+                return Err(DataError::ConfigurationError);
+            }
+            Some(url) => url
+        };
+        if provider.url.len() > 1 {
+            warn!("provider {} lists several urls, only the first is used", provider.name);
+        }
+
+        let primary = Self::pool(&url, &provider)?;
+        let mut replicas = Vec::new();
+        for url in &provider.replicas {
+            replicas.push(Self::pool(&url, &provider)?);
+        }
+
+        return Ok(Self {
+            primary: primary,
+            replicas: replicas,
+            next: Arc::new(AtomicUsize::new(0))
+        });
+    }
+
+    fn pool(
+        url: &str,
+        provider: &Provider
+    ) -> Result<Pool, DataError> {
+        let mut config = match Config::from_str(&url) {
+            Err(e) => {
+                error!("invalid database url: {:?}", e);
+                return Err(DataError::ConfigurationError);
+            }
+            Ok(config) => config
+        };
+
+        let manager_config = ManagerConfig { recycling_method: RecyclingMethod::Fast };
+        let mgr = match &provider.tls {
+            None => Manager::from_config(config, NoTls, manager_config),
+            Some(tls) => {
+                // never fall back to an unencrypted connection
+                config.ssl_mode(SslMode::Require);
+                Manager::from_config(config, tls::connector(&tls)?, manager_config)
+            }
+        };
+
+        let settings = &provider.pool;
+        match Pool::builder(mgr)
+            .max_size(settings.max_size)
+            .runtime(Runtime::Tokio1)
+            .wait_timeout(settings.wait_timeout_ms.map(Duration::from_millis))
+            .create_timeout(settings.create_timeout_ms.map(Duration::from_millis))
+            .recycle_timeout(settings.recycle_timeout_ms.map(Duration::from_millis))
+            .build() {
+                Err(e) => {
+                    error!("unable to build connection pool: {:?}", e);
+                    return Err(DataError::ConfigurationError);
+                }
+                Ok(pool) => {
+                    return Ok(pool);
+                }
+            }
+    }
+
+    /// pool of the primary, used for writes and reads that must see them
+    pub fn primary(&self) -> Pool {
+        return self.primary.clone();
+    }
+
+    pub fn replicas(&self) -> usize {
+        return self.replicas.len();
+    }
+
+    /// record the state of the pools as metrics of the provider
+    pub fn observe(&self, provider: &str) {
+        Self::observe_pool(provider, "primary", &self.primary);
+        for (i, replica) in self.replicas.iter().enumerate() {
+            Self::observe_pool(provider, &format!("replica-{}", i), replica);
+        }
+    }
+
+    fn observe_pool(provider: &str, name: &str, pool: &Pool) {
+        let status = pool.status();
+        // available is negative while requests wait for a connection
+        metrics::pool(
+            provider,
+            name,
+            status.max_size,
+            status.size,
+            status.available.max(0) as usize,
+            (-status.available).max(0) as usize
+        );
+    }
+
+    /// retrieve a connection for read-only queries. replicas are tried in
+    /// turn, the primary serves the query when none is reachable
+    pub async fn read(&self) -> Result<Client, PoolError> {
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        for i in Self::order(start, self.replicas.len()) {
+            match self.replicas[i].get().await {
+                Err(e) => {
+                    warn!("replica {} unavailable: {:?}", i, e);
+                }
+                Ok(client) => {
+                    return Ok(client);
+                }
+            }
+        }
+        return self.primary.get().await;
+    }
+
+    /// replica indexes in the order they are tried, starting at the
+    /// replica whose turn it is
+    fn order(start: usize, len: usize) -> impl Iterator<Item = usize> {
+        return (0..len).map(move |i| (start + i) % len);
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use configuration::{
+        PoolSettings,
+        ProviderType
+    };
+
+    fn provider(url: &str, replicas: Vec<String>) -> Provider {
+        return Provider {
+            name: String::from("default"),
+            provider_type: ProviderType::Postgres,
+            url: vec![String::from(url)],
+            replicas: replicas,
+            pool: PoolSettings {
+                max_size: 7,
+                wait_timeout_ms: Some(100),
+                create_timeout_ms: Some(100),
+                recycle_timeout_ms: None
+            },
+            tls: None
+        };
+    }
+
+    #[test]
+    fn test_order() {
+        assert_eq!(PgPools::order(0, 3).collect::<Vec<usize>>(), vec![0, 1, 2]);
+        assert_eq!(PgPools::order(4, 3).collect::<Vec<usize>>(), vec![1, 2, 0]);
+        assert_eq!(PgPools::order(5, 0).count(), 0);
+    }
+
+    #[test]
+    fn test_new() {
+        let pools = PgPools::new(&provider(
+            "postgres://app@localhost/app",
+            vec![String::from("postgres://app@replica-1/app"), String::from("postgres://app@replica-2/app")]
+        )).unwrap();
+        assert_eq!(pools.replicas(), 2);
+        assert_eq!(pools.primary().status().max_size, 7);
+
+        assert_eq!(PgPools::new(&provider("not a url", vec![])).unwrap_err(), DataError::ConfigurationError);
+    }
+}
@@ -0,0 +1,54 @@
+use log::{
+    info,
+    error
+};
+
+use deadpool_postgres::Pool;
+
+use crate::pg::{
+    DataError,
+    timed
+};
+
+
+/// revoke every session of a user except for the one specified, nil
+/// revokes all of them. shared by the modules that end sessions
+pub async fn user_sessions_revoke(
+    pool: &Pool,
+    user_id: &uuid::Uuid,
+    except_session_id: &uuid::Uuid
+) -> Result<(), DataError> {
+    info!("sessions::user_sessions_revoke()");
+
+    let result = pool.get().await;
+    if let Err(e) = result {
+        error!("unable to retrieve database client: {:?}", e);
+        return Err(DataError::Unavailable);
+    }
+    let client = result.unwrap();
+
+    let result = client.prepare_cached(
+        "call iam.user_sessions_revoke($1, $2)"
+    ).await;
+    if let Err(e) = result {
+        error!("unable to prepare database statement: {:?}", e);
+        return Err(DataError::from(&e));
+    }
+    let stmt = result.unwrap();
+
+    match timed("iam.user_sessions_revoke", client.execute(
+        &stmt,
+        &[
+            &user_id,
+            &except_session_id
+        ]
+    )).await {
+        Err(e) => {
+            error!("unable to execute statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        Ok(_) => {
+            return Ok(());
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use log::{
+    info,
+    error
+};
+use std::fs::File;
+use std::io::BufReader;
+use std::sync::Arc;
+
+use rustls::{
+    ClientConfig,
+    RootCertStore,
+    crypto::ring,
+    pki_types::{
+        CertificateDer,
+        PrivateKeyDer
+    }
+};
+use tokio_postgres_rustls::MakeRustlsConnect;
+
+use configuration::ProviderTls;
+
+use crate::pg::DataError;
+
+
+/// build the connector for a provider's tls settings. the server
+/// certificate must chain to the ca bundle and match the host name, a
+/// client certificate is presented when one is configured
+pub fn connector(tls: &ProviderTls) -> Result<MakeRustlsConnect, DataError> {
+    info!("tls::connector()");
+
+    let mut roots = RootCertStore::empty();
+    let (added, ignored) = roots.add_parsable_certificates(certificates(&tls.ca_file)?);
+    if added == 0 {
+        error!("no usable ca certificate in {} ({} ignored)", tls.ca_file, ignored);
+        return Err(DataError::ConfigurationError);
+    }
+
+    let builder = match ClientConfig::builder_with_provider(Arc::new(ring::default_provider()))
+        .with_safe_default_protocol_versions() {
+            Err(e) => {
+                error!("unable to configure tls: {:?}", e);
+                return Err(DataError::ConfigurationError);
+            }
+            Ok(builder) => builder.with_root_certificates(roots)
+        };
+    let config = match (&tls.cert_file, &tls.key_file) {
+        (None, None) => builder.with_no_client_auth(),
+        (Some(cert_file), Some(key_file)) => {
+            let chain = certificates(&cert_file)?;
+            match builder.with_client_auth_cert(chain, private_key(&key_file)?) {
+                Err(e) => {
+                    error!("unable to use client certificate {}: {:?}", cert_file, e);
+                    return Err(DataError::ConfigurationError);
+                }
+                Ok(config) => config
+            }
+        }
+        _ => {
+            error!("a client certificate requires both certFile and keyFile");
+            return Err(DataError::ConfigurationError);
+        }
+    };
+
+    return Ok(MakeRustlsConnect::new(config));
+}
+
+/// read the DER encoded certificates of a PEM file
+fn certificates(path: &str) -> Result<Vec<CertificateDer<'static>>, DataError> {
+    let mut reader = BufReader::new(open(&path)?);
+    match rustls_pemfile::certs(&mut reader).collect::<Result<Vec<_>, _>>() {
+        Err(e) => {
+            error!("unable to read certificates from {}: {:?}", path, e);
+            return Err(DataError::ConfigurationError);
+        }
+        Ok(certs) if certs.is_empty() => {
+            error!("no certificate in {}", path);
+            return Err(DataError::ConfigurationError);
+        }
+        Ok(certs) => {
+            return Ok(certs);
+        }
+    }
+}
+
+/// read the first private key of a PEM file
+fn private_key(path: &str) -> Result<PrivateKeyDer<'static>, DataError> {
+    let mut reader = BufReader::new(open(&path)?);
+    match rustls_pemfile::private_key(&mut reader) {
+        Err(e) => {
+            error!("unable to read private key from {}: {:?}", path, e);
+            return Err(DataError::ConfigurationError);
+        }
+        Ok(None) => {
+            error!("no private key in {}", path);
+            return Err(DataError::ConfigurationError);
+        }
+        Ok(Some(key)) => {
+            return Ok(key);
+        }
+    }
+}
+
+fn open(path: &str) -> Result<File, DataError> {
+    match File::open(path) {
+        Err(e) => {
+            error!("unable to open {}: {:?}", path, e);
+            return Err(DataError::ConfigurationError);
+        }
+        Ok(file) => {
+            return Ok(file);
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connector_missing_files() {
+        let tls = ProviderTls {
+            ca_file: String::from("/nonexistent/ca.pem"),
+            cert_file: None,
+            key_file: None
+        };
+        assert_eq!(connector(&tls).err(), Some(DataError::ConfigurationError));
+
+        // a manifest is no PEM file
+        let tls = ProviderTls {
+            ca_file: String::from(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml")),
+            cert_file: None,
+            key_file: None
+        };
+        assert_eq!(connector(&tls).err(), Some(DataError::ConfigurationError));
+    }
+}
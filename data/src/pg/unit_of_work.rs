@@ -0,0 +1,83 @@
+use log::{
+    info,
+    warn,
+    error
+};
+
+use deadpool_postgres::{
+    Client,
+    Pool
+};
+
+use crate::pg::DataError;
+
+
+/// a database transaction shared by data calls of several modules. the
+/// calls made through the unit of work are committed or rolled back
+/// together
+pub struct UnitOfWork {
+    client: Option<Client>
+}
+
+impl UnitOfWork {
+
+    /// start a transaction on a pooled connection
+    pub async fn begin(pool: &Pool) -> Result<Self, DataError> {
+        info!("UnitOfWork::begin()");
+
+        let result = pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        if let Err(e) = client.batch_execute("begin").await {
+            error!("unable to start transaction: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        return Ok(Self {
+            client: Some(client)
+        });
+    }
+
+    /// connection the transaction runs on
+    pub fn client(&self) -> &Client {
+        return self.client.as_ref().unwrap();
+    }
+
+    pub async fn commit(mut self) -> Result<(), DataError> {
+        info!("UnitOfWork::commit()");
+        return self.finish("commit").await;
+    }
+
+    pub async fn rollback(mut self) -> Result<(), DataError> {
+        info!("UnitOfWork::rollback()");
+        return self.finish("rollback").await;
+    }
+
+    async fn finish(&mut self, statement: &str) -> Result<(), DataError> {
+        let client = self.client.take().unwrap();
+        if let Err(e) = client.batch_execute(statement).await {
+            error!("unable to {} transaction: {:?}", statement, e);
+            Self::discard(client);
+            return Err(DataError::from(&e));
+        }
+        return Ok(());
+    }
+
+    /// close the connection instead of returning it to the pool, the
+    /// server rolls back a transaction left open on it
+    fn discard(client: Client) {
+        drop(deadpool::managed::Object::take(client));
+    }
+}
+
+impl Drop for UnitOfWork {
+    fn drop(&mut self) {
+        if let Some(client) = self.client.take() {
+            warn!("unit of work dropped without commit or rollback, discarding its connection");
+            Self::discard(client);
+        }
+    }
+}
@@ -2,6 +2,7 @@ use log::{
     info,
     error
 };
+use std::time::Duration;
 
 use lettre::{
     Message, 
@@ -63,12 +64,25 @@ impl Mailer {
     //     }           
     // }
 
+    /// send a mail, counting successes and failures
     pub fn send(
         &self,
         from: &str,
         to: &str,
         subject: &str,
         body: &str
+    ) -> Result<(), MailerError> {
+        let result = self.deliver(from, to, subject, body);
+        metrics::mail_sent(result.is_ok());
+        return result;
+    }
+
+    fn deliver(
+        &self,
+        from: &str,
+        to: &str,
+        subject: &str,
+        body: &str
     ) -> Result<(), MailerError> {
         match SmtpTransport::relay(&self.relay_host) {
             Err(e) => {
@@ -116,6 +130,35 @@ impl Mailer {
             }
         };
     }
+
+    /// connect to the relay host to check that mail can be sent, giving up
+    /// after the timeout
+    pub fn test_connection(&self, timeout: Duration) -> Result<(), MailerError> {
+        match SmtpTransport::relay(&self.relay_host) {
+            Err(e) => {
+                error!("unable to create relay: {:?}", e);
+                return Err(MailerError::ConfigurationError(String::from("unable to create relay")));
+            }
+            Ok(tb) => {
+                let transport = tb.credentials(self.credentials.clone())
+                    .timeout(Some(timeout))
+                    .build();
+                match transport.test_connection() {
+                    Err(e) => {
+                        error!("unable to connect to relay: {:?}", e);
+                        return Err(MailerError::SendError(e.to_string()));
+                    }
+                    Ok(false) => {
+                        error!("relay did not accept the connection");
+                        return Err(MailerError::SendError(String::from("relay did not accept the connection")));
+                    }
+                    Ok(true) => {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
 }
 
 
@@ -0,0 +1,194 @@
+use log::{
+    error
+};
+use std::sync::LazyLock;
+
+use prometheus::{
+    Encoder,
+    HistogramOpts,
+    HistogramVec,
+    IntCounterVec,
+    IntGaugeVec,
+    Opts,
+    Registry,
+    TextEncoder
+};
+
+
+/// prefix of every metric name
+const NAMESPACE: &str = "ideas";
+
+static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+
+struct Metrics {
+    registry: Registry,
+    http_requests: IntCounterVec,
+    http_request_duration: HistogramVec,
+    statement_duration: HistogramVec,
+    pool_max_size: IntGaugeVec,
+    pool_size: IntGaugeVec,
+    pool_available: IntGaugeVec,
+    pool_waiting: IntGaugeVec,
+    token_validation_failures: IntCounterVec,
+    mails_sent: IntCounterVec
+}
+
+impl Metrics {
+
+    fn new() -> Self {
+        let registry = Registry::new();
+
+        let http_requests = counter(
+            &registry,
+            "http_requests_total",
+            "http requests by method, route pattern and status",
+            &["method", "route", "status"]
+        );
+        let http_request_duration = histogram(
+            &registry,
+            "http_request_duration_seconds",
+            "http request latency by method, route pattern and status",
+            &["method", "route", "status"]
+        );
+        let statement_duration = histogram(
+            &registry,
+            "db_statement_duration_seconds",
+            "database statement duration by statement and outcome",
+            &["statement", "outcome"]
+        );
+        let pool_max_size = gauge(&registry, "db_pool_max_size", "maximum connections of a pool");
+        let pool_size = gauge(&registry, "db_pool_size", "open connections of a pool");
+        let pool_available = gauge(&registry, "db_pool_available", "idle connections of a pool");
+        let pool_waiting = gauge(&registry, "db_pool_waiting", "requests waiting for a connection of a pool");
+        let token_validation_failures = counter(
+            &registry,
+            "token_validation_failures_total",
+            "rejected tokens by reason",
+            &["reason"]
+        );
+        let mails_sent = counter(
+            &registry,
+            "mails_sent_total",
+            "mails sent by outcome",
+            &["outcome"]
+        );
+
+        return Self {
+            registry: registry,
+            http_requests: http_requests,
+            http_request_duration: http_request_duration,
+            statement_duration: statement_duration,
+            pool_max_size: pool_max_size,
+            pool_size: pool_size,
+            pool_available: pool_available,
+            pool_waiting: pool_waiting,
+            token_validation_failures: token_validation_failures,
+            mails_sent: mails_sent
+        };
+    }
+}
+
+fn counter(registry: &Registry, name: &str, help: &str, labels: &[&str]) -> IntCounterVec {
+    let counter = IntCounterVec::new(Opts::new(name, help).namespace(NAMESPACE), labels)
+        .expect("invalid counter definition");
+    registry.register(Box::new(counter.clone())).expect("counter registered twice");
+    return counter;
+}
+
+fn histogram(registry: &Registry, name: &str, help: &str, labels: &[&str]) -> HistogramVec {
+    let histogram = HistogramVec::new(HistogramOpts::new(name, help).namespace(NAMESPACE), labels)
+        .expect("invalid histogram definition");
+    registry.register(Box::new(histogram.clone())).expect("histogram registered twice");
+    return histogram;
+}
+
+fn gauge(registry: &Registry, name: &str, help: &str) -> IntGaugeVec {
+    let gauge = IntGaugeVec::new(Opts::new(name, help).namespace(NAMESPACE), &["provider", "pool"])
+        .expect("invalid gauge definition");
+    registry.register(Box::new(gauge.clone())).expect("gauge registered twice");
+    return gauge;
+}
+
+fn outcome(ok: bool) -> &'static str {
+    return if ok { "success" } else { "failure" };
+}
+
+
+/// record a handled http request. route is the matched route pattern,
+/// not the path, to keep the number of series bounded
+pub fn http_request(method: &str, route: &str, status: u16, seconds: f64) {
+    let status = status.to_string();
+    let labels = [method, route, status.as_str()];
+    METRICS.http_requests.with_label_values(&labels).inc();
+    METRICS.http_request_duration.with_label_values(&labels).observe(seconds);
+}
+
+/// record the execution of a database statement
+pub fn statement(statement: &str, ok: bool, seconds: f64) {
+    METRICS.statement_duration.with_label_values(&[statement, outcome(ok)]).observe(seconds);
+}
+
+/// record the state of a connection pool, e.g. pool "primary" or
+/// "replica-0" of a provider
+pub fn pool(
+    provider: &str,
+    pool: &str,
+    max_size: usize,
+    size: usize,
+    available: usize,
+    waiting: usize
+) {
+    let labels = [provider, pool];
+    METRICS.pool_max_size.with_label_values(&labels).set(max_size as i64);
+    METRICS.pool_size.with_label_values(&labels).set(size as i64);
+    METRICS.pool_available.with_label_values(&labels).set(available as i64);
+    METRICS.pool_waiting.with_label_values(&labels).set(waiting as i64);
+}
+
+/// record a rejected token
+pub fn token_validation_failure(reason: &str) {
+    METRICS.token_validation_failures.with_label_values(&[reason]).inc();
+}
+
+/// record an attempt to send a mail
+pub fn mail_sent(ok: bool) {
+    METRICS.mails_sent.with_label_values(&[outcome(ok)]).inc();
+}
+
+/// every metric in the prometheus text exposition format
+pub fn render() -> String {
+    let mut buffer = Vec::new();
+    if let Err(e) = TextEncoder::new().encode(&METRICS.registry.gather(), &mut buffer) {
+        error!("unable to encode metrics: {:?}", e);
+    }
+    return String::from_utf8(buffer).unwrap_or_default();
+}
+
+/// content type of the text exposition format
+pub fn content_type() -> &'static str {
+    return prometheus::TEXT_FORMAT;
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        http_request("GET", "/status/live", 200, 0.002);
+        statement("iam.user_by_email", true, 0.01);
+        pool("default", "primary", 4, 2, 1, 0);
+        token_validation_failure("expired");
+        mail_sent(false);
+
+        let text = render();
+        assert!(text.contains("ideas_http_requests_total{method=\"GET\",route=\"/status/live\",status=\"200\"} 1"));
+        assert!(text.contains("ideas_http_request_duration_seconds_count{method=\"GET\",route=\"/status/live\",status=\"200\"} 1"));
+        assert!(text.contains("ideas_db_statement_duration_seconds_count{outcome=\"success\",statement=\"iam.user_by_email\"} 1"));
+        assert!(text.contains("ideas_db_pool_available{pool=\"primary\",provider=\"default\"} 1"));
+        assert!(text.contains("ideas_token_validation_failures_total{reason=\"expired\"} 1"));
+        assert!(text.contains("ideas_mails_sent_total{outcome=\"failure\"} 1"));
+    }
+}
@@ -7,6 +7,11 @@ use log::{
 };
 
 use chrono::prelude::*;
+use rand::{
+    thread_rng,
+    Rng,
+    distributions::Alphanumeric
+};
 use serde::{
     Serialize,
     Deserialize
@@ -20,7 +25,17 @@ use tokenizer::Tokenizer;
 use data::pg::DataError;
 
 use crate::user::User;
-use crate::validators::password::Password;
+use crate::throttle::{
+    SignInFailures,
+    Throttle,
+    ThrottleResult
+};
+use crate::totp::Totp;
+use crate::oidc::OidcClient;
+use common::password::{
+    Password,
+    PasswordRule
+};
 
 use crate::user_data::{
     // DataError,
@@ -28,6 +43,19 @@ use crate::user_data::{
 };
 
 
+/// number of minutes a password reset token remains valid
+const PASSWORD_RESET_EXPIRY_MINUTES: i64 = 60;
+
+/// number of minutes an account unlock token remains valid
+const ACCOUNT_UNLOCK_EXPIRY_MINUTES: i64 = 60;
+
+/// number of recovery codes generated when two-factor authentication is enabled
+const RECOVERY_CODES: usize = 10;
+
+/// number of minutes a user has to complete sign in at an openid provider
+const OIDC_REQUEST_EXPIRY_MINUTES: i64 = 10;
+
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RegistrationInfo {
     pub token: String,
@@ -37,19 +65,123 @@ pub struct RegistrationInfo {
 
 
 
+/// access token and the refresh token that can be exchanged for a new pair
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionTokens {
+    pub access_token: String,
+    pub refresh_token: String
+}
+
+
+/// outcome of verifying a user's password
+#[derive(Debug)]
+pub enum SignInResult {
+    Authenticated(SessionTokens),
+    /// a second factor is required. the mfa token can only be exchanged at
+    /// mfa verify, or used to enroll if the user has not enrolled yet but a
+    /// tenant they are a member of requires two-factor authentication
+    MfaRequired {
+        mfa_token: String,
+        enroll: bool
+    }
+}
+
+
+/// totp secret to be added to an authenticator app
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MfaEnrollment {
+    pub secret: String,
+    pub uri: String
+}
+
+
 #[derive(Debug)]
 pub enum AuthError {
-    ToBeImplemented(String),
+    DataError(DataError),
     ConfigurationError,
     MailerError,
     ValidationError,
     TokenGenerationError,
-    IncorrectUsernameAndPassword
+    IncorrectUsernameAndPassword,
+    InvalidRefreshToken,
+    RefreshTokenReused,
+    InvalidPasswordResetToken,
+    PasswordPolicy(Vec<PasswordRule>),
+    AccountLocked(DateTime<Utc>),
+    TooManyAttempts(DateTime<Utc>),
+    InvalidUnlockToken,
+    InvalidMfaToken,
+    InvalidMfaCode,
+    MfaAlreadyEnabled,
+    MfaNotEnabled,
+    MfaRequiredByTenant,
+    OidcProviderNotFound,
+    InvalidOidcState,
+    OidcFailed,
+    OidcEmailNotVerified,
+    OidcAccountNotLinked
 }
 
 impl Display for AuthError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        todo!()
+        match self {
+            AuthError::DataError(e) => write!(f, "database error: {:?}", e),
+            AuthError::ConfigurationError => write!(f, "authentication is not configured"),
+            AuthError::MailerError => write!(f, "unable to send email"),
+            AuthError::ValidationError => write!(f, "invalid request"),
+            AuthError::TokenGenerationError => write!(f, "unable to generate token"),
+            AuthError::IncorrectUsernameAndPassword => write!(f, "incorrect username or password"),
+            AuthError::InvalidRefreshToken => write!(f, "invalid refresh token"),
+            AuthError::RefreshTokenReused => write!(f, "refresh token has already been used"),
+            AuthError::InvalidPasswordResetToken => write!(f, "invalid password reset token"),
+            AuthError::PasswordPolicy(failed) => write!(f, "password does not meet the policy: {:?}", failed),
+            AuthError::AccountLocked(until) => write!(f, "account is locked until {}", until.to_rfc3339()),
+            AuthError::TooManyAttempts(until) => write!(f, "too many attempts, retry after {}", until.to_rfc3339()),
+            AuthError::InvalidUnlockToken => write!(f, "invalid unlock token"),
+            AuthError::InvalidMfaToken => write!(f, "invalid two-factor authentication token"),
+            AuthError::InvalidMfaCode => write!(f, "invalid two-factor authentication code"),
+            AuthError::MfaAlreadyEnabled => write!(f, "two-factor authentication is already enabled"),
+            AuthError::MfaNotEnabled => write!(f, "two-factor authentication is not enabled"),
+            AuthError::MfaRequiredByTenant => write!(f, "two-factor authentication is required by the tenant"),
+            AuthError::OidcProviderNotFound => write!(f, "identity provider not found"),
+            AuthError::InvalidOidcState => write!(f, "invalid or expired sign in state"),
+            AuthError::OidcFailed => write!(f, "sign in with the identity provider failed"),
+            AuthError::OidcEmailNotVerified => write!(f, "identity provider has not verified the email address"),
+            AuthError::OidcAccountNotLinked => write!(f, "an account with the email address exists outside of the identity provider's tenant")
+        }
+    }
+}
+
+impl std::error::Error for AuthError {}
+
+impl From<AuthError> for common::Error {
+    fn from(e: AuthError) -> Self {
+        let message = e.to_string();
+        match e {
+            AuthError::DataError(e) => common::Error::from(e),
+            AuthError::ConfigurationError
+            | AuthError::TokenGenerationError => common::Error::Internal(message),
+            AuthError::MailerError => common::Error::Unavailable(message),
+            AuthError::ValidationError
+            | AuthError::PasswordPolicy(_)
+            | AuthError::InvalidMfaCode => common::Error::ValidationError(message),
+            AuthError::IncorrectUsernameAndPassword
+            | AuthError::InvalidRefreshToken
+            | AuthError::RefreshTokenReused
+            | AuthError::InvalidPasswordResetToken
+            | AuthError::InvalidUnlockToken
+            | AuthError::InvalidMfaToken
+            | AuthError::InvalidOidcState
+            | AuthError::OidcFailed => common::Error::Unauthorized(message),
+            AuthError::AccountLocked(_)
+            | AuthError::TooManyAttempts(_) => common::Error::TooManyRequests(message),
+            AuthError::MfaRequiredByTenant
+            | AuthError::OidcEmailNotVerified
+            | AuthError::OidcAccountNotLinked => common::Error::Forbidden(message),
+            AuthError::MfaAlreadyEnabled
+            | AuthError::MfaNotEnabled => common::Error::Conflict(message),
+            AuthError::OidcProviderNotFound => common::Error::NotFound(message)
+        }
     }
 }
 
@@ -59,7 +191,10 @@ pub struct Auth {
     cfg: ApplicationConfiguration,
     user_data: crate::user_data::UserData,
     mailer: Mailer,
-    tokenizer: tokenizer::Tokenizer
+    tokenizer: tokenizer::Tokenizer,
+    password: Password,
+    throttle: Throttle,
+    oidc: Vec<OidcClient>
 }
 
 impl Auth {
@@ -82,11 +217,19 @@ impl Auth {
         // }
 
         let user_data = UserData::new(&cfg, data);
+        let password = Password::new(&cfg.password_policy);
+        let throttle = Throttle::new(&cfg.sign_in);
+        let oidc = cfg.oidc.iter()
+            .map(|provider| OidcClient::new(provider, &cfg.base_url))
+            .collect();
         return Self {
             cfg: cfg,
             user_data: user_data,
             mailer: mailer,
-            tokenizer: tokenizer
+            tokenizer: tokenizer,
+            password: password,
+            throttle: throttle,
+            oidc: oidc
         };
 
         // return Err(AuthError::ConfigurationError);
@@ -98,18 +241,7 @@ impl Auth {
         match self.user_data.register(id, email).await {
             Err(e) => {
                 error!("unable to register: {:?}", e);
-                match e {
-                    DataError::ToBeImplemented(method) => {
-                        debug!("method not implemented: Data::{}", method);
-                        return Err(AuthError::ToBeImplemented(String::from("register")))
-                    }
-                    DataError::ConfigurationError => {
-                        return Err(AuthError::ConfigurationError);
-                    }
-                    DataError::DatabaseError => {
-                        return Err(AuthError::ConfigurationError)
-                    }
-                }
+                return Err(AuthError::DataError(e));
             }
             Ok(token) => {
                 let body = format!("<p>Please click on the link to \
@@ -134,7 +266,7 @@ impl Auth {
         match self.user_data.get_registration_info(token).await {
             Err(e) => {
                 debug!("error: {:?}", e);
-                return Err(AuthError::ToBeImplemented(String::from("get_registration_info")));
+                return Err(AuthError::DataError(e));
             }
             Ok(result) => {
                 debug!("result: {:?}", result);
@@ -148,60 +280,853 @@ impl Auth {
     }
 
     pub async fn complete_registration(&self, token: &str, pw: &str) -> Result<(), AuthError> {
-        if Password::validate(&pw) {
-            match self.user_data.complete_registration(&token, &pw).await {
-                Err(e) => {
-                    error!("unable to complete registration: {:?}", e);
-                    return Err(AuthError::ToBeImplemented(String::from("complete_registration")));
+        let info = self.get_registration_info(&token).await?;
+        if let Err(failed) = self.password.validate(&pw, &info.email) {
+            return Err(AuthError::PasswordPolicy(failed));
+        }
+
+        match self.user_data.complete_registration(&token, &pw).await {
+            Err(e) => {
+                error!("unable to complete registration: {:?}", e);
+                return Err(AuthError::DataError(e));
+            }
+            Ok(_) => {
+                return Ok(());
+            }
+        }
+    }
+
+    /// create a user with a password, registering and completing the
+    /// registration without sending an email
+    pub async fn user_create(&self, id: &uuid::Uuid, email: &str, pw: &str) -> Result<(), AuthError> {
+        if let Err(failed) = self.password.validate(&pw, &email) {
+            return Err(AuthError::PasswordPolicy(failed));
+        }
+
+        let token = match self.user_data.register(id, email).await {
+            Err(e) => {
+                error!("unable to register: {:?}", e);
+                return Err(AuthError::DataError(e));
+            }
+            Ok(token) => token
+        };
+        match self.user_data.complete_registration(&token, &pw).await {
+            Err(e) => {
+                error!("unable to complete registration: {:?}", e);
+                return Err(AuthError::DataError(e));
+            }
+            Ok(_) => {
+                return Ok(());
+            }
+        }
+    }
+
+    /// send an email containing a link to reset the password. nothing is
+    /// sent if there is no user with the email address, the caller is not
+    /// told either way. the mail is sent in the background so that the
+    /// response takes as long for unknown email addresses
+    pub async fn password_forgot(&self, email: &str) -> Result<(), AuthError> {
+        info!("Auth::password_forgot()");
+
+        let expires = Utc::now() + chrono::Duration::minutes(PASSWORD_RESET_EXPIRY_MINUTES);
+        match self.user_data.password_reset_add(&email, &expires).await {
+            Err(e) => {
+                error!("unable to add password reset token: {:?}", e);
+                return Err(AuthError::DataError(e));
+            }
+            Ok(None) => {
+                debug!("password reset requested for unknown email");
+                return Ok(());
+            }
+            Ok(Some(token)) => {
+                let body = format!("<p>Please click on the link to \
+                    reset your password: <a href=\"{base_url}/password/reset/{token}\">{base_url}/password/reset/{token}</a></p>\
+                    <p>The link expires in {minutes} minutes. If you did not request a password reset, you can ignore this email.</p>",
+                    base_url = self.cfg.base_url,
+                    token = token,
+                    minutes = PASSWORD_RESET_EXPIRY_MINUTES
+                );
+                let mailer = self.mailer.clone();
+                let from = self.cfg.mailer.defaults.from.clone();
+                let to = email.to_string();
+                tokio::task::spawn_blocking(move || {
+                    if let Err(e) = mailer.send(&from, &to, "Password Reset", &body) {
+                        error!("failed to send email: {:?}", e);
+                    }
+                });
+                return Ok(());
+            }
+        }
+    }
+
+    /// set a new password using a password reset token. the token can only be
+    /// used once and every session of the user is revoked
+    pub async fn password_reset(&self, token: &str, pw: &str) -> Result<(), AuthError> {
+        info!("Auth::password_reset()");
+
+        match self.user_data.password_reset_get_info(&token).await {
+            Err(e) => {
+                error!("unable to retrieve password reset details: {:?}", e);
+                return Err(AuthError::DataError(e));
+            }
+            Ok(None) => {
+                return Err(AuthError::InvalidPasswordResetToken);
+            }
+            Ok(Some(email)) => {
+                if let Err(failed) = self.password.validate(&pw, &email) {
+                    return Err(AuthError::PasswordPolicy(failed));
                 }
-                Ok(_) => {
-                    return Ok(());
+            }
+        }
+
+        match self.user_data.password_reset_complete(&token, &pw).await {
+            Err(e) => {
+                error!("unable to reset password: {:?}", e);
+                return Err(AuthError::DataError(e));
+            }
+            Ok(None) => {
+                return Err(AuthError::InvalidPasswordResetToken);
+            }
+            Ok(Some(user_id)) => {
+                if let Err(e) = self.user_data.user_sessions_revoke(&user_id).await {
+                    error!("unable to revoke sessions: {:?}", e);
                 }
+                return Ok(());
             }
-        } else {
-            return Err(AuthError::ValidationError);
         }
     }
 
+    /// authenticate a user. failed attempts are counted per email and per
+    /// client ip; every failure increases the delay before the next attempt is
+    /// verified and too many failures lock the account until the lockout
+    /// expires or the account is unlocked using the emailed link
     pub async fn user_authenticate(
         &self,
         email: &str,
-        password: &str
-    ) -> Result<String, AuthError> {
-        match self.user_data.user_authenticate(&email, &password).await {
+        password: &str,
+        ip: Option<&str>
+    ) -> Result<SignInResult, AuthError> {
+        info!("Auth::user_authenticate()");
+
+        let failures = self.sign_in_throttle(&email, ip).await?;
+
+        let authentic = match self.user_data.user_authenticate(&email, &password).await {
             Err(e) => {
                 error!("unable to authenticate user: {:?}", e);
-                return Err(AuthError::ToBeImplemented(String::from("user_authenticate")));
-            }
-            Ok(authentic) => {
-                if authentic {
-
-                    if let Ok(user) = self.user_data.get_user(&email).await {
-                        let user_id = user.id();
-                        if let Ok(tenant_id) = self.user_data.user_default_tenant_fetch(&user_id).await {
-                            if let Ok(token) = self.tokenizer.generate(
-                                &email,
-                                &tenant_id
-                            ) {
-                                return Ok(token);
-                            } else {
-                                return Err(AuthError::TokenGenerationError);
-                            }
-                        } else {
-                            error!("unable to fetch user default tenant");
-                            return Err(AuthError::TokenGenerationError);
-                        }
-                    } else {
-                        error!("unable to fetch user");
+                return Err(AuthError::DataError(e));
+            }
+            Ok(authentic) => authentic
+        };
+
+        if !authentic {
+            self.sign_in_attempt(&email, ip, &failures, false).await;
+            return Err(AuthError::IncorrectUsernameAndPassword);
+        }
+
+        if let Ok(user) = self.user_data.get_user(&email).await {
+            let user_id = user.id();
+            if let Ok(tenant_id) = self.user_data.user_default_tenant_fetch(&user_id).await {
+                // a success resets the failures of the email, so it is only
+                // recorded once no second factor remains to be verified
+                let result = self.sign_in_complete(&user_id, &email, &tenant_id).await?;
+                if let SignInResult::Authenticated(_) = result {
+                    self.sign_in_attempt(&email, ip, &failures, true).await;
+                }
+                return Ok(result);
+            } else {
+                error!("unable to fetch user default tenant");
+                return Err(AuthError::TokenGenerationError);
+            }
+        } else {
+            error!("unable to fetch user");
+            return Err(AuthError::TokenGenerationError);
+        }
+    }
+
+    /// finish the sign in of an authenticated user. users enrolled in
+    /// two-factor authentication, or members of a tenant that requires it,
+    /// receive an mfa pending token instead of session tokens
+    async fn sign_in_complete(
+        &self,
+        user_id: &uuid::Uuid,
+        email: &str,
+        tenant_id: &uuid::Uuid
+    ) -> Result<SignInResult, AuthError> {
+        let enrolled = match self.user_data.user_mfa_fetch(&user_id).await {
+            Err(e) => {
+                error!("unable to fetch two-factor authentication details: {:?}", e);
+                return Err(AuthError::DataError(e));
+            }
+            Ok(mfa) => mfa.is_some_and(|mfa| mfa.confirmed)
+        };
+        let required = enrolled || match self.user_data.user_mfa_required(&user_id).await {
+            Err(e) => {
+                error!("unable to check if two-factor authentication is required: {:?}", e);
+                return Err(AuthError::DataError(e));
+            }
+            Ok(required) => required
+        };
+
+        if required {
+            match self.tokenizer.generate_mfa_pending(&email, &tenant_id) {
+                Err(e) => {
+                    error!("unable to generate mfa pending token: {:?}", e);
+                    return Err(AuthError::TokenGenerationError);
+                }
+                Ok(mfa_token) => {
+                    return Ok(SignInResult::MfaRequired {
+                        mfa_token: mfa_token,
+                        enroll: !enrolled
+                    });
+                }
+            }
+        }
+
+        // every sign in starts a new refresh token family
+        let tokens = self.session_tokens(
+            &uuid::Uuid::new_v4(),
+            &user_id,
+            &email,
+            &tenant_id
+        ).await?;
+        return Ok(SignInResult::Authenticated(tokens));
+    }
+
+    /// exchange an mfa pending token and a totp or recovery code for session
+    /// tokens. failed codes count towards the sign in lockout of the user and
+    /// the token can only be exchanged once
+    pub async fn mfa_verify(
+        &self,
+        mfa_token: &str,
+        code: &str,
+        ip: Option<&str>
+    ) -> Result<SessionTokens, AuthError> {
+        info!("Auth::mfa_verify()");
+
+        let claims = self.mfa_pending(&mfa_token).await?;
+        let email = claims.email();
+        let failures = self.sign_in_throttle(&email, ip).await?;
+
+        let user = match self.user_data.get_user(&email).await {
+            Err(e) => {
+                error!("unable to fetch user: {:?}", e);
+                return Err(AuthError::InvalidMfaToken);
+            }
+            Ok(user) => user
+        };
+
+        let verified = self.mfa_code_verify(&user.id(), &code).await?;
+        self.sign_in_attempt(&email, ip, &failures, verified).await;
+        if !verified {
+            return Err(AuthError::InvalidMfaCode);
+        }
+
+        self.mfa_pending_use(&claims).await?;
+        return self.session_tokens(
+            &uuid::Uuid::new_v4(),
+            &user.id(),
+            &email,
+            &claims.tenant()
+        ).await;
+    }
+
+    /// generate a totp secret for a user. the secret is not used until it
+    /// has been confirmed with a code from the authenticator app
+    pub async fn mfa_enroll(&self, email: &str) -> Result<MfaEnrollment, AuthError> {
+        info!("Auth::mfa_enroll()");
+
+        let user = self.get_user(&email).await?;
+        match self.user_data.user_mfa_fetch(&user.id()).await {
+            Err(e) => {
+                error!("unable to fetch two-factor authentication details: {:?}", e);
+                return Err(AuthError::DataError(e));
+            }
+            Ok(Some(mfa)) if mfa.confirmed => {
+                return Err(AuthError::MfaAlreadyEnabled);
+            }
+            Ok(_) => {}
+        }
+
+        let totp = Totp::generate();
+        if let Err(e) = self.user_data.user_mfa_enroll(&user.id(), &totp.base32()).await {
+            error!("unable to store totp secret: {:?}", e);
+            return Err(AuthError::DataError(e));
+        }
+        return Ok(MfaEnrollment {
+            secret: totp.base32(),
+            uri: totp.uri(&self.cfg.jwt.issuer, &email)
+        });
+    }
+
+    /// enroll using an mfa pending token, for users signing in to a tenant
+    /// that requires two-factor authentication
+    pub async fn mfa_enroll_pending(&self, mfa_token: &str) -> Result<MfaEnrollment, AuthError> {
+        info!("Auth::mfa_enroll_pending()");
+
+        let claims = self.mfa_pending(&mfa_token).await?;
+        return self.mfa_enroll(&claims.email()).await;
+    }
+
+    /// confirm enrollment with a code from the authenticator app. returns
+    /// the recovery codes, these are only shown once
+    pub async fn mfa_confirm(&self, email: &str, code: &str) -> Result<Vec<String>, AuthError> {
+        info!("Auth::mfa_confirm()");
+
+        let user = self.get_user(&email).await?;
+        let totp = match self.user_data.user_mfa_fetch(&user.id()).await {
+            Err(e) => {
+                error!("unable to fetch two-factor authentication details: {:?}", e);
+                return Err(AuthError::DataError(e));
+            }
+            Ok(None) => {
+                return Err(AuthError::MfaNotEnabled);
+            }
+            Ok(Some(mfa)) if mfa.confirmed => {
+                return Err(AuthError::MfaAlreadyEnabled);
+            }
+            Ok(Some(mfa)) => {
+                match Totp::from_base32(&mfa.secret) {
+                    None => {
+                        error!("unable to decode totp secret");
+                        return Err(AuthError::DataError(DataError::DatabaseError));
+                    }
+                    Some(totp) => totp
+                }
+            }
+        };
+
+        if !self.totp_verify(&user.id(), &totp, &code).await? {
+            return Err(AuthError::InvalidMfaCode);
+        }
+
+        let codes: Vec<String> = (0..RECOVERY_CODES)
+            .map(|_| {
+                return thread_rng()
+                    .sample_iter(&Alphanumeric)
+                    .take(10)
+                    .map(|c| char::from(c).to_ascii_lowercase())
+                    .collect();
+            })
+            .collect();
+        let digests: Vec<String> = codes.iter()
+            .map(|c| Tokenizer::digest(c))
+            .collect();
+        if let Err(e) = self.user_data.user_mfa_confirm(&user.id(), &digests).await {
+            error!("unable to confirm two-factor authentication: {:?}", e);
+            return Err(AuthError::DataError(e));
+        }
+        return Ok(codes);
+    }
+
+    /// confirm enrollment using an mfa pending token. returns the recovery
+    /// codes and the session tokens for the pending sign in. failed codes
+    /// count towards the sign in lockout of the user
+    pub async fn mfa_confirm_pending(
+        &self,
+        mfa_token: &str,
+        code: &str,
+        ip: Option<&str>
+    ) -> Result<(Vec<String>, SessionTokens), AuthError> {
+        info!("Auth::mfa_confirm_pending()");
+
+        let claims = self.mfa_pending(&mfa_token).await?;
+        let email = claims.email();
+        let failures = self.sign_in_throttle(&email, ip).await?;
+
+        let result = self.mfa_confirm(&email, &code).await;
+        if let Err(AuthError::InvalidMfaCode) = result {
+            self.sign_in_attempt(&email, ip, &failures, false).await;
+        }
+        let codes = result?;
+        self.sign_in_attempt(&email, ip, &failures, true).await;
+
+        self.mfa_pending_use(&claims).await?;
+        let user = self.get_user(&email).await?;
+        let tokens = self.session_tokens(
+            &uuid::Uuid::new_v4(),
+            &user.id(),
+            &email,
+            &claims.tenant()
+        ).await?;
+        return Ok((codes, tokens));
+    }
+
+    /// turn off two-factor authentication. requires a current totp or
+    /// recovery code, and is refused if a tenant of the user requires it
+    pub async fn mfa_disable(&self, email: &str, code: &str) -> Result<(), AuthError> {
+        info!("Auth::mfa_disable()");
+
+        let user = self.get_user(&email).await?;
+        match self.user_data.user_mfa_required(&user.id()).await {
+            Err(e) => {
+                error!("unable to check if two-factor authentication is required: {:?}", e);
+                return Err(AuthError::DataError(e));
+            }
+            Ok(true) => {
+                return Err(AuthError::MfaRequiredByTenant);
+            }
+            Ok(false) => {}
+        }
+
+        if !self.mfa_code_verify(&user.id(), &code).await? {
+            return Err(AuthError::InvalidMfaCode);
+        }
+        if let Err(e) = self.user_data.user_mfa_disable(&user.id()).await {
+            error!("unable to disable two-factor authentication: {:?}", e);
+            return Err(AuthError::DataError(e));
+        }
+        return Ok(());
+    }
+
+    /// verify an mfa pending token, tokens that have been exchanged for a
+    /// session are rejected
+    async fn mfa_pending(&self, mfa_token: &str) -> Result<tokenizer::Claims, AuthError> {
+        let claims = match self.tokenizer.validate_mfa_pending(&mfa_token) {
+            Err(e) => {
+                info!("invalid mfa pending token: {}", e);
+                return Err(AuthError::InvalidMfaToken);
+            }
+            Ok(claims) => claims
+        };
+        match self.user_data.mfa_pending_used(&claims.jti()).await {
+            Err(e) => {
+                error!("unable to check mfa pending token: {:?}", e);
+                return Err(AuthError::DataError(e));
+            }
+            Ok(true) => {
+                info!("mfa pending token has already been used");
+                return Err(AuthError::InvalidMfaToken);
+            }
+            Ok(false) => {
+                return Ok(claims);
+            }
+        }
+    }
+
+    /// mark an mfa pending token as exchanged for a session
+    async fn mfa_pending_use(&self, claims: &tokenizer::Claims) -> Result<(), AuthError> {
+        match self.user_data.mfa_pending_use(&claims.jti(), &claims.expiry()).await {
+            Err(e) => {
+                error!("unable to record mfa pending token: {:?}", e);
+                return Err(AuthError::DataError(e));
+            }
+            Ok(false) => {
+                info!("mfa pending token has already been used");
+                return Err(AuthError::InvalidMfaToken);
+            }
+            Ok(true) => {
+                return Ok(());
+            }
+        }
+    }
+
+    /// refuse a sign in step while the account is locked or the client has
+    /// failed too often, otherwise wait for the delay earned by earlier
+    /// failures. returns the failures counted so far
+    async fn sign_in_throttle(
+        &self,
+        email: &str,
+        ip: Option<&str>
+    ) -> Result<SignInFailures, AuthError> {
+        let now = Utc::now();
+        let failures = match self.user_data.sign_in_failures(
+            &email,
+            ip,
+            &self.throttle.window_start(&now)
+        ).await {
+            Err(e) => {
+                error!("unable to retrieve failed sign in attempts: {:?}", e);
+                return Err(AuthError::DataError(e));
+            }
+            Ok(failures) => failures
+        };
+
+        match self.throttle.check(&failures, &now) {
+            ThrottleResult::Locked(until) => {
+                return Err(AuthError::AccountLocked(until));
+            }
+            ThrottleResult::Refused(until) => {
+                return Err(AuthError::TooManyAttempts(until));
+            }
+            ThrottleResult::Allowed => {
+                tokio::time::sleep(self.throttle.delay(&failures)).await;
+                return Ok(failures);
+            }
+        }
+    }
+
+    /// record the outcome of a sign in step. the unlock email is sent when
+    /// a failure locks the account
+    async fn sign_in_attempt(
+        &self,
+        email: &str,
+        ip: Option<&str>,
+        failures: &SignInFailures,
+        success: bool
+    ) {
+        if let Err(e) = self.user_data.sign_in_attempt_add(&email, ip, success).await {
+            error!("unable to record sign in attempt: {:?}", e);
+        }
+        if !success && self.throttle.locks(&failures) {
+            info!("account locked after {} failed sign in attempts", failures.email + 1);
+            if let Err(e) = self.send_unlock_email(&email).await {
+                error!("unable to send unlock email: {:?}", e);
+            }
+        }
+    }
+
+    /// verify a totp code or a recovery code against the confirmed secret
+    /// of a user
+    async fn mfa_code_verify(
+        &self,
+        user_id: &uuid::Uuid,
+        code: &str
+    ) -> Result<bool, AuthError> {
+        let totp = match self.user_data.user_mfa_fetch(&user_id).await {
+            Err(e) => {
+                error!("unable to fetch two-factor authentication details: {:?}", e);
+                return Err(AuthError::DataError(e));
+            }
+            Ok(Some(mfa)) if mfa.confirmed => {
+                match Totp::from_base32(&mfa.secret) {
+                    None => {
+                        error!("unable to decode totp secret");
+                        return Err(AuthError::DataError(DataError::DatabaseError));
+                    }
+                    Some(totp) => totp
+                }
+            }
+            Ok(_) => {
+                return Err(AuthError::MfaNotEnabled);
+            }
+        };
+
+        let code = code.trim();
+        if code.chars().all(|c| c.is_ascii_digit()) {
+            return self.totp_verify(&user_id, &totp, &code).await;
+        }
+        match self.user_data.user_mfa_recovery_use(&user_id, &code.to_ascii_lowercase()).await {
+            Err(e) => {
+                error!("unable to use recovery code: {:?}", e);
+                return Err(AuthError::DataError(e));
+            }
+            Ok(used) => {
+                return Ok(used);
+            }
+        }
+    }
+
+    /// verify a totp code. a code is rejected if it, or a later code, has
+    /// already been used
+    async fn totp_verify(
+        &self,
+        user_id: &uuid::Uuid,
+        totp: &Totp,
+        code: &str
+    ) -> Result<bool, AuthError> {
+        match totp.verify(&code, Utc::now().timestamp()) {
+            None => {
+                return Ok(false);
+            }
+            Some(step) => {
+                match self.user_data.user_mfa_step_use(&user_id, &step).await {
+                    Err(e) => {
+                        error!("unable to record totp code: {:?}", e);
+                        return Err(AuthError::DataError(e));
+                    }
+                    Ok(used) => {
+                        return Ok(used);
+                    }
+                }
+            }
+        }
+    }
+
+    /// send an email containing a link to unlock the account
+    async fn send_unlock_email(&self, email: &str) -> Result<(), AuthError> {
+        let expires = Utc::now() + chrono::Duration::minutes(ACCOUNT_UNLOCK_EXPIRY_MINUTES);
+        match self.user_data.account_unlock_add(&email, &expires).await {
+            Err(e) => {
+                error!("unable to add account unlock token: {:?}", e);
+                return Err(AuthError::DataError(e));
+            }
+            Ok(None) => {
+                return Ok(());
+            }
+            Ok(Some(token)) => {
+                let body = format!("<p>Your account has been locked after too many failed sign in attempts. \
+                    Please click on the link to unlock it: <a href=\"{base_url}/unlock/{token}\">{base_url}/unlock/{token}</a></p>\
+                    <p>If you did not try to sign in, please reset your password.</p>",
+                    base_url = self.cfg.base_url,
+                    token = token
+                );
+                if let Err(e) = self.mailer.send(
+                    &self.cfg.mailer.defaults.from,
+                    &email,
+                    "Account Locked", &body) {
+                        error!("failed to send email: {:?}", e);
+                        return Err(AuthError::MailerError)
+                }
+                return Ok(());
+            }
+        }
+    }
+
+    /// unlock an account using the token sent when it was locked
+    pub async fn account_unlock(&self, token: &str) -> Result<(), AuthError> {
+        info!("Auth::account_unlock()");
+
+        match self.user_data.account_unlock(&token).await {
+            Err(e) => {
+                error!("unable to unlock account: {:?}", e);
+                return Err(AuthError::DataError(e));
+            }
+            Ok(false) => {
+                return Err(AuthError::InvalidUnlockToken);
+            }
+            Ok(true) => {
+                return Ok(());
+            }
+        }
+    }
+
+    /// start signing in at an openid provider. returns the url the user has
+    /// to be redirected to
+    pub async fn oidc_authorize(&self, provider_id: &str) -> Result<String, AuthError> {
+        info!("Auth::oidc_authorize()");
+
+        let client = self.oidc_client(&provider_id)?;
+        let metadata = match client.discover().await {
+            Err(e) => {
+                error!("unable to retrieve openid provider metadata: {}", e);
+                return Err(AuthError::OidcFailed);
+            }
+            Ok(metadata) => metadata
+        };
+        let request = match client.authorization_request(&metadata) {
+            Err(e) => {
+                error!("unable to create authorization request: {}", e);
+                return Err(AuthError::OidcFailed);
+            }
+            Ok(request) => request
+        };
+
+        let expires = Utc::now() + chrono::Duration::minutes(OIDC_REQUEST_EXPIRY_MINUTES);
+        if let Err(e) = self.user_data.oidc_request_add(
+            &request.state,
+            &provider_id,
+            &request.code_verifier,
+            &request.nonce,
+            &expires
+        ).await {
+            error!("unable to store authorization request: {:?}", e);
+            return Err(AuthError::DataError(e));
+        }
+        return Ok(request.url);
+    }
+
+    /// complete signing in at an openid provider. a user signing in for the
+    /// first time is linked to the member of the provider's tenant with the
+    /// same verified email address, or created and added to the tenant. the
+    /// sign in is throttled and requires two-factor authentication like a
+    /// sign in with a password
+    pub async fn oidc_callback(
+        &self,
+        state: &str,
+        code: &str,
+        ip: Option<&str>
+    ) -> Result<SignInResult, AuthError> {
+        info!("Auth::oidc_callback()");
+
+        let request = match self.user_data.oidc_request_use(&state).await {
+            Err(e) => {
+                error!("unable to retrieve authorization request: {:?}", e);
+                return Err(AuthError::DataError(e));
+            }
+            Ok(Some(request)) if request.expires > Utc::now() => request,
+            Ok(_) => {
+                return Err(AuthError::InvalidOidcState);
+            }
+        };
+
+        let client = self.oidc_client(&request.provider_id)?;
+        let provider = client.provider();
+        let claims = match client.authenticate(
+            &code,
+            &request.code_verifier,
+            &request.nonce
+        ).await {
+            Err(e) => {
+                info!("openid connect sign in failed: {}", e);
+                return Err(AuthError::OidcFailed);
+            }
+            Ok(claims) => claims
+        };
+
+        let user_id = match self.user_data.user_identity_fetch(&provider.issuer, &claims.sub).await {
+            Err(e) => {
+                error!("unable to fetch user identity: {:?}", e);
+                return Err(AuthError::DataError(e));
+            }
+            Ok(Some(user_id)) => user_id,
+            Ok(None) => {
+                // only link or create users from addresses the provider has verified
+                let email = match &claims.email {
+                    Some(email) if claims.email_verified == Some(true) => email.clone(),
+                    _ => {
+                        return Err(AuthError::OidcEmailNotVerified);
+                    }
+                };
+                match self.user_data.user_identity_provision(
+                    &email,
+                    &claims.given_name.clone().unwrap_or_default(),
+                    &claims.family_name.clone().unwrap_or_default(),
+                    &provider.issuer,
+                    &claims.sub,
+                    &provider.tenant_id
+                ).await {
+                    Err(e) => {
+                        error!("unable to provision user: {:?}", e);
+                        return Err(AuthError::DataError(e));
+                    }
+                    Ok(None) => {
+                        info!("not linking identity to a user outside of the provider's tenant");
+                        return Err(AuthError::OidcAccountNotLinked);
+                    }
+                    Ok(Some(user_id)) => user_id
+                }
+            }
+        };
+
+        let user = match self.user_data.get_user_by_id(&user_id).await {
+            Err(e) => {
+                error!("unable to fetch user: {:?}", e);
+                return Err(AuthError::TokenGenerationError);
+            }
+            Ok(user) => user
+        };
+        self.sign_in_throttle(&user.email(), ip).await?;
+        return self.sign_in_complete(&user_id, &user.email(), &provider.tenant_id).await;
+    }
+
+    fn oidc_client(&self, provider_id: &str) -> Result<&OidcClient, AuthError> {
+        match self.oidc.iter().find(|client| client.provider().id == provider_id) {
+            None => {
+                return Err(AuthError::OidcProviderNotFound);
+            }
+            Some(client) => {
+                return Ok(client);
+            }
+        }
+    }
+
+    /// exchange a refresh token for a new access token and refresh token.
+    /// a refresh token can only be used once, presenting it a second time
+    /// revokes every token issued in its family
+    pub async fn token_refresh(
+        &self,
+        refresh_token: &str
+    ) -> Result<SessionTokens, AuthError> {
+        info!("Auth::token_refresh()");
+
+        match self.user_data.refresh_token_use(&refresh_token).await {
+            Err(e) => {
+                error!("unable to use refresh token: {:?}", e);
+                return Err(AuthError::DataError(e));
+            }
+            Ok(None) => {
+                return Err(AuthError::InvalidRefreshToken);
+            }
+            Ok(Some(info)) => {
+                if info.used || info.revoked {
+                    error!("refresh token reuse detected, revoking token family: {:?}", info.family_id);
+                    if let Err(e) = self.user_data.refresh_token_family_revoke(&info.family_id).await {
+                        error!("unable to revoke refresh token family: {:?}", e);
+                    }
+                    return Err(AuthError::RefreshTokenReused);
+                }
+
+                if info.expires <= Utc::now() {
+                    return Err(AuthError::InvalidRefreshToken);
+                }
+
+                match self.user_data.get_user_by_id(&info.user_id).await {
+                    Err(e) => {
+                        error!("unable to fetch user: {:?}", e);
                         return Err(AuthError::TokenGenerationError);
                     }
-                } else {
-                    return Err(AuthError::IncorrectUsernameAndPassword);
+                    Ok(user) => {
+                        return self.session_tokens(
+                            &info.family_id,
+                            &info.user_id,
+                            &user.email(),
+                            &info.tenant_id
+                        ).await;
+                    }
                 }
             }
         }
     }
 
+    /// revoke the session a token was issued for. if everywhere is set,
+    /// every session of the user is revoked
+    pub async fn sign_out(
+        &self,
+        user_id: &uuid::Uuid,
+        session_id: &uuid::Uuid,
+        everywhere: bool
+    ) -> Result<(), AuthError> {
+        info!("Auth::sign_out()");
+
+        let result = if everywhere {
+            self.user_data.user_sessions_revoke(&user_id).await
+        } else {
+            self.user_data.refresh_token_family_revoke(&session_id).await
+        };
+        if let Err(e) = result {
+            error!("unable to revoke session: {:?}", e);
+            return Err(AuthError::DataError(e));
+        }
+        return Ok(());
+    }
+
+    /// generate an access token and add a new refresh token to the token family.
+    /// the token family identifies the session
+    async fn session_tokens(
+        &self,
+        family_id: &uuid::Uuid,
+        user_id: &uuid::Uuid,
+        email: &str,
+        tenant_id: &uuid::Uuid
+    ) -> Result<SessionTokens, AuthError> {
+        let access_token = match self.tokenizer.generate(&email, &tenant_id, &family_id) {
+            Err(e) => {
+                error!("unable to generate access token: {:?}", e);
+                return Err(AuthError::TokenGenerationError);
+            }
+            Ok(token) => token
+        };
+
+        let expires = Utc::now() + chrono::Duration::seconds(self.cfg.jwt.refresh_expiry);
+        match self.user_data.refresh_token_add(
+            &family_id,
+            &user_id,
+            &tenant_id,
+            &expires
+        ).await {
+            Err(e) => {
+                error!("unable to add refresh token: {:?}", e);
+                return Err(AuthError::TokenGenerationError);
+            }
+            Ok(refresh_token) => {
+                return Ok(SessionTokens {
+                    access_token: access_token,
+                    refresh_token: refresh_token
+                });
+            }
+        }
+    }
+
     // pub async fn user_current_tenant(
     //     &self,
     //     email: &str,
@@ -224,7 +1149,7 @@ impl Auth {
         match self.user_data.get_user(&email).await {
             Err(e) => {
                 error!("unable to authenticate user: {:?}", e);
-                return Err(AuthError::ToBeImplemented(String::from("get_user")));
+                return Err(AuthError::DataError(e));
             }
             Ok(user) => {
                 return Ok(user);
@@ -1,6 +1,8 @@
 pub mod user;
-pub mod validators;
 pub mod auth;
+pub mod throttle;
+pub mod totp;
+pub mod oidc;
 mod user_data;
 
 #[cfg(test)]
@@ -0,0 +1,742 @@
+use log::{
+    info,
+    debug,
+    error
+};
+
+use std::sync::{
+    Arc,
+    RwLock
+};
+use std::time::{
+    Duration,
+    Instant
+};
+
+use serde::{
+    Serialize,
+    Deserialize
+};
+use rand::{
+    thread_rng,
+    Rng,
+    distributions::Alphanumeric
+};
+use sha2::{
+    Digest,
+    Sha256
+};
+use base64::{
+    Engine,
+    engine::general_purpose::URL_SAFE_NO_PAD
+};
+use jsonwebtoken::{
+    Algorithm,
+    DecodingKey,
+    Validation,
+    decode,
+    decode_header,
+    jwk::{
+        Jwk,
+        JwkSet
+    }
+};
+use reqwest::Url;
+
+use configuration::OidcProvider;
+
+
+/// algorithms accepted for id tokens. shared secret algorithms are rejected
+const ID_TOKEN_ALGORITHMS: [Algorithm; 6] = [
+    Algorithm::RS256,
+    Algorithm::RS384,
+    Algorithm::RS512,
+    Algorithm::ES256,
+    Algorithm::ES384,
+    Algorithm::EdDSA
+];
+
+/// number of seconds of clock skew allowed when validating id tokens
+const LEEWAY_SECONDS: u64 = 60;
+
+/// number of seconds provider metadata and signing keys are cached
+const DISCOVERY_CACHE_SECONDS: u64 = 3600;
+
+/// minimum number of seconds between fetching the signing keys again when an
+/// id token is signed with an unknown key, in case the provider rotated them
+const JWKS_REFRESH_SECONDS: u64 = 60;
+
+
+#[derive(Debug)]
+pub enum OidcError {
+    /// provider metadata or keys could not be retrieved
+    Discovery(String),
+    /// the authorization code could not be exchanged
+    Exchange(String),
+    InvalidIdToken(String)
+}
+
+impl std::fmt::Display for OidcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OidcError::Discovery(message) => write!(f, "discovery failed: {}", message),
+            OidcError::Exchange(message) => write!(f, "code exchange failed: {}", message),
+            OidcError::InvalidIdToken(message) => write!(f, "invalid id token: {}", message)
+        }
+    }
+}
+
+
+/// subset of the provider metadata used by the authorization code flow
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderMetadata {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub jwks_uri: String
+}
+
+
+/// authorization request sent to the provider. state, nonce and the pkce
+/// code verifier have to be kept server-side until the callback
+#[derive(Debug, Clone)]
+pub struct AuthorizationRequest {
+    pub url: String,
+    pub state: String,
+    pub nonce: String,
+    pub code_verifier: String
+}
+
+
+/// claims of a verified id token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdentityClaims {
+    pub sub: String,
+    #[serde(default)]
+    pub email: Option<String>,
+    #[serde(default)]
+    pub email_verified: Option<bool>,
+    #[serde(default)]
+    pub given_name: Option<String>,
+    #[serde(default)]
+    pub family_name: Option<String>,
+    #[serde(default)]
+    pub nonce: Option<String>
+}
+
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: String
+}
+
+
+/// value cached with the time it was fetched
+type Cached<T> = Arc<RwLock<Option<(Instant, T)>>>;
+
+
+/// openid connect relying party for a single provider, using the
+/// authorization code flow with pkce. provider metadata and signing keys are
+/// cached, clones share the cache
+#[derive(Debug, Clone)]
+pub struct OidcClient {
+    provider: OidcProvider,
+    redirect_uri: String,
+    http: reqwest::Client,
+    metadata: Cached<ProviderMetadata>,
+    jwks: Cached<JwkSet>
+}
+
+impl OidcClient {
+
+    pub fn new(provider: &OidcProvider, base_url: &str) -> Self {
+        let redirect_uri = match &provider.redirect_uri {
+            Some(redirect_uri) => redirect_uri.clone(),
+            None => format!("{}/oidc/callback", base_url.trim_end_matches('/'))
+        };
+        return Self {
+            provider: provider.clone(),
+            redirect_uri: redirect_uri,
+            http: reqwest::Client::new(),
+            metadata: Arc::new(RwLock::new(None)),
+            jwks: Arc::new(RwLock::new(None))
+        };
+    }
+
+    pub fn provider(&self) -> OidcProvider {
+        return self.provider.clone();
+    }
+
+    /// retrieve the provider metadata
+    pub async fn discover(&self) -> Result<ProviderMetadata, OidcError> {
+        info!("OidcClient::discover()");
+
+        if let Some(metadata) = Self::cached(&self.metadata, DISCOVERY_CACHE_SECONDS) {
+            return Ok(metadata);
+        }
+
+        let url = format!("{}/.well-known/openid-configuration", self.provider.issuer.trim_end_matches('/'));
+        let metadata: ProviderMetadata = self.get_json(&url).await?;
+        if metadata.issuer.trim_end_matches('/') != self.provider.issuer.trim_end_matches('/') {
+            error!("provider metadata issuer mismatch: {}", metadata.issuer);
+            return Err(OidcError::Discovery(String::from("issuer mismatch")));
+        }
+        Self::store(&self.metadata, &metadata);
+        return Ok(metadata);
+    }
+
+    /// retrieve the signing keys of the provider. cached keys older than
+    /// max_age seconds are fetched again
+    async fn jwks(&self, metadata: &ProviderMetadata, max_age: u64) -> Result<JwkSet, OidcError> {
+        if let Some(jwks) = Self::cached(&self.jwks, max_age) {
+            return Ok(jwks);
+        }
+
+        let jwks: JwkSet = self.get_json(&metadata.jwks_uri).await?;
+        Self::store(&self.jwks, &jwks);
+        return Ok(jwks);
+    }
+
+    fn cached<T: Clone>(cache: &Cached<T>, max_age: u64) -> Option<T> {
+        match cache.read() {
+            Ok(cached) => {
+                return cached.as_ref()
+                    .filter(|(fetched, _)| fetched.elapsed() < Duration::from_secs(max_age))
+                    .map(|(_, value)| value.clone());
+            }
+            Err(_) => {
+                return None;
+            }
+        }
+    }
+
+    fn store<T: Clone>(cache: &Cached<T>, value: &T) {
+        if let Ok(mut cached) = cache.write() {
+            *cached = Some((Instant::now(), value.clone()));
+        }
+    }
+
+    /// key an id token is signed with. without a key id the key set has to
+    /// contain a single key
+    fn signing_key<'a>(jwks: &'a JwkSet, kid: &Option<String>) -> Option<&'a Jwk> {
+        match kid {
+            Some(kid) => {
+                return jwks.find(kid);
+            }
+            None if jwks.keys.len() == 1 => {
+                return jwks.keys.first();
+            }
+            None => {
+                return None;
+            }
+        }
+    }
+
+    /// build the url the user is redirected to for signing in
+    pub fn authorization_request(
+        &self,
+        metadata: &ProviderMetadata
+    ) -> Result<AuthorizationRequest, OidcError> {
+        let state = Self::random(32);
+        let nonce = Self::random(32);
+        let code_verifier = Self::random(64);
+
+        let mut url = match Url::parse(&metadata.authorization_endpoint) {
+            Err(e) => {
+                error!("invalid authorization endpoint: {:?}", e);
+                return Err(OidcError::Discovery(String::from("invalid authorization endpoint")));
+            }
+            Ok(url) => url
+        };
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &self.provider.client_id)
+            .append_pair("redirect_uri", &self.redirect_uri)
+            .append_pair("scope", &self.provider.scopes.join(" "))
+            .append_pair("state", &state)
+            .append_pair("nonce", &nonce)
+            .append_pair("code_challenge", &Self::pkce_challenge(&code_verifier))
+            .append_pair("code_challenge_method", "S256");
+
+        return Ok(AuthorizationRequest {
+            url: url.to_string(),
+            state: state,
+            nonce: nonce,
+            code_verifier: code_verifier
+        });
+    }
+
+    /// exchange the authorization code and verify the id token
+    pub async fn authenticate(
+        &self,
+        code: &str,
+        code_verifier: &str,
+        nonce: &str
+    ) -> Result<IdentityClaims, OidcError> {
+        info!("OidcClient::authenticate()");
+
+        let metadata = self.discover().await?;
+        let id_token = self.exchange(&metadata, &code, &code_verifier).await?;
+        return self.verify_id_token(&metadata, &id_token, &nonce).await;
+    }
+
+    async fn exchange(
+        &self,
+        metadata: &ProviderMetadata,
+        code: &str,
+        code_verifier: &str
+    ) -> Result<String, OidcError> {
+        let mut form: Vec<(&str, &str)> = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", &self.redirect_uri),
+            ("client_id", &self.provider.client_id),
+            ("code_verifier", code_verifier)
+        ];
+        if let Some(secret) = &self.provider.client_secret {
+            form.push(("client_secret", secret));
+        }
+
+        let response = match self.http.post(&metadata.token_endpoint)
+            .form(&form)
+            .send()
+            .await {
+            Err(e) => {
+                error!("unable to send token request: {:?}", e);
+                return Err(OidcError::Exchange(String::from("token endpoint unreachable")));
+            }
+            Ok(response) => response
+        };
+        if !response.status().is_success() {
+            let status = response.status();
+            debug!("token endpoint response: {:?}", response.text().await);
+            return Err(OidcError::Exchange(format!("token endpoint returned {}", status)));
+        }
+        match response.json::<TokenResponse>().await {
+            Err(e) => {
+                error!("unable to parse token response: {:?}", e);
+                return Err(OidcError::Exchange(String::from("invalid token response")));
+            }
+            Ok(tokens) => {
+                return Ok(tokens.id_token);
+            }
+        }
+    }
+
+    /// verify the signature, issuer, audience, expiry and nonce of an id token
+    pub async fn verify_id_token(
+        &self,
+        metadata: &ProviderMetadata,
+        id_token: &str,
+        nonce: &str
+    ) -> Result<IdentityClaims, OidcError> {
+        let header = match decode_header(id_token) {
+            Err(e) => {
+                return Err(OidcError::InvalidIdToken(format!("{}", e)));
+            }
+            Ok(header) => header
+        };
+        if !ID_TOKEN_ALGORITHMS.contains(&header.alg) {
+            return Err(OidcError::InvalidIdToken(format!("unsupported algorithm {:?}", header.alg)));
+        }
+
+        let mut jwks = self.jwks(&metadata, DISCOVERY_CACHE_SECONDS).await?;
+        if Self::signing_key(&jwks, &header.kid).is_none() {
+            // the provider may have rotated its keys since they were cached
+            jwks = self.jwks(&metadata, JWKS_REFRESH_SECONDS).await?;
+        }
+        let key = match Self::signing_key(&jwks, &header.kid).map(DecodingKey::from_jwk) {
+            None => {
+                return Err(OidcError::InvalidIdToken(String::from("signing key not found")));
+            }
+            Some(Err(e)) => {
+                return Err(OidcError::InvalidIdToken(format!("unusable signing key: {}", e)));
+            }
+            Some(Ok(key)) => key
+        };
+
+        let mut validation = Validation::new(header.alg);
+        validation.leeway = LEEWAY_SECONDS;
+        validation.set_issuer(&[&metadata.issuer]);
+        validation.set_audience(&[&self.provider.client_id]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+
+        let claims = match decode::<IdentityClaims>(id_token, &key, &validation) {
+            Err(e) => {
+                return Err(OidcError::InvalidIdToken(format!("{}", e)));
+            }
+            Ok(data) => data.claims
+        };
+        if claims.nonce.as_deref() != Some(nonce) {
+            return Err(OidcError::InvalidIdToken(String::from("nonce mismatch")));
+        }
+        return Ok(claims);
+    }
+
+    async fn get_json<T: serde::de::DeserializeOwned>(&self, url: &str) -> Result<T, OidcError> {
+        match self.http.get(url).send().await {
+            Err(e) => {
+                error!("unable to retrieve {}: {:?}", url, e);
+                return Err(OidcError::Discovery(format!("unable to retrieve {}", url)));
+            }
+            Ok(response) => {
+                match response.error_for_status() {
+                    Err(e) => {
+                        error!("unable to retrieve {}: {:?}", url, e);
+                        return Err(OidcError::Discovery(format!("unable to retrieve {}", url)));
+                    }
+                    Ok(response) => {
+                        match response.json::<T>().await {
+                            Err(e) => {
+                                error!("unable to parse {}: {:?}", url, e);
+                                return Err(OidcError::Discovery(format!("unable to parse {}", url)));
+                            }
+                            Ok(value) => {
+                                return Ok(value);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// S256 code challenge, RFC 7636 section 4.2
+    pub fn pkce_challenge(code_verifier: &str) -> String {
+        return URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()));
+    }
+
+    fn random(length: usize) -> String {
+        return thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(length)
+            .map(char::from)
+            .collect();
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::{
+        BufRead,
+        BufReader,
+        Read,
+        Write
+    };
+    use std::net::TcpListener;
+    use std::sync::{
+        Arc,
+        Mutex
+    };
+
+    use jsonwebtoken::{
+        EncodingKey,
+        Header,
+        encode
+    };
+    use tokenizer::keyring::Keyring;
+
+    const CLIENT_ID: &str = "ideas-client";
+    const CODE: &str = "mock-code";
+
+    /// claims the mock provider puts into the id token, apart from the nonce
+    #[derive(Debug, Clone)]
+    struct MockIdentity {
+        audience: String,
+        kid: String
+    }
+
+    /// minimal openid provider serving discovery, jwks and the token
+    /// endpoint on a local port
+    struct MockProvider {
+        issuer: String,
+        /// code challenge and nonce of the authorization request
+        request: Arc<Mutex<Option<(String, String)>>>,
+        /// paths requested from the provider
+        requests: Arc<Mutex<Vec<String>>>
+    }
+
+    impl MockProvider {
+
+        fn start(identity: MockIdentity) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let issuer = format!("http://{}", listener.local_addr().unwrap());
+            let request: Arc<Mutex<Option<(String, String)>>> = Arc::new(Mutex::new(None));
+            let requests: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
+
+            let mut keyring = Keyring::new();
+            keyring.add_pem(
+                "mock",
+                Algorithm::RS256,
+                Some(include_bytes!("../testdata/rsa.pem")),
+                include_bytes!("../testdata/rsa.pub.pem")
+            ).unwrap();
+            let jwks = serde_json::to_string(&keyring.jwks()).unwrap();
+
+            let thread_issuer = issuer.clone();
+            let thread_request = request.clone();
+            let thread_requests = requests.clone();
+            std::thread::spawn(move || {
+                for stream in listener.incoming() {
+                    let mut stream = match stream {
+                        Err(_) => continue,
+                        Ok(stream) => stream
+                    };
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                    let mut request_line = String::new();
+                    reader.read_line(&mut request_line).unwrap();
+                    let mut content_length = 0;
+                    loop {
+                        let mut line = String::new();
+                        reader.read_line(&mut line).unwrap();
+                        if line.trim().is_empty() {
+                            break;
+                        }
+                        if let Some((name, value)) = line.split_once(':') {
+                            if name.eq_ignore_ascii_case("content-length") {
+                                content_length = value.trim().parse().unwrap();
+                            }
+                        }
+                    }
+                    let mut body = vec![0u8; content_length];
+                    reader.read_exact(&mut body).unwrap();
+
+                    let path = request_line.split_whitespace().nth(1).unwrap_or("");
+                    thread_requests.lock().unwrap().push(String::from(path));
+                    let (status, response) = match path {
+                        "/.well-known/openid-configuration" => {
+                            ("200 OK", serde_json::json!({
+                                "issuer": thread_issuer,
+                                "authorization_endpoint": format!("{}/authorize", thread_issuer),
+                                "token_endpoint": format!("{}/token", thread_issuer),
+                                "jwks_uri": format!("{}/jwks", thread_issuer)
+                            }).to_string())
+                        }
+                        "/jwks" => {
+                            ("200 OK", jwks.clone())
+                        }
+                        "/token" => {
+                            Self::token(&thread_issuer, &identity, &thread_request, &body)
+                        }
+                        _ => {
+                            ("404 Not Found", String::from("{}"))
+                        }
+                    };
+
+                    let _ = write!(
+                        stream,
+                        "HTTP/1.1 {}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                        status,
+                        response.len(),
+                        response
+                    );
+                }
+            });
+
+            return Self {
+                issuer: issuer,
+                request: request,
+                requests: requests
+            };
+        }
+
+        /// token endpoint, checks the code and the pkce code verifier
+        fn token(
+            issuer: &str,
+            identity: &MockIdentity,
+            request: &Arc<Mutex<Option<(String, String)>>>,
+            body: &[u8]
+        ) -> (&'static str, String) {
+            let form: Vec<(String, String)> = Url::parse(&format!("http://form/?{}", String::from_utf8_lossy(body)))
+                .unwrap()
+                .query_pairs()
+                .into_owned()
+                .collect();
+            let param = |name: &str| form.iter()
+                .find(|(k, _)| k == name)
+                .map(|(_, v)| v.clone())
+                .unwrap_or_default();
+
+            let (challenge, nonce) = request.lock().unwrap().clone().unwrap();
+            if param("code") != CODE || OidcClient::pkce_challenge(&param("code_verifier")) != challenge {
+                return ("400 Bad Request", String::from("{\"error\":\"invalid_grant\"}"));
+            }
+
+            let now = chrono::Utc::now().timestamp();
+            let claims = serde_json::json!({
+                "iss": issuer,
+                "aud": identity.audience,
+                "sub": "mock-subject",
+                "email": "testing@mailinator.com",
+                "email_verified": true,
+                "given_name": "Test",
+                "family_name": "User",
+                "nonce": nonce,
+                "iat": now,
+                "exp": now + 300
+            });
+            let mut header = Header::new(Algorithm::RS256);
+            header.kid = Some(identity.kid.clone());
+            let id_token = encode(
+                &header,
+                &claims,
+                &EncodingKey::from_rsa_pem(include_bytes!("../testdata/rsa.pem")).unwrap()
+            ).unwrap();
+            return ("200 OK", serde_json::json!({
+                "access_token": "mock-access-token",
+                "token_type": "Bearer",
+                "id_token": id_token
+            }).to_string());
+        }
+
+        fn client(&self) -> OidcClient {
+            return OidcClient::new(&OidcProvider {
+                id: String::from("mock"),
+                tenant_id: uuid::Uuid::nil(),
+                issuer: self.issuer.clone(),
+                client_id: String::from(CLIENT_ID),
+                client_secret: Some(String::from("secret")),
+                scopes: vec![String::from("openid"), String::from("email")],
+                redirect_uri: None
+            }, "http://localhost:4200");
+        }
+
+        /// start an authorization request and record it as the provider would
+        async fn authorize(&self, client: &OidcClient) -> AuthorizationRequest {
+            let metadata = client.discover().await.unwrap();
+            let request = client.authorization_request(&metadata).unwrap();
+            let url = Url::parse(&request.url).unwrap();
+            let challenge = url.query_pairs()
+                .find(|(k, _)| k == "code_challenge")
+                .map(|(_, v)| v.into_owned())
+                .unwrap();
+            *self.request.lock().unwrap() = Some((challenge, request.nonce.clone()));
+            return request;
+        }
+
+        fn requested(&self, path: &str) -> usize {
+            return self.requests.lock().unwrap().iter().filter(|p| *p == path).count();
+        }
+    }
+
+    fn identity() -> MockIdentity {
+        return MockIdentity {
+            audience: String::from(CLIENT_ID),
+            kid: String::from("mock")
+        };
+    }
+
+    #[test]
+    fn test_pkce_challenge() {
+        // RFC 7636 appendix B
+        assert_eq!(
+            OidcClient::pkce_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_authorization_request() {
+        let provider = MockProvider::start(identity());
+        let client = provider.client();
+        let request = provider.authorize(&client).await;
+
+        let url = Url::parse(&request.url).unwrap();
+        assert_eq!(url.path(), "/authorize");
+        let params: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+        assert!(params.contains(&(String::from("client_id"), String::from(CLIENT_ID))));
+        assert!(params.contains(&(String::from("redirect_uri"), String::from("http://localhost:4200/oidc/callback"))));
+        assert!(params.contains(&(String::from("code_challenge_method"), String::from("S256"))));
+        assert!(params.contains(&(String::from("state"), request.state.clone())));
+    }
+
+    #[actix_rt::test]
+    async fn test_authenticate() {
+        let provider = MockProvider::start(identity());
+        let client = provider.client();
+        let request = provider.authorize(&client).await;
+
+        match client.authenticate(CODE, &request.code_verifier, &request.nonce).await {
+            Err(e) => {
+                assert!(false, "authentication should succeed: {}", e);
+            }
+            Ok(claims) => {
+                assert_eq!(claims.sub, "mock-subject");
+                assert_eq!(claims.email.as_deref(), Some("testing@mailinator.com"));
+                assert_eq!(claims.email_verified, Some(true));
+            }
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_authenticate_code_verifier() {
+        let provider = MockProvider::start(identity());
+        let client = provider.client();
+        let request = provider.authorize(&client).await;
+
+        assert!(matches!(
+            client.authenticate(CODE, "wrong verifier", &request.nonce).await,
+            Err(OidcError::Exchange(_))
+        ));
+    }
+
+    #[actix_rt::test]
+    async fn test_authenticate_nonce() {
+        let provider = MockProvider::start(identity());
+        let client = provider.client();
+        let request = provider.authorize(&client).await;
+
+        assert!(matches!(
+            client.authenticate(CODE, &request.code_verifier, "other nonce").await,
+            Err(OidcError::InvalidIdToken(_))
+        ));
+    }
+
+    #[actix_rt::test]
+    async fn test_authenticate_audience() {
+        let provider = MockProvider::start(MockIdentity {
+            audience: String::from("other-client"),
+            ..identity()
+        });
+        let client = provider.client();
+        let request = provider.authorize(&client).await;
+
+        assert!(matches!(
+            client.authenticate(CODE, &request.code_verifier, &request.nonce).await,
+            Err(OidcError::InvalidIdToken(_))
+        ));
+    }
+
+    #[actix_rt::test]
+    async fn test_authenticate_unknown_key() {
+        let provider = MockProvider::start(MockIdentity {
+            kid: String::from("unknown"),
+            ..identity()
+        });
+        let client = provider.client();
+        let request = provider.authorize(&client).await;
+
+        assert!(matches!(
+            client.authenticate(CODE, &request.code_verifier, &request.nonce).await,
+            Err(OidcError::InvalidIdToken(_))
+        ));
+    }
+
+    #[actix_rt::test]
+    async fn test_discovery_cached() {
+        let provider = MockProvider::start(identity());
+        let client = provider.client();
+
+        for _ in 0..2 {
+            let request = provider.authorize(&client).await;
+            assert!(client.authenticate(CODE, &request.code_verifier, &request.nonce).await.is_ok());
+        }
+        assert_eq!(provider.requested("/.well-known/openid-configuration"), 1);
+        assert_eq!(provider.requested("/jwks"), 1);
+        assert_eq!(provider.requested("/token"), 2);
+    }
+}
@@ -0,0 +1,130 @@
+use std::time::Duration;
+
+use chrono::prelude::*;
+
+use configuration::SignInPolicy;
+
+
+/// failed sign in attempts counted since the start of the lockout window,
+/// and since the last successful sign in or unlock of the email
+#[derive(Debug, Clone, Default)]
+pub struct SignInFailures {
+    pub email: i64,
+    pub ip: i64,
+    pub last_failure: Option<DateTime<Utc>>
+}
+
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ThrottleResult {
+    Allowed,
+    /// the account is locked until the given time
+    Locked(DateTime<Utc>),
+    /// too many failed attempts from the client ip
+    Refused(DateTime<Utc>)
+}
+
+
+#[derive(Debug, Clone)]
+pub struct Throttle {
+    policy: SignInPolicy
+}
+
+impl Throttle {
+
+    pub fn new(policy: &SignInPolicy) -> Self {
+        return Self {
+            policy: policy.clone()
+        };
+    }
+
+    /// start of the window failed attempts are counted in
+    pub fn window_start(&self, now: &DateTime<Utc>) -> DateTime<Utc> {
+        return *now - chrono::Duration::seconds(self.policy.lockout_seconds);
+    }
+
+    /// check if another sign in attempt is allowed
+    pub fn check(&self, failures: &SignInFailures, now: &DateTime<Utc>) -> ThrottleResult {
+        let until = match failures.last_failure {
+            None => {
+                return ThrottleResult::Allowed;
+            }
+            Some(last_failure) => last_failure + chrono::Duration::seconds(self.policy.lockout_seconds)
+        };
+        if until <= *now {
+            return ThrottleResult::Allowed;
+        }
+
+        if failures.email >= self.policy.max_attempts {
+            return ThrottleResult::Locked(until);
+        }
+        if failures.ip >= self.policy.ip_max_attempts {
+            return ThrottleResult::Refused(until);
+        }
+        return ThrottleResult::Allowed;
+    }
+
+    /// delay applied before verifying the credentials. doubles with every
+    /// failed attempt up to the configured maximum
+    pub fn delay(&self, failures: &SignInFailures) -> Duration {
+        let count = std::cmp::max(failures.email, failures.ip);
+        if count <= 0 {
+            return Duration::ZERO;
+        }
+        let exponent = std::cmp::min(count - 1, 16) as u32;
+        let delay = self.policy.delay_base_ms.saturating_mul(2u64.pow(exponent));
+        return Duration::from_millis(std::cmp::min(delay, self.policy.delay_max_ms));
+    }
+
+    /// check if a failed attempt, in addition to the given failures, locks the account
+    pub fn locks(&self, failures: &SignInFailures) -> bool {
+        return failures.email + 1 == self.policy.max_attempts;
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failures(email: i64, ip: i64, seconds_ago: i64) -> SignInFailures {
+        return SignInFailures {
+            email: email,
+            ip: ip,
+            last_failure: Some(Utc::now() - chrono::Duration::seconds(seconds_ago))
+        };
+    }
+
+    #[test]
+    fn test_check() {
+        let throttle = Throttle::new(&SignInPolicy::default());
+        let now = Utc::now();
+
+        assert_eq!(throttle.check(&SignInFailures::default(), &now), ThrottleResult::Allowed);
+        assert_eq!(throttle.check(&failures(4, 4, 10), &now), ThrottleResult::Allowed);
+        assert!(matches!(throttle.check(&failures(5, 5, 10), &now), ThrottleResult::Locked(_)));
+        assert!(matches!(throttle.check(&failures(0, 50, 10), &now), ThrottleResult::Refused(_)));
+
+        // lockout expires
+        assert_eq!(throttle.check(&failures(5, 5, 901), &now), ThrottleResult::Allowed);
+    }
+
+    #[test]
+    fn test_delay() {
+        let throttle = Throttle::new(&SignInPolicy::default());
+
+        assert_eq!(throttle.delay(&SignInFailures::default()), Duration::ZERO);
+        assert_eq!(throttle.delay(&failures(1, 1, 0)), Duration::from_millis(250));
+        assert_eq!(throttle.delay(&failures(3, 1, 0)), Duration::from_millis(1000));
+        assert_eq!(throttle.delay(&failures(0, 40, 0)), Duration::from_millis(4000));
+    }
+
+    #[test]
+    fn test_locks() {
+        let throttle = Throttle::new(&SignInPolicy::default());
+
+        assert!(!throttle.locks(&failures(3, 3, 0)));
+        assert!(throttle.locks(&failures(4, 4, 0)));
+        assert!(!throttle.locks(&failures(5, 5, 0)));
+    }
+}
@@ -0,0 +1,180 @@
+use hmac::{
+    Hmac,
+    Mac
+};
+use sha1::Sha1;
+use data_encoding::BASE32_NOPAD;
+use rand::{
+    thread_rng,
+    RngCore
+};
+
+
+/// number of seconds a code is valid for
+const STEP_SECONDS: i64 = 30;
+
+/// number of digits in a code
+const DIGITS: u32 = 6;
+
+/// number of steps before and after the current step a code is accepted for,
+/// to allow for clock drift
+const SKEW: i64 = 1;
+
+/// number of bytes in a generated secret
+const SECRET_LENGTH: usize = 20;
+
+
+/// RFC 6238 time-based one-time password using HMAC-SHA1
+#[derive(Debug, Clone)]
+pub struct Totp {
+    secret: Vec<u8>
+}
+
+impl Totp {
+
+    pub fn new(secret: &[u8]) -> Self {
+        return Self {
+            secret: secret.to_vec()
+        };
+    }
+
+    /// generate a random secret
+    pub fn generate() -> Self {
+        let mut secret = vec![0u8; SECRET_LENGTH];
+        thread_rng().fill_bytes(&mut secret);
+        return Self::new(&secret);
+    }
+
+    /// decode a base32 encoded secret
+    pub fn from_base32(secret: &str) -> Option<Self> {
+        match BASE32_NOPAD.decode(secret.trim_end_matches('=').as_bytes()) {
+            Err(_) => {
+                return None;
+            }
+            Ok(secret) => {
+                return Some(Self::new(&secret));
+            }
+        }
+    }
+
+    pub fn secret(&self) -> Vec<u8> {
+        return self.secret.clone();
+    }
+
+    /// base32 encoded secret, as entered into authenticator apps
+    pub fn base32(&self) -> String {
+        return BASE32_NOPAD.encode(&self.secret);
+    }
+
+    /// otpauth uri for enrolling the secret in an authenticator app,
+    /// usually rendered as a qr code
+    pub fn uri(&self, issuer: &str, account: &str) -> String {
+        return format!(
+            "otpauth://totp/{issuer}:{account}?secret={secret}&issuer={issuer}&algorithm=SHA1&digits={digits}&period={period}",
+            issuer = Self::encode(issuer),
+            account = Self::encode(account),
+            secret = self.base32(),
+            digits = DIGITS,
+            period = STEP_SECONDS
+        );
+    }
+
+    /// time step a unix timestamp falls in
+    pub fn step(timestamp: i64) -> i64 {
+        return timestamp.div_euclid(STEP_SECONDS);
+    }
+
+    /// code for a time step
+    pub fn code(&self, step: i64) -> String {
+        let mut mac = Hmac::<Sha1>::new_from_slice(&self.secret)
+            .expect("hmac accepts keys of any length");
+        mac.update(&step.to_be_bytes());
+        let hash = mac.finalize().into_bytes();
+
+        // dynamic truncation, RFC 4226 section 5.3
+        let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+        let binary = ((hash[offset] as u32 & 0x7f) << 24)
+            | ((hash[offset + 1] as u32) << 16)
+            | ((hash[offset + 2] as u32) << 8)
+            | (hash[offset + 3] as u32);
+        return format!("{:0width$}", binary % 10u32.pow(DIGITS), width = DIGITS as usize);
+    }
+
+    /// verify a code at a unix timestamp. returns the time step the code
+    /// matched so callers can reject a code that has already been used
+    pub fn verify(&self, code: &str, timestamp: i64) -> Option<i64> {
+        let code = code.trim();
+        if code.len() != DIGITS as usize {
+            return None;
+        }
+        let current = Self::step(timestamp);
+        return (current - SKEW..=current + SKEW)
+            .find(|step| Self::constant_time_eq(self.code(*step).as_bytes(), code.as_bytes()));
+    }
+
+    fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+        if a.len() != b.len() {
+            return false;
+        }
+        return a.iter().zip(b.iter()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0;
+    }
+
+    /// percent encode a label component of the otpauth uri
+    fn encode(value: &str) -> String {
+        return value.bytes()
+            .map(|b| {
+                if b.is_ascii_alphanumeric() || b"-._~@".contains(&b) {
+                    return (b as char).to_string();
+                }
+                return format!("%{:02X}", b);
+            })
+            .collect();
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 6238 appendix B test vectors, truncated to 6 digits
+    const SECRET: &[u8] = b"12345678901234567890";
+
+    #[test]
+    fn test_code() {
+        let totp = Totp::new(SECRET);
+        assert_eq!(totp.code(Totp::step(59)), "287082");
+        assert_eq!(totp.code(Totp::step(1111111109)), "081804");
+        assert_eq!(totp.code(Totp::step(1234567890)), "005924");
+        assert_eq!(totp.code(Totp::step(2000000000)), "279037");
+    }
+
+    #[test]
+    fn test_verify() {
+        let totp = Totp::new(SECRET);
+        let step = Totp::step(1111111109);
+
+        assert_eq!(totp.verify("081804", 1111111109), Some(step));
+        // previous step is accepted to allow for clock drift
+        assert_eq!(totp.verify("081804", 1111111109 + 30), Some(step));
+        assert_eq!(totp.verify("081804", 1111111109 + 90), None);
+        assert_eq!(totp.verify("000000", 1111111109), None);
+        assert_eq!(totp.verify("81804", 1111111109), None);
+    }
+
+    #[test]
+    fn test_base32() {
+        let totp = Totp::new(SECRET);
+        assert_eq!(totp.base32(), "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ");
+        assert_eq!(Totp::from_base32(&totp.base32()).unwrap().secret(), SECRET);
+    }
+
+    #[test]
+    fn test_uri() {
+        let totp = Totp::new(SECRET);
+        assert_eq!(
+            totp.uri("ideas", "testing@mailinator.com"),
+            "otpauth://totp/ideas:testing@mailinator.com?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&issuer=ideas&algorithm=SHA1&digits=6&period=30"
+        );
+    }
+}
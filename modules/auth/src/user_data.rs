@@ -39,7 +39,11 @@ use configuration::{
 };
 
 use data::pg::DataError;
+use data::pg::timed;
+use data::pg::sessions;
+use tokenizer::Tokenizer;
 use crate::user::User;
+use crate::throttle::SignInFailures;
 
 
 // #[derive(Debug)]
@@ -58,6 +62,34 @@ pub struct RegistrationInfo {
 }
 
 
+#[derive(Debug)]
+pub struct RefreshTokenInfo {
+    pub family_id: uuid::Uuid,
+    pub user_id: uuid::Uuid,
+    pub tenant_id: uuid::Uuid,
+    pub used: bool,
+    pub revoked: bool,
+    pub expires: DateTime<Utc>
+}
+
+
+#[derive(Debug)]
+pub struct MfaInfo {
+    /// base32 encoded totp secret
+    pub secret: String,
+    pub confirmed: bool
+}
+
+
+#[derive(Debug)]
+pub struct OidcRequestInfo {
+    pub provider_id: String,
+    pub code_verifier: String,
+    pub nonce: String,
+    pub expires: DateTime<Utc>
+}
+
+
 #[derive(Debug, Clone)]
 pub struct UserData {
     pool: Pool
@@ -88,7 +120,7 @@ impl UserData {
         let result = self.pool.get().await;
         if let Err(e) = result {
             error!("unable to retrieve database client: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::Unavailable);
         }
         let client = result.unwrap();
 
@@ -97,20 +129,20 @@ impl UserData {
         ).await;
         if let Err(e) = result {
             error!("unable to prepare database statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         }
         let stmt = result.unwrap();
 
-        if let Err(e) = client.execute(
+        if let Err(e) = timed("iam.register", client.execute(
             &stmt, 
             &[
                 &id,
                 &data::pg::email::Email::new(&email),
                 &token
             ]
-        ).await {
+        )).await {
             error!("unable to execute statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         } else {
             return Ok(token);
         }
@@ -123,7 +155,7 @@ impl UserData {
         let result = self.pool.get().await;
         if let Err(e) = result {
             error!("unable to retrieve database client: {:?}", e);
-            return Err(DataError::ConfigurationError);
+            return Err(DataError::Unavailable);
         }
         let client = result.unwrap();
 
@@ -132,18 +164,18 @@ impl UserData {
         ).await;
         if let Err(e) = result {
             error!("unable to prepare database statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         }
         let stmt = result.unwrap();
 
-        match client.query_one(
+        match timed("iam.register_get_info", client.query_one(
             &stmt,
             &[
                 &token
-            ]).await {
+            ])).await {
                 Err(e) => {
                     error!("unable to execute statement: {:?}", e);
-                    return Err(DataError::DatabaseError);
+                    return Err(DataError::from(&e));
                 }
                 Ok(row) => {
                     debug!("row: {:?}", row);
@@ -171,7 +203,7 @@ impl UserData {
         let result = self.pool.get().await;
         if let Err(e) = result {
             error!("unable to retrieve database client: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::Unavailable);
         }
         let client = result.unwrap();
 
@@ -180,20 +212,20 @@ impl UserData {
         ).await;
         if let Err(e) = result {
             error!("unable to prepare database statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         }
         let stmt = result.unwrap();
 
-        match client.execute(
+        match timed("iam.register_complete", client.execute(
             &stmt,
             &[
                 &token,
                 &pw
             ]
-        ).await {
+        )).await {
             Err(e) => {
                 error!("unable to execute statement: {:?}", e);
-                return Err(DataError::DatabaseError);
+                return Err(DataError::from(&e));
             }
             Ok(_) => {
                 return Ok(());
@@ -212,7 +244,7 @@ impl UserData {
         let result = self.pool.get().await;
         if let Err(e) = result {
             error!("unable to retrieve database client: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::Unavailable);
         }
         let client = result.unwrap();
 
@@ -221,20 +253,20 @@ impl UserData {
         ).await;
         if let Err(e) = result {
             error!("unable to prepare database statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         }
         let stmt = result.unwrap();
 
-        match client.query_one(
+        match timed("iam.user_authenticate", client.query_one(
             &stmt,
             &[
                 &data::pg::email::Email::new(&email),
                 &pw
             ]
-        ).await {
+        )).await {
             Err(e) => {
                 error!("unable to execute statement: {:?}", e);
-                return Err(DataError::DatabaseError);
+                return Err(DataError::from(&e));
             }
             Ok(row) => {
                 debug!("row: {:?}", row);
@@ -255,7 +287,7 @@ impl UserData {
         let result = self.pool.get().await;
         if let Err(e) = result {
             error!("unable to retrieve database client: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::Unavailable);
         }
         let client = result.unwrap();
 
@@ -264,19 +296,19 @@ impl UserData {
         ).await;
         if let Err(e) = result {
             error!("unable to prepare database statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         }
         let stmt = result.unwrap();
 
-        match client.query_one(
+        match timed("iam.user_tenant_fetch_default", client.query_one(
             &stmt,
             &[
                 &user_id
             ]
-        ).await {
+        )).await {
             Err(e) => {
                 error!("unable to execute statement: {:?}", e);
-                return Err(DataError::DatabaseError);
+                return Err(DataError::from(&e));
             }
             Ok(row) => {
                 debug!("row: {:?}", row);
@@ -295,7 +327,7 @@ impl UserData {
         let result = self.pool.get().await;
         if let Err(e) = result {
             error!("unable to retrieve database client: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::Unavailable);
         }
         let client = result.unwrap();
 
@@ -304,19 +336,19 @@ impl UserData {
         ).await;
         if let Err(e) = result {
             error!("unable to prepare database statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         }
         let stmt = result.unwrap();
 
-        match client.query_one(
+        match timed("iam.user_by_email", client.query_one(
             &stmt,
             &[
                 &data::pg::email::Email::new(&email)
             ]
-        ).await {
+        )).await {
             Err(e) => {
                 error!("unable to execute statement: {:?}", e);
-                return Err(DataError::DatabaseError);
+                return Err(DataError::from(&e));
             }
             Ok(row) => {
                 debug!("row: {:?}", row);
@@ -334,4 +366,1111 @@ impl UserData {
             }
         }
     }
-}
\ No newline at end of file
+
+    pub async fn get_user_by_id(
+        &self,
+        user_id: &uuid::Uuid
+    ) -> Result<User, DataError> {
+        info!("get_user_by_id");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "select * from iam.user_by_id($1)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("iam.user_by_id", client.query_one(
+            &stmt,
+            &[
+                &user_id
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(row) => {
+                debug!("row: {:?}", row);
+
+                let user_id: uuid::Uuid = row.get("id");
+
+                return Ok(User::new(
+                    &user_id,
+                    row.get("email"),
+                    row.get("given_name"),
+                    row.get("middle_name"),
+                    row.get("family_name")
+                ));
+            }
+        }
+    }
+
+    /// add a refresh token to a token family and return the token string.
+    /// only the digest of the token is stored
+    pub async fn refresh_token_add(
+        &self,
+        family_id: &uuid::Uuid,
+        user_id: &uuid::Uuid,
+        tenant_id: &uuid::Uuid,
+        expires: &DateTime<Utc>
+    ) -> Result<String, DataError> {
+        info!("Data::refresh_token_add()");
+
+        let token: String = thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(48)
+            .map(char::from)
+            .collect();
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "call iam.refresh_token_add($1, $2, $3, $4, $5)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        if let Err(e) = timed("iam.refresh_token_add", client.execute(
+            &stmt,
+            &[
+                &family_id,
+                &user_id,
+                &tenant_id,
+                &Tokenizer::digest(&token),
+                &expires
+            ]
+        )).await {
+            error!("unable to execute statement: {:?}", e);
+            return Err(DataError::from(&e));
+        } else {
+            return Ok(token);
+        }
+    }
+
+    /// mark a refresh token as used and return its state prior to the call.
+    /// returns None if the token is unknown
+    pub async fn refresh_token_use(
+        &self,
+        token: &str
+    ) -> Result<Option<RefreshTokenInfo>, DataError> {
+        info!("Data::refresh_token_use()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "select * from iam.refresh_token_use($1)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("iam.refresh_token_use", client.query_opt(
+            &stmt,
+            &[
+                &Tokenizer::digest(&token)
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(None) => {
+                return Ok(None);
+            }
+            Ok(Some(row)) => {
+                debug!("row: {:?}", row);
+                return Ok(Some(RefreshTokenInfo {
+                    family_id: row.get("family_id"),
+                    user_id: row.get("user_id"),
+                    tenant_id: row.get("tenant_id"),
+                    used: row.get("used"),
+                    revoked: row.get("revoked"),
+                    expires: row.get("expires")
+                }));
+            }
+        }
+    }
+
+    /// revoke all refresh tokens issued in a token family
+    pub async fn refresh_token_family_revoke(
+        &self,
+        family_id: &uuid::Uuid
+    ) -> Result<(), DataError> {
+        info!("Data::refresh_token_family_revoke()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "call iam.refresh_token_family_revoke($1)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("iam.refresh_token_family_revoke", client.execute(
+            &stmt,
+            &[
+                &family_id
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(_) => {
+                return Ok(());
+            }
+        }
+    }
+
+    /// revoke every refresh token family (session) of a user
+    pub async fn user_sessions_revoke(
+        &self,
+        user_id: &uuid::Uuid
+    ) -> Result<(), DataError> {
+        info!("Data::user_sessions_revoke()");
+        return sessions::user_sessions_revoke(&self.pool, &user_id, &uuid::Uuid::nil()).await;
+    }
+
+    /// add a password reset token for the user with the email address and
+    /// return the token string. only the digest of the token is stored.
+    /// returns None if there is no user with the email address
+    pub async fn password_reset_add(
+        &self,
+        email: &str,
+        expires: &DateTime<Utc>
+    ) -> Result<Option<String>, DataError> {
+        info!("Data::password_reset_add()");
+
+        // generate url friendly token
+        let token: String = thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(30)
+            .map(char::from)
+            .collect();
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "select * from iam.password_reset_add($1, $2, $3)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("iam.password_reset_add", client.query_one(
+            &stmt,
+            &[
+                &data::pg::email::Email::new(&email),
+                &Tokenizer::digest(&token),
+                &expires
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(row) => {
+                let added: bool = row.get(0);
+                if added {
+                    return Ok(Some(token));
+                } else {
+                    return Ok(None);
+                }
+            }
+        }
+    }
+
+    /// retrieve the email address of the user a password reset token was
+    /// issued for. returns None if the token is unknown, expired or has
+    /// already been used
+    pub async fn password_reset_get_info(
+        &self,
+        token: &str
+    ) -> Result<Option<String>, DataError> {
+        info!("Data::password_reset_get_info()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "select * from iam.password_reset_get_info($1)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("iam.password_reset_get_info", client.query_opt(
+            &stmt,
+            &[
+                &Tokenizer::digest(&token)
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(None) => {
+                return Ok(None);
+            }
+            Ok(Some(row)) => {
+                let email: String = row.get("email");
+                return Ok(Some(email));
+            }
+        }
+    }
+
+    /// set the password of the user a password reset token was issued for and
+    /// mark the token as used. returns the user id, or None if the token is
+    /// unknown, expired or has already been used
+    pub async fn password_reset_complete(
+        &self,
+        token: &str,
+        pw: &str
+    ) -> Result<Option<uuid::Uuid>, DataError> {
+        info!("Data::password_reset_complete()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "select * from iam.password_reset_complete($1, $2)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("iam.password_reset_complete", client.query_opt(
+            &stmt,
+            &[
+                &Tokenizer::digest(&token),
+                &pw
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(None) => {
+                return Ok(None);
+            }
+            Ok(Some(row)) => {
+                let user_id: uuid::Uuid = row.get("user_id");
+                return Ok(Some(user_id));
+            }
+        }
+    }
+
+    /// count failed sign in attempts for an email and a client ip since the
+    /// given time. attempts before the last successful sign in or unlock of
+    /// the email are not counted against the email
+    pub async fn sign_in_failures(
+        &self,
+        email: &str,
+        ip: Option<&str>,
+        since: &DateTime<Utc>
+    ) -> Result<SignInFailures, DataError> {
+        info!("Data::sign_in_failures()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "select * from iam.sign_in_failures($1, $2, $3)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("iam.sign_in_failures", client.query_one(
+            &stmt,
+            &[
+                &data::pg::email::Email::new(&email),
+                &ip,
+                &since
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(row) => {
+                debug!("row: {:?}", row);
+                return Ok(SignInFailures {
+                    email: row.get("email_failures"),
+                    ip: row.get("ip_failures"),
+                    last_failure: row.get("last_failure")
+                });
+            }
+        }
+    }
+
+    /// record a sign in attempt
+    pub async fn sign_in_attempt_add(
+        &self,
+        email: &str,
+        ip: Option<&str>,
+        success: bool
+    ) -> Result<(), DataError> {
+        info!("Data::sign_in_attempt_add()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "call iam.sign_in_attempt_add($1, $2, $3)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("iam.sign_in_attempt_add", client.execute(
+            &stmt,
+            &[
+                &data::pg::email::Email::new(&email),
+                &ip,
+                &success
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(_) => {
+                return Ok(());
+            }
+        }
+    }
+
+    /// add an unlock token for the user with the email address and return
+    /// the token string. only the digest of the token is stored. returns
+    /// None if there is no user with the email address
+    pub async fn account_unlock_add(
+        &self,
+        email: &str,
+        expires: &DateTime<Utc>
+    ) -> Result<Option<String>, DataError> {
+        info!("Data::account_unlock_add()");
+
+        // generate url friendly token
+        let token: String = thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(30)
+            .map(char::from)
+            .collect();
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "select * from iam.account_unlock_add($1, $2, $3)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("iam.account_unlock_add", client.query_one(
+            &stmt,
+            &[
+                &data::pg::email::Email::new(&email),
+                &Tokenizer::digest(&token),
+                &expires
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(row) => {
+                let added: bool = row.get(0);
+                if added {
+                    return Ok(Some(token));
+                } else {
+                    return Ok(None);
+                }
+            }
+        }
+    }
+
+    /// clear the failed sign in attempts of the user an unlock token was
+    /// issued for and mark the token as used. returns false if the token is
+    /// unknown, expired or has already been used
+    pub async fn account_unlock(
+        &self,
+        token: &str
+    ) -> Result<bool, DataError> {
+        info!("Data::account_unlock()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "select * from iam.account_unlock($1)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("iam.account_unlock", client.query_one(
+            &stmt,
+            &[
+                &Tokenizer::digest(&token)
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(row) => {
+                let unlocked: bool = row.get(0);
+                return Ok(unlocked);
+            }
+        }
+    }
+
+    /// retrieve the totp secret of a user. returns None if the user has
+    /// not enrolled
+    pub async fn user_mfa_fetch(
+        &self,
+        user_id: &uuid::Uuid
+    ) -> Result<Option<MfaInfo>, DataError> {
+        info!("Data::user_mfa_fetch()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "select * from iam.user_mfa_fetch($1)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("iam.user_mfa_fetch", client.query_opt(
+            &stmt,
+            &[
+                &user_id
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(None) => {
+                return Ok(None);
+            }
+            Ok(Some(row)) => {
+                return Ok(Some(MfaInfo {
+                    secret: row.get("secret"),
+                    confirmed: row.get("confirmed")
+                }));
+            }
+        }
+    }
+
+    /// check if any tenant the user is a member of requires two-factor authentication
+    pub async fn user_mfa_required(
+        &self,
+        user_id: &uuid::Uuid
+    ) -> Result<bool, DataError> {
+        info!("Data::user_mfa_required()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "select * from iam.user_mfa_required($1)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("iam.user_mfa_required", client.query_one(
+            &stmt,
+            &[
+                &user_id
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(row) => {
+                let required: bool = row.get(0);
+                return Ok(required);
+            }
+        }
+    }
+
+    /// store an unconfirmed totp secret, replacing any previous unconfirmed secret
+    pub async fn user_mfa_enroll(
+        &self,
+        user_id: &uuid::Uuid,
+        secret: &str
+    ) -> Result<(), DataError> {
+        info!("Data::user_mfa_enroll()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "call iam.user_mfa_enroll($1, $2)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("iam.user_mfa_enroll", client.execute(
+            &stmt,
+            &[
+                &user_id,
+                &secret
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(_) => {
+                return Ok(());
+            }
+        }
+    }
+
+    /// confirm the totp secret of a user and store the digests of the recovery codes
+    pub async fn user_mfa_confirm(
+        &self,
+        user_id: &uuid::Uuid,
+        recovery_codes: &Vec<String>
+    ) -> Result<(), DataError> {
+        info!("Data::user_mfa_confirm()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "call iam.user_mfa_confirm($1, $2)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("iam.user_mfa_confirm", client.execute(
+            &stmt,
+            &[
+                &user_id,
+                &recovery_codes
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(_) => {
+                return Ok(());
+            }
+        }
+    }
+
+    /// record the time step of a verified totp code. returns false if a code
+    /// for the same or a later time step has already been used
+    pub async fn user_mfa_step_use(
+        &self,
+        user_id: &uuid::Uuid,
+        step: &i64
+    ) -> Result<bool, DataError> {
+        info!("Data::user_mfa_step_use()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "select * from iam.user_mfa_step_use($1, $2)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("iam.user_mfa_step_use", client.query_one(
+            &stmt,
+            &[
+                &user_id,
+                &step
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(row) => {
+                let used: bool = row.get(0);
+                return Ok(used);
+            }
+        }
+    }
+
+    /// mark a recovery code as used. returns false if the code is unknown or
+    /// has already been used
+    pub async fn user_mfa_recovery_use(
+        &self,
+        user_id: &uuid::Uuid,
+        code: &str
+    ) -> Result<bool, DataError> {
+        info!("Data::user_mfa_recovery_use()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "select * from iam.user_mfa_recovery_use($1, $2)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("iam.user_mfa_recovery_use", client.query_one(
+            &stmt,
+            &[
+                &user_id,
+                &Tokenizer::digest(&code)
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(row) => {
+                let used: bool = row.get(0);
+                return Ok(used);
+            }
+        }
+    }
+
+    /// check if an mfa pending token has already been exchanged
+    pub async fn mfa_pending_used(
+        &self,
+        jti: &uuid::Uuid
+    ) -> Result<bool, DataError> {
+        info!("Data::mfa_pending_used()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "select * from iam.mfa_pending_used($1)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("iam.mfa_pending_used", client.query_one(
+            &stmt,
+            &[
+                &jti
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(row) => {
+                let used: bool = row.get(0);
+                return Ok(used);
+            }
+        }
+    }
+
+    /// mark an mfa pending token as exchanged. returns false if it has
+    /// already been used
+    pub async fn mfa_pending_use(
+        &self,
+        jti: &uuid::Uuid,
+        expires: &DateTime<Utc>
+    ) -> Result<bool, DataError> {
+        info!("Data::mfa_pending_use()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "select * from iam.mfa_pending_use($1, $2)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("iam.mfa_pending_use", client.query_one(
+            &stmt,
+            &[
+                &jti,
+                &expires
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(row) => {
+                let used: bool = row.get(0);
+                return Ok(used);
+            }
+        }
+    }
+
+    /// remove the totp secret and recovery codes of a user
+    pub async fn user_mfa_disable(
+        &self,
+        user_id: &uuid::Uuid
+    ) -> Result<(), DataError> {
+        info!("Data::user_mfa_disable()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "call iam.user_mfa_disable($1)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("iam.user_mfa_disable", client.execute(
+            &stmt,
+            &[
+                &user_id
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(_) => {
+                return Ok(());
+            }
+        }
+    }
+
+    /// store a pending openid connect authorization request. only the
+    /// digest of the state is stored
+    pub async fn oidc_request_add(
+        &self,
+        state: &str,
+        provider_id: &str,
+        code_verifier: &str,
+        nonce: &str,
+        expires: &DateTime<Utc>
+    ) -> Result<(), DataError> {
+        info!("Data::oidc_request_add()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "call iam.oidc_request_add($1, $2, $3, $4, $5)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("iam.oidc_request_add", client.execute(
+            &stmt,
+            &[
+                &Tokenizer::digest(&state),
+                &provider_id,
+                &code_verifier,
+                &nonce,
+                &expires
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(_) => {
+                return Ok(());
+            }
+        }
+    }
+
+    /// remove a pending openid connect authorization request and return it.
+    /// returns None if the state is unknown or has already been used
+    pub async fn oidc_request_use(
+        &self,
+        state: &str
+    ) -> Result<Option<OidcRequestInfo>, DataError> {
+        info!("Data::oidc_request_use()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "select * from iam.oidc_request_use($1)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("iam.oidc_request_use", client.query_opt(
+            &stmt,
+            &[
+                &Tokenizer::digest(&state)
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(None) => {
+                return Ok(None);
+            }
+            Ok(Some(row)) => {
+                return Ok(Some(OidcRequestInfo {
+                    provider_id: row.get("provider_id"),
+                    code_verifier: row.get("code_verifier"),
+                    nonce: row.get("nonce"),
+                    expires: row.get("expires")
+                }));
+            }
+        }
+    }
+
+    /// retrieve the user linked to an external identity. returns None if
+    /// the identity has not been linked
+    pub async fn user_identity_fetch(
+        &self,
+        issuer: &str,
+        subject: &str
+    ) -> Result<Option<uuid::Uuid>, DataError> {
+        info!("Data::user_identity_fetch()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "select * from iam.user_identity_fetch($1, $2)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("iam.user_identity_fetch", client.query_opt(
+            &stmt,
+            &[
+                &issuer,
+                &subject
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(None) => {
+                return Ok(None);
+            }
+            Ok(Some(row)) => {
+                let user_id: uuid::Uuid = row.get("user_id");
+                return Ok(Some(user_id));
+            }
+        }
+    }
+
+    /// link an external identity to the user with the email address, creating
+    /// the user as a member of the tenant of the identity provider if there is
+    /// none. returns None if the user is not a member of that tenant
+    pub async fn user_identity_provision(
+        &self,
+        email: &str,
+        given_name: &str,
+        family_name: &str,
+        issuer: &str,
+        subject: &str,
+        tenant_id: &uuid::Uuid
+    ) -> Result<Option<uuid::Uuid>, DataError> {
+        info!("Data::user_identity_provision()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "select * from iam.user_identity_provision($1, $2, $3, $4, $5, $6, $7)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("iam.user_identity_provision", client.query_one(
+            &stmt,
+            &[
+                &uuid::Uuid::new_v4(),
+                &data::pg::email::Email::new(&email),
+                &given_name,
+                &family_name,
+                &issuer,
+                &subject,
+                &tenant_id
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(row) => {
+                let user_id: Option<uuid::Uuid> = row.get("user_id");
+                return Ok(user_id);
+            }
+        }
+    }
+}
@@ -1 +0,0 @@
-pub mod password;
\ No newline at end of file
@@ -1,11 +0,0 @@
-pub struct Password {
-
-}
-
-impl Password {
-
-    pub fn validate(pw: &str) -> bool {
-        // todo!();
-        return true;
-    }
-}
\ No newline at end of file
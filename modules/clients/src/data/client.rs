@@ -96,7 +96,7 @@ impl ClientData {
         let result = self.pool.get().await;
         if let Err(e) = result {
             error!("unable to retrieve database client: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::Unavailable);
         }
         let client = result.unwrap();
 
@@ -105,7 +105,7 @@ impl ClientData {
         ).await;
         if let Err(e) = result {
             error!("unable to prepare database statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         }
         let stmt = result.unwrap();
 
@@ -117,7 +117,7 @@ impl ClientData {
         ).await {
             Err(e) => {
                 error!("unable to execute statement: {:?}", e);
-                return Err(DataError::DatabaseError);
+                return Err(DataError::from(&e));
             }
             Ok(row) => {
                 debug!("row: {:?}", row);
@@ -144,7 +144,7 @@ impl ClientData {
         let result = self.pool.get().await;
         if let Err(e) = result {
             error!("unable to retrieve database client: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::Unavailable);
         }
         let client = result.unwrap();
 
@@ -153,7 +153,7 @@ impl ClientData {
         ).await;
         if let Err(e) = result {
             error!("unable to prepare database statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         }
         let stmt = result.unwrap();
 
@@ -164,7 +164,7 @@ impl ClientData {
         ).await {
             Err(e) => {
                 error!("unable to execute statement: {:?}", e);
-                return Err(DataError::DatabaseError);
+                return Err(DataError::from(&e));
             }
             Ok(rows) => {
                 let clients = rows.iter().map(|r| {
@@ -196,7 +196,7 @@ impl ClientData {
         let result = self.pool.get().await;
         if let Err(e) = result {
             error!("unable to retrieve database client: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::Unavailable);
         }
         let client = result.unwrap();
 
@@ -205,7 +205,7 @@ impl ClientData {
         ).await;
         if let Err(e) = result {
             error!("unable to prepare database statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         }
         let stmt = result.unwrap();
 
@@ -220,7 +220,7 @@ impl ClientData {
         ).await {
             Err(e) => {
                 error!("unable to execute statement: {:?}", e);
-                return Err(DataError::DatabaseError);
+                return Err(DataError::from(&e));
             }
             Ok(row) => {
                 debug!("row: {:?}", row);
@@ -243,7 +243,7 @@ impl ClientData {
         let result = self.pool.get().await;
         if let Err(e) = result {
             error!("unable to retrieve database client: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::Unavailable);
         }
         let client = result.unwrap();
 
@@ -252,7 +252,7 @@ impl ClientData {
         ).await;
         if let Err(e) = result {
             error!("unable to prepare database statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         }
         let stmt = result.unwrap();
 
@@ -264,7 +264,7 @@ impl ClientData {
         ).await {
             Err(e) => {
                 error!("unable to execute statement: {:?}", e);
-                return Err(DataError::DatabaseError);
+                return Err(DataError::from(&e));
             }
             Ok(rows) => {
                 debug!("rows: {:?}", rows);
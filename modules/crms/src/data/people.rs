@@ -18,6 +18,7 @@ use tokio_postgres::{
 use tokio_postgres::config::{ Config };
 
 use data::pg::DataError;
+use data::pg::timed;
 
 
 use configuration::{
@@ -59,7 +60,7 @@ impl Data {
         let result = self.pool.get().await;
         if let Err(e) = result {
             error!("unable to retrieve database client: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::Unavailable);
         }
         let client = result.unwrap();
 
@@ -68,11 +69,11 @@ impl Data {
         ).await;
         if let Err(e) = result {
             error!("unable to prepare database statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         }
         let stmt = result.unwrap();
 
-        match client.execute(
+        match timed("people.people_add", client.execute(
             &stmt,
             &[
                 &tenant_id,
@@ -83,10 +84,10 @@ impl Data {
                 &prefix,
                 &suffix
             ]
-        ).await {
+        )).await {
             Err(e) => {
                 error!("unable to execute statement: {:?}", e);
-                return Err(DataError::DatabaseError);
+                return Err(DataError::from(&e));
             }
             Ok(_) => {
                 return Ok(());
@@ -7,6 +7,7 @@ use log::{
 use std::str::FromStr;
 
 use deadpool_postgres::{ 
+    Client,
     Manager, 
     ManagerConfig, 
     Pool, 
@@ -14,11 +15,13 @@ use deadpool_postgres::{
 };
 use tokio_postgres::{
     NoTls,
-    // row::Row
+    row::Row
 };
 use tokio_postgres::config::{ Config };
 
 use data::pg::DataError;
+use data::pg::timed;
+use data::pg::unit_of_work::UnitOfWork;
 
 
 use configuration::{
@@ -84,6 +87,11 @@ impl Data {
         };
     }
 
+    /// start a unit of work on the pool employee records are kept in
+    pub async fn begin(&self) -> Result<UnitOfWork, DataError> {
+        return UnitOfWork::begin(&self.pool).await;
+    }
+
     /// add an employee
     pub async fn add(
         &self,
@@ -96,30 +104,52 @@ impl Data {
         let result = self.pool.get().await;
         if let Err(e) = result {
             error!("unable to retrieve database client: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::Unavailable);
         }
         let client = result.unwrap();
 
+        return Self::add_on(&client, &tenant_id, &employee_id, &people_id).await;
+    }
+
+    /// add an employee as part of a unit of work
+    pub async fn add_in(
+        &self,
+        uow: &UnitOfWork,
+        tenant_id: &uuid::Uuid,
+        employee_id: &uuid::Uuid,
+        people_id: &uuid::Uuid
+    ) -> Result<(), DataError> {
+        info!("Data::add_in()");
+
+        return Self::add_on(uow.client(), &tenant_id, &employee_id, &people_id).await;
+    }
+
+    async fn add_on(
+        client: &Client,
+        tenant_id: &uuid::Uuid,
+        employee_id: &uuid::Uuid,
+        people_id: &uuid::Uuid
+    ) -> Result<(), DataError> {
         let result = client.prepare_cached(
             "call hr.employee_add($1,$2,$3)"
         ).await;
         if let Err(e) = result {
             error!("unable to prepare database statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         }
         let stmt = result.unwrap();
 
-        match client.execute(
+        match timed("hr.employee_add", client.execute(
             &stmt,
             &[
                 &tenant_id,
                 &employee_id,
                 &people_id
             ]
-        ).await {
+        )).await {
             Err(e) => {
-                error!("unable to employee record: {:?}", e);
-                return Err(DataError::ToBeImplemented(String::from("Data::add()")));
+                error!("unable to add employee record: {:?}", e);
+                return Err(DataError::from(&e));
             }
             Ok(_) => {
                 return Ok(());
@@ -137,7 +167,7 @@ impl Data {
         let result = self.pool.get().await;
         if let Err(e) = result {
             error!("unable to retrieve database client: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::Unavailable);
         }
         let client = result.unwrap();
 
@@ -146,54 +176,96 @@ impl Data {
         ).await;
         if let Err(e) = result {
             error!("unable to prepare database statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         }
         let stmt = result.unwrap();
 
-        match client.query(
+        match timed("hr.employee_fetch", client.query(
             &stmt,
             &[
                 &tenant_id
             ]
-        ).await {
+        )).await {
             Err(e) => {
                 error!("unable to retrieved employee records: {:?}", e);
-                return Err(DataError::ToBeImplemented(String::from("Data::fetch()")));
+                return Err(DataError::from(&e));
             }
             Ok(rows) => {
-                let result = rows.iter().map(|r| {
-                    let employee_id: uuid::Uuid = r.get("id");
-
-                    let people_id: uuid::Uuid = r.get("people_id");
-                    let given_name: String = r.get("given_name");
-                    let middle_name: String = r.get("middle_name");
-                    let family_name: String = r.get("family_name");
-                    let prefix: String = r.get("prefix");
-                    let suffix: String = r.get("suffix");
-                    let gender_id: i16 = r.get("gender_id");
-                    let ethnicity_id: i16 = r.get("ethnicity_id");
-                    let marital_status_id: i16 = r.get("marital_status_id");
-
-                    return Employee::new(
-                        &employee_id,
-                        &People::new(
-                            &people_id,
-                            &true,
-                            &given_name,
-                            &middle_name,
-                            &family_name,
-                            &prefix,
-                            &suffix,
-                            &gender_id, 
-                            &ethnicity_id,
-                            &marital_status_id
-                        )
-                    );
-                })
-                .collect();
-
-                return Ok(result);
+                return Ok(rows.iter().map(Self::employee).collect());
             }
         }
     }
-}
\ No newline at end of file
+
+    /// fetch a page of employees
+    pub async fn fetch_page(
+        &self,
+        tenant_id: &uuid::Uuid,
+        offset: &i64,
+        limit: &i64
+    ) -> Result<Vec<Employee>, DataError> {
+        info!("Data::fetch_page()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "select * from hr.employee_fetch($1) offset $2 limit $3"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("hr.employee_fetch", client.query(
+            &stmt,
+            &[
+                &tenant_id,
+                &offset,
+                &limit
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to retrieved employee records: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(rows) => {
+                return Ok(rows.iter().map(Self::employee).collect());
+            }
+        }
+    }
+
+    fn employee(r: &Row) -> Employee {
+        let employee_id: uuid::Uuid = r.get("id");
+
+        let people_id: uuid::Uuid = r.get("people_id");
+        let given_name: String = r.get("given_name");
+        let middle_name: String = r.get("middle_name");
+        let family_name: String = r.get("family_name");
+        let prefix: String = r.get("prefix");
+        let suffix: String = r.get("suffix");
+        let gender_id: i16 = r.get("gender_id");
+        let ethnicity_id: i16 = r.get("ethnicity_id");
+        let marital_status_id: i16 = r.get("marital_status_id");
+
+        return Employee::new(
+            &employee_id,
+            &People::new(
+                &people_id,
+                &true,
+                &given_name,
+                &middle_name,
+                &family_name,
+                &prefix,
+                &suffix,
+                &gender_id, 
+                &ethnicity_id,
+                &marital_status_id
+            )
+        );
+    }
+}
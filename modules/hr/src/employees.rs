@@ -5,10 +5,14 @@ use log::{
 };
 
 use configuration::ApplicationConfiguration;
-use people::people::PeopleError;
+use common::hr::people::People;
+use data::pg::unit_of_work::UnitOfWork;
 
 use crate::HrError;
 
+/// largest page of employee records returned at once
+pub const PAGE_SIZE_MAX: u32 = 100;
+
 // #[derive(Debug)]
 // pub enum EmployeesError {
 //     ToBeImplemented(String),
@@ -52,7 +56,7 @@ impl Employees {
         ).await {
             Err(e) => {
                 error!("unable to add employee record: {:?}", e);
-                return Err(HrError::ToBeImplemented(String::from("Data::add()")));
+                return Err(HrError::DataError(e));
             }
             Ok(_) => {
                 return Ok(());
@@ -60,6 +64,74 @@ impl Employees {
         }
     }
 
+    /// add a people record and an employee for it. both records are added
+    /// or neither is
+    pub async fn add_with_people(
+        &self,
+        tenant_id: &uuid::Uuid,
+        employee_id: &uuid::Uuid,
+        people: &People
+    ) -> Result<(), HrError> {
+        info!("Employees::add_with_people()");
+
+        let uow = match self.data.begin().await {
+            Err(e) => {
+                error!("unable to start unit of work: {:?}", e);
+                return Err(HrError::DataError(e));
+            }
+            Ok(uow) => uow
+        };
+
+        if let Err(e) = self.add_with_people_in(&uow, &tenant_id, &employee_id, &people).await {
+            if let Err(e) = uow.rollback().await {
+                error!("unable to roll back employee record: {:?}", e);
+            }
+            return Err(e);
+        }
+
+        if let Err(e) = uow.commit().await {
+            error!("unable to commit employee record: {:?}", e);
+            return Err(HrError::DataError(e));
+        }
+        return Ok(());
+    }
+
+    async fn add_with_people_in(
+        &self,
+        uow: &UnitOfWork,
+        tenant_id: &uuid::Uuid,
+        employee_id: &uuid::Uuid,
+        people: &People
+    ) -> Result<(), HrError> {
+        if let Err(e) = self.people.add_in(
+            &uow,
+            &tenant_id,
+            &people.id(),
+            &people.given_name(),
+            &people.middle_name(),
+            &people.family_name(),
+            &people.prefix(),
+            &people.suffix(),
+            &people.gender_id(),
+            &people.ethnicity_id(),
+            &people.marital_state_id()
+        ).await {
+            error!("unable to add people record: {:?}", e);
+            return Err(HrError::from(e));
+        }
+
+        if let Err(e) = self.data.add_in(
+            &uow,
+            &tenant_id,
+            &employee_id,
+            &people.id()
+        ).await {
+            error!("unable to add employee record: {:?}", e);
+            return Err(HrError::DataError(e));
+        }
+        return Ok(());
+    }
+
     pub async fn fetch(
         &self,
         tenant_id: &uuid::Uuid
@@ -71,7 +143,36 @@ impl Employees {
         ).await {
             Err(e) => {
                 error!("unable to fetch employee records: {:?}", e);
-                return Err(HrError::ToBeImplemented(String::from("Data::fetch()")));
+                return Err(HrError::DataError(e));
+            }
+            Ok(result) => {
+                return Ok(result);
+            }
+        }
+    }
+
+    /// fetch a page of employees, pages start at 1
+    pub async fn page(
+        &self,
+        tenant_id: &uuid::Uuid,
+        page: &u32,
+        size: &u32
+    ) -> Result<Vec<common::hr::employee::Employee>, HrError> {
+        info!("Employees::page()");
+
+        if *page < 1 || *size < 1 || *size > PAGE_SIZE_MAX {
+            return Err(HrError::ValidationError);
+        }
+
+        let offset = (i64::from(*page) - 1) * i64::from(*size);
+        match self.data.fetch_page(
+            &tenant_id,
+            &offset,
+            &i64::from(*size)
+        ).await {
+            Err(e) => {
+                error!("unable to fetch employee records: {:?}", e);
+                return Err(HrError::DataError(e));
             }
             Ok(result) => {
                 return Ok(result);
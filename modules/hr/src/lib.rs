@@ -7,13 +7,36 @@ use log::{
     error
 };
 
+use ::data::pg::DataError;
+use people::people::PeopleError;
+
 #[derive(Debug)]
 pub enum HrError {
-    ToBeImplemented(String),
+    DataError(DataError),
     ConfigurationError,
     ValidationError
 }
 
+impl From<PeopleError> for HrError {
+    fn from(e: PeopleError) -> Self {
+        match e {
+            PeopleError::DataError(e) => HrError::DataError(e),
+            PeopleError::ConfigurationError => HrError::ConfigurationError,
+            PeopleError::ValidationError => HrError::ValidationError
+        }
+    }
+}
+
+impl From<HrError> for common::Error {
+    fn from(e: HrError) -> Self {
+        match e {
+            HrError::DataError(e) => common::Error::from(e),
+            HrError::ConfigurationError => common::Error::ConfigurationError(String::from("hr is not configured")),
+            HrError::ValidationError => common::Error::ValidationError(String::from("invalid employee record"))
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct Hr {
     employees: employees::Employees
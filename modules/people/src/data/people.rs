@@ -7,6 +7,7 @@ use log::{
 use std::str::FromStr;
 
 use deadpool_postgres::{ 
+    Client,
     Manager, 
     ManagerConfig, 
     Pool, 
@@ -19,6 +20,8 @@ use tokio_postgres::{
 use tokio_postgres::config::{ Config };
 
 use data::pg::DataError;
+use data::pg::timed;
+use data::pg::unit_of_work::UnitOfWork;
 
 
 use configuration::{
@@ -96,20 +99,80 @@ impl Data {
         let result = self.pool.get().await;
         if let Err(e) = result {
             error!("unable to retrieve database client: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::Unavailable);
         }
         let client = result.unwrap();
 
+        return Self::add_on(
+            &client,
+            &tenant_id,
+            &people_id,
+            &given_name,
+            &middle_name,
+            &family_name,
+            &prefix,
+            &suffix,
+            &gender_id,
+            &ethnicity_id,
+            &marital_state_id
+        ).await;
+    }
+
+    /// add a people record as part of a unit of work
+    pub async fn add_in(
+        &self,
+        uow: &UnitOfWork,
+        tenant_id: &uuid::Uuid,
+        people_id: &uuid::Uuid,
+        given_name: &str,
+        middle_name: &str,
+        family_name: &str,
+        prefix: &str,
+        suffix: &str,
+        gender_id: &i16,
+        ethnicity_id: &i16,
+        marital_state_id: &i16
+    ) -> Result<(), DataError> {
+        info!("Data::add_in()");
+
+        return Self::add_on(
+            uow.client(),
+            &tenant_id,
+            &people_id,
+            &given_name,
+            &middle_name,
+            &family_name,
+            &prefix,
+            &suffix,
+            &gender_id,
+            &ethnicity_id,
+            &marital_state_id
+        ).await;
+    }
+
+    async fn add_on(
+        client: &Client,
+        tenant_id: &uuid::Uuid,
+        people_id: &uuid::Uuid,
+        given_name: &str,
+        middle_name: &str,
+        family_name: &str,
+        prefix: &str,
+        suffix: &str,
+        gender_id: &i16,
+        ethnicity_id: &i16,
+        marital_state_id: &i16
+    ) -> Result<(), DataError> {
         let result = client.prepare_cached(
             "call people.people_add($1,$2,$3,$4,$5,$6,$7,$8,$9,$10)"
         ).await;
         if let Err(e) = result {
             error!("unable to prepare database statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         }
         let stmt = result.unwrap();
 
-        match client.execute(
+        match timed("people.people_add", client.execute(
             &stmt,
             &[
                 &tenant_id,
@@ -123,10 +186,10 @@ impl Data {
                 &ethnicity_id,
                 &marital_state_id
             ]
-        ).await {
+        )).await {
             Err(e) => {
                 error!("unable to add people record: {:?}", e);
-                return Err(DataError::DatabaseError);
+                return Err(DataError::from(&e));
             }
             Ok(_) => {
                 return Ok(());
@@ -143,7 +206,7 @@ impl Data {
         let result = self.pool.get().await;
         if let Err(e) = result {
             error!("unable to retrieve database client: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::Unavailable);
         }
         let client = result.unwrap();
 
@@ -152,19 +215,19 @@ impl Data {
         ).await;
         if let Err(e) = result {
             error!("unable to prepare database statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         }
         let stmt = result.unwrap();
 
-        match client.query_one(
+        match timed("people.people_get_by_id", client.query_one(
             &stmt,
             &[
                 &people_id
             ]
-        ).await {
+        )).await {
             Err(e) => {
                 error!("unable to retrieve people record by id: {:?}", e);
-                return Err(DataError::ToBeImplemented(String::from("Data::by_id()")));
+                return Err(DataError::from(&e));
             }
             Ok(row) => {
                 debug!("row: {:?}", row);
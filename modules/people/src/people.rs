@@ -5,14 +5,26 @@ use log::{
 };
 
 use configuration::ApplicationConfiguration;
+use data::pg::DataError;
+use data::pg::unit_of_work::UnitOfWork;
 
 #[derive(Debug)]
 pub enum PeopleError {
-    ToBeImplemented(String),
+    DataError(DataError),
     ConfigurationError,
     ValidationError
 }
 
+impl From<PeopleError> for common::Error {
+    fn from(e: PeopleError) -> Self {
+        match e {
+            PeopleError::DataError(e) => common::Error::from(e),
+            PeopleError::ConfigurationError => common::Error::ConfigurationError(String::from("people are not configured")),
+            PeopleError::ValidationError => common::Error::ValidationError(String::from("invalid people record"))
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct People {
     data: crate::data::people::Data
@@ -66,7 +78,47 @@ impl People {
         ).await {
             Err(e) => {
                 error!("unable to add people record");
-                return Err(PeopleError::ToBeImplemented(String::from("People::add")));
+                return Err(PeopleError::DataError(e));
+            }
+            Ok(_) => {
+                return Ok(());
+            }
+        }
+    }
+
+    /// add a people record as part of a unit of work
+    pub async fn add_in(
+        &self,
+        uow: &UnitOfWork,
+        tenant_id: &uuid::Uuid,
+        people_id: &uuid::Uuid,
+        given_name: &str,
+        middle_name: &str,
+        family_name: &str,
+        prefix: &str,
+        suffix: &str,
+        gender_id: &i16,
+        ethnicity_id: &i16,
+        marital_state_id: &i16
+    ) -> Result<(), PeopleError> {
+        info!("People::add_in()");
+
+        match self.data.add_in(
+            &uow,
+            &tenant_id,
+            &people_id,
+            &given_name,
+            &middle_name,
+            &family_name,
+            &prefix,
+            &suffix,
+            &gender_id,
+            &ethnicity_id,
+            &marital_state_id
+        ).await {
+            Err(e) => {
+                error!("unable to add people record: {:?}", e);
+                return Err(PeopleError::DataError(e));
             }
             Ok(_) => {
                 return Ok(());
@@ -84,7 +136,7 @@ impl People {
         match self.data.by_id(&people_id).await {
             Err(e) => {
                 error!("unable to retrieve people record");
-                return Err(PeopleError::ToBeImplemented(String::from("People::by_id()")));
+                return Err(PeopleError::DataError(e));
             }
             Ok(people) => {
                 debug!("result: {:?}", people);
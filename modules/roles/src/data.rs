@@ -23,7 +23,13 @@ use configuration::{
     ProviderType
 };
 
+use tokio_postgres::row::Row;
+
 use data::pg::DataError;
+use data::pg::timed;
+use data::pg::unit_of_work::UnitOfWork;
+use common::role::Role;
+use common::permission::Permission;
 
 
 #[derive(Debug, Clone)]
@@ -78,4 +84,636 @@ impl Data {
             pool: data.get_pg_pool().unwrap()
         };
     }
-}
\ No newline at end of file
+
+    fn role(row: &Row) -> Role {
+        return Role::new(
+            &row.get("id"),
+            &row.get("tenant_id"),
+            &row.get("active"),
+            row.get("name"),
+            row.get("slug"),
+            row.get("description"),
+            &row.get("built_in")
+        );
+    }
+
+    /// add a role to a tenant
+    pub async fn role_add(
+        &self,
+        role_id: &uuid::Uuid,
+        tenant_id: &uuid::Uuid,
+        name: &str,
+        slug: &str,
+        description: &str,
+        built_in: &bool
+    ) -> Result<(), DataError> {
+        info!("Data::role_add()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        return Self::role_add_on(
+            &client,
+            &role_id,
+            &tenant_id,
+            &name,
+            &slug,
+            &description,
+            &built_in
+        ).await;
+    }
+
+    /// add a role to a tenant as part of a unit of work
+    pub async fn role_add_in(
+        &self,
+        uow: &UnitOfWork,
+        role_id: &uuid::Uuid,
+        tenant_id: &uuid::Uuid,
+        name: &str,
+        slug: &str,
+        description: &str,
+        built_in: &bool
+    ) -> Result<(), DataError> {
+        info!("Data::role_add_in()");
+
+        return Self::role_add_on(
+            uow.client(),
+            &role_id,
+            &tenant_id,
+            &name,
+            &slug,
+            &description,
+            &built_in
+        ).await;
+    }
+
+    async fn role_add_on(
+        client: &Client,
+        role_id: &uuid::Uuid,
+        tenant_id: &uuid::Uuid,
+        name: &str,
+        slug: &str,
+        description: &str,
+        built_in: &bool
+    ) -> Result<(), DataError> {
+        let result = client.prepare_cached(
+            "call iam.role_add($1, $2, $3, $4, $5, $6)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("iam.role_add", client.execute(
+            &stmt,
+            &[
+                &role_id,
+                &tenant_id,
+                &name,
+                &slug,
+                &description,
+                &built_in
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(_) => {
+                return Ok(());
+            }
+        }
+    }
+
+    /// retrieve roles of a tenant
+    pub async fn roles_fetch(
+        &self,
+        tenant_id: &uuid::Uuid
+    ) -> Result<Vec<Role>, DataError> {
+        info!("Data::roles_fetch()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "select * from iam.roles_fetch($1)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("iam.roles_fetch", client.query(
+            &stmt,
+            &[
+                &tenant_id
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(rows) => {
+                return Ok(rows.iter().map(Self::role).collect());
+            }
+        }
+    }
+
+    /// retrieve a role of a tenant. returns None if the role does not exist
+    pub async fn role_get(
+        &self,
+        tenant_id: &uuid::Uuid,
+        role_id: &uuid::Uuid
+    ) -> Result<Option<Role>, DataError> {
+        info!("Data::role_get()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "select * from iam.role_get($1, $2)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("iam.role_get", client.query_opt(
+            &stmt,
+            &[
+                &tenant_id,
+                &role_id
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(row) => {
+                return Ok(row.as_ref().map(Self::role));
+            }
+        }
+    }
+
+    pub async fn role_update(
+        &self,
+        tenant_id: &uuid::Uuid,
+        role_id: &uuid::Uuid,
+        name: &str,
+        description: &str
+    ) -> Result<(), DataError> {
+        info!("Data::role_update()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "call iam.role_update($1, $2, $3, $4)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("iam.role_update", client.execute(
+            &stmt,
+            &[
+                &tenant_id,
+                &role_id,
+                &name,
+                &description
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(_) => {
+                return Ok(());
+            }
+        }
+    }
+
+    pub async fn role_set_active(
+        &self,
+        tenant_id: &uuid::Uuid,
+        role_id: &uuid::Uuid,
+        active: &bool
+    ) -> Result<(), DataError> {
+        info!("Data::role_set_active()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "call iam.role_set_active($1, $2, $3)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("iam.role_set_active", client.execute(
+            &stmt,
+            &[
+                &tenant_id,
+                &role_id,
+                &active
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(_) => {
+                return Ok(());
+            }
+        }
+    }
+
+    /// remove a role, its permissions and its assignments to users
+    pub async fn role_remove(
+        &self,
+        tenant_id: &uuid::Uuid,
+        role_id: &uuid::Uuid
+    ) -> Result<(), DataError> {
+        info!("Data::role_remove()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "call iam.role_remove($1, $2)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("iam.role_remove", client.execute(
+            &stmt,
+            &[
+                &tenant_id,
+                &role_id
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(_) => {
+                return Ok(());
+            }
+        }
+    }
+
+    /// retrieve the permission catalog
+    pub async fn permissions_fetch(
+        &self
+    ) -> Result<Vec<Permission>, DataError> {
+        info!("Data::permissions_fetch()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "select * from iam.permissions_fetch()"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("iam.permissions_fetch", client.query(
+            &stmt,
+            &[]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(rows) => {
+                return Ok(rows.iter().map(|r| {
+                    let name: String = r.get("name");
+                    let description: String = r.get("description");
+                    return Permission::new(&name, &description);
+                }).collect());
+            }
+        }
+    }
+
+    /// retrieve names of the permissions granted by a role
+    pub async fn role_permissions_fetch(
+        &self,
+        tenant_id: &uuid::Uuid,
+        role_id: &uuid::Uuid
+    ) -> Result<Vec<String>, DataError> {
+        info!("Data::role_permissions_fetch()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "select * from iam.role_permissions_fetch($1, $2)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("iam.role_permissions_fetch", client.query(
+            &stmt,
+            &[
+                &tenant_id,
+                &role_id
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(rows) => {
+                return Ok(rows.iter().map(|r| {
+                    let name: String = r.get("name");
+                    return name;
+                }).collect());
+            }
+        }
+    }
+
+    /// replace the permissions granted by a role
+    pub async fn role_permissions_set(
+        &self,
+        tenant_id: &uuid::Uuid,
+        role_id: &uuid::Uuid,
+        permissions: &Vec<String>
+    ) -> Result<(), DataError> {
+        info!("Data::role_permissions_set()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        return Self::role_permissions_set_on(
+            &client,
+            &tenant_id,
+            &role_id,
+            &permissions
+        ).await;
+    }
+
+    /// replace the permissions granted by a role as part of a unit of work
+    pub async fn role_permissions_set_in(
+        &self,
+        uow: &UnitOfWork,
+        tenant_id: &uuid::Uuid,
+        role_id: &uuid::Uuid,
+        permissions: &Vec<String>
+    ) -> Result<(), DataError> {
+        info!("Data::role_permissions_set_in()");
+
+        return Self::role_permissions_set_on(
+            uow.client(),
+            &tenant_id,
+            &role_id,
+            &permissions
+        ).await;
+    }
+
+    async fn role_permissions_set_on(
+        client: &Client,
+        tenant_id: &uuid::Uuid,
+        role_id: &uuid::Uuid,
+        permissions: &Vec<String>
+    ) -> Result<(), DataError> {
+        let result = client.prepare_cached(
+            "call iam.role_permissions_set($1, $2, $3)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("iam.role_permissions_set", client.execute(
+            &stmt,
+            &[
+                &tenant_id,
+                &role_id,
+                &permissions
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(_) => {
+                return Ok(());
+            }
+        }
+    }
+
+    /// assign a role to a member of the tenant
+    pub async fn user_role_add(
+        &self,
+        tenant_id: &uuid::Uuid,
+        user_id: &uuid::Uuid,
+        role_id: &uuid::Uuid
+    ) -> Result<(), DataError> {
+        info!("Data::user_role_add()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        return Self::user_role_add_on(
+            &client,
+            &tenant_id,
+            &user_id,
+            &role_id
+        ).await;
+    }
+
+    /// assign a role as part of a unit of work
+    pub async fn user_role_add_in(
+        &self,
+        uow: &UnitOfWork,
+        tenant_id: &uuid::Uuid,
+        user_id: &uuid::Uuid,
+        role_id: &uuid::Uuid
+    ) -> Result<(), DataError> {
+        info!("Data::user_role_add_in()");
+
+        return Self::user_role_add_on(
+            uow.client(),
+            &tenant_id,
+            &user_id,
+            &role_id
+        ).await;
+    }
+
+    async fn user_role_add_on(
+        client: &Client,
+        tenant_id: &uuid::Uuid,
+        user_id: &uuid::Uuid,
+        role_id: &uuid::Uuid
+    ) -> Result<(), DataError> {
+        let result = client.prepare_cached(
+            "call iam.user_role_add($1, $2, $3)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("iam.user_role_add", client.execute(
+            &stmt,
+            &[
+                &tenant_id,
+                &user_id,
+                &role_id
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(_) => {
+                return Ok(());
+            }
+        }
+    }
+
+    /// remove a role from a user. returns false, removing nothing, if the
+    /// user is the last owner of the tenant
+    pub async fn user_role_remove(
+        &self,
+        tenant_id: &uuid::Uuid,
+        user_id: &uuid::Uuid,
+        role_id: &uuid::Uuid
+    ) -> Result<bool, DataError> {
+        info!("Data::user_role_remove()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "select * from iam.user_role_remove($1, $2, $3)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("iam.user_role_remove", client.query_one(
+            &stmt,
+            &[
+                &tenant_id,
+                &user_id,
+                &role_id
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(row) => {
+                let removed: bool = row.get(0);
+                return Ok(removed);
+            }
+        }
+    }
+
+    /// retrieve roles assigned to a user in a tenant
+    pub async fn user_roles_fetch(
+        &self,
+        tenant_id: &uuid::Uuid,
+        user_id: &uuid::Uuid
+    ) -> Result<Vec<Role>, DataError> {
+        info!("Data::user_roles_fetch()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "select * from iam.user_roles_fetch($1, $2)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("iam.user_roles_fetch", client.query(
+            &stmt,
+            &[
+                &tenant_id,
+                &user_id
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(rows) => {
+                return Ok(rows.iter().map(Self::role).collect());
+            }
+        }
+    }
+}
@@ -4,30 +4,78 @@ use log::{
     error
 };
 
-use chrono::prelude::*;
-use serde::{
-    Serialize,
-    Deserialize
-};
+use configuration::ApplicationConfiguration;
+use common::role::Role;
+use common::permission::Permission;
+use common::cache::PermissionCache;
+
+use data::pg::DataError;
+use data::pg::unit_of_work::UnitOfWork;
 
-use configuration::{
-    ApplicationConfiguration,
-    ProviderType
-};
 use crate::data::Data;
 
 
+/// built-in role granted every permission, assigned to the user adding a tenant
+pub const ROLE_OWNER: &str = "owner";
+
+/// built-in role granted every permission except the owner permissions
+pub const ROLE_ADMIN: &str = "admin";
+
+/// built-in role granted every read permission
+pub const ROLE_MEMBER: &str = "member";
+
+/// built-in roles seeded for every tenant: slug, name, description
+const BUILT_IN_ROLES: [(&str, &str, &str); 3] = [
+    (ROLE_OWNER, "Owner", "Full access to the tenant"),
+    (ROLE_ADMIN, "Administrator", "Manages the tenant, its members and their roles"),
+    (ROLE_MEMBER, "Member", "Read access to the tenant")
+];
+
+/// permissions only granted to tenant owners
+const OWNER_PERMISSIONS: [&str; 2] = [
+    "tenants.set_active",
+    "tenants.set_mfa"
+];
+
+
 #[derive(Debug)]
 pub enum RolesError {
-    ToBeImplemented(String),
-    ConfigurationError
+    DataError(DataError),
+    ConfigurationError,
+    ValidationError(String),
+    NotFound,
+    /// built-in roles cannot be changed or removed
+    BuiltInRole,
+    InactiveRole,
+    /// a tenant keeps at least one owner
+    LastOwner,
+    /// permission names that are not in the permission catalog
+    UnknownPermissions(Vec<String>)
+}
+
+impl From<RolesError> for common::Error {
+    fn from(e: RolesError) -> Self {
+        match e {
+            RolesError::DataError(e) => common::Error::from(e),
+            RolesError::ConfigurationError => common::Error::ConfigurationError(String::from("roles are not configured")),
+            RolesError::ValidationError(message) => common::Error::ValidationError(message),
+            RolesError::NotFound => common::Error::NotFound(String::from("role not found")),
+            RolesError::BuiltInRole => common::Error::Conflict(String::from("built-in roles cannot be changed")),
+            RolesError::InactiveRole => common::Error::Conflict(String::from("role is not active")),
+            RolesError::LastOwner => common::Error::Conflict(String::from("the last owner of a tenant cannot be removed")),
+            RolesError::UnknownPermissions(_) => common::Error::ValidationError(String::from("unknown permissions"))
+        }
+    }
 }
 
 
 #[derive(Debug, Clone)]
 pub struct Roles {
     cfg: ApplicationConfiguration,
-    data: crate::data::Data
+    data: Data,
+    /// permissions cached for authenticating requests, shared with the
+    /// users module and discarded whenever roles change
+    permissions: PermissionCache
 }
 
 
@@ -35,12 +83,415 @@ impl Roles {
 
     pub fn new(
         cfg: ApplicationConfiguration,
-        data: data::Data
+        data: data::Data,
+        permissions: PermissionCache
     ) -> Self {
-        let roles_data = crate::data::Data::new(data);
+        let roles_data = Data::new(data);
         return Self {
             cfg: cfg,
-            data: roles_data
+            data: roles_data,
+            permissions: permissions
         };
     }
-}
\ No newline at end of file
+
+    /// add a role to a tenant. the slug is derived from the name
+    pub async fn role_add(
+        &self,
+        tenant_id: &uuid::Uuid,
+        name: &str,
+        description: &str
+    ) -> Result<Role, RolesError> {
+        info!("Roles::role_add()");
+
+        let name = name.trim();
+        let slug = Self::slug(&name);
+        if slug.is_empty() {
+            return Err(RolesError::ValidationError(String::from("name is required")));
+        }
+        if BUILT_IN_ROLES.iter().any(|(built_in, _, _)| *built_in == slug) {
+            return Err(RolesError::ValidationError(format!("{} is reserved for a built-in role", slug)));
+        }
+
+        let role = Role::new(
+            &uuid::Uuid::new_v4(),
+            &tenant_id,
+            &true,
+            &name,
+            &slug,
+            &description,
+            &false
+        );
+        self.add(&role).await?;
+        return Ok(role);
+    }
+
+    pub async fn roles_fetch(
+        &self,
+        tenant_id: &uuid::Uuid
+    ) -> Result<Vec<Role>, RolesError> {
+        info!("Roles::roles_fetch()");
+
+        match self.data.roles_fetch(&tenant_id).await {
+            Err(e) => {
+                error!("unable to retrieve roles: {:?}", e);
+                return Err(RolesError::DataError(e));
+            }
+            Ok(roles) => {
+                return Ok(roles);
+            }
+        }
+    }
+
+    pub async fn role_get(
+        &self,
+        tenant_id: &uuid::Uuid,
+        role_id: &uuid::Uuid
+    ) -> Result<Role, RolesError> {
+        info!("Roles::role_get()");
+
+        match self.data.role_get(&tenant_id, &role_id).await {
+            Err(e) => {
+                error!("unable to retrieve role: {:?}", e);
+                return Err(RolesError::DataError(e));
+            }
+            Ok(None) => {
+                return Err(RolesError::NotFound);
+            }
+            Ok(Some(role)) => {
+                return Ok(role);
+            }
+        }
+    }
+
+    pub async fn role_update(
+        &self,
+        tenant_id: &uuid::Uuid,
+        role_id: &uuid::Uuid,
+        name: &str,
+        description: &str
+    ) -> Result<(), RolesError> {
+        info!("Roles::role_update()");
+
+        if name.trim().is_empty() {
+            return Err(RolesError::ValidationError(String::from("name is required")));
+        }
+        self.custom_role_get(&tenant_id, &role_id).await?;
+
+        if let Err(e) = self.data.role_update(&tenant_id, &role_id, name.trim(), &description).await {
+            error!("unable to update role: {:?}", e);
+            return Err(RolesError::DataError(e));
+        }
+        return Ok(());
+    }
+
+    /// set the active status of a role. permissions of inactive roles are
+    /// not granted
+    pub async fn role_set_active(
+        &self,
+        tenant_id: &uuid::Uuid,
+        role_id: &uuid::Uuid,
+        active: &bool
+    ) -> Result<(), RolesError> {
+        info!("Roles::role_set_active()");
+
+        self.custom_role_get(&tenant_id, &role_id).await?;
+
+        if let Err(e) = self.data.role_set_active(&tenant_id, &role_id, &active).await {
+            error!("unable to set role active status: {:?}", e);
+            return Err(RolesError::DataError(e));
+        }
+        self.permissions.invalidate_tenant(&tenant_id);
+        return Ok(());
+    }
+
+    /// remove a role and its assignments to users
+    pub async fn role_remove(
+        &self,
+        tenant_id: &uuid::Uuid,
+        role_id: &uuid::Uuid
+    ) -> Result<(), RolesError> {
+        info!("Roles::role_remove()");
+
+        self.custom_role_get(&tenant_id, &role_id).await?;
+
+        if let Err(e) = self.data.role_remove(&tenant_id, &role_id).await {
+            error!("unable to remove role: {:?}", e);
+            return Err(RolesError::DataError(e));
+        }
+        self.permissions.invalidate_tenant(&tenant_id);
+        return Ok(());
+    }
+
+    /// retrieve the permission catalog
+    pub async fn permissions_fetch(&self) -> Result<Vec<Permission>, RolesError> {
+        info!("Roles::permissions_fetch()");
+
+        match self.data.permissions_fetch().await {
+            Err(e) => {
+                error!("unable to retrieve permissions: {:?}", e);
+                return Err(RolesError::DataError(e));
+            }
+            Ok(permissions) => {
+                return Ok(permissions);
+            }
+        }
+    }
+
+    pub async fn role_permissions_fetch(
+        &self,
+        tenant_id: &uuid::Uuid,
+        role_id: &uuid::Uuid
+    ) -> Result<Vec<String>, RolesError> {
+        info!("Roles::role_permissions_fetch()");
+
+        self.role_get(&tenant_id, &role_id).await?;
+
+        match self.data.role_permissions_fetch(&tenant_id, &role_id).await {
+            Err(e) => {
+                error!("unable to retrieve role permissions: {:?}", e);
+                return Err(RolesError::DataError(e));
+            }
+            Ok(permissions) => {
+                return Ok(permissions);
+            }
+        }
+    }
+
+    /// replace the permissions granted by a role. every permission has to be
+    /// in the permission catalog
+    pub async fn role_permissions_set(
+        &self,
+        tenant_id: &uuid::Uuid,
+        role_id: &uuid::Uuid,
+        permissions: &Vec<String>
+    ) -> Result<(), RolesError> {
+        info!("Roles::role_permissions_set()");
+
+        self.custom_role_get(&tenant_id, &role_id).await?;
+
+        let catalog = self.permissions_fetch().await?;
+        let unknown: Vec<String> = permissions.iter()
+            .filter(|p| !catalog.iter().any(|c| c.name() == **p))
+            .cloned()
+            .collect();
+        if !unknown.is_empty() {
+            return Err(RolesError::UnknownPermissions(unknown));
+        }
+
+        let mut permissions = permissions.clone();
+        permissions.sort();
+        permissions.dedup();
+        if let Err(e) = self.data.role_permissions_set(&tenant_id, &role_id, &permissions).await {
+            error!("unable to set role permissions: {:?}", e);
+            return Err(RolesError::DataError(e));
+        }
+        self.permissions.invalidate_tenant(&tenant_id);
+        return Ok(());
+    }
+
+    /// assign a role to a member of the tenant
+    pub async fn user_role_add(
+        &self,
+        tenant_id: &uuid::Uuid,
+        user_id: &uuid::Uuid,
+        role_id: &uuid::Uuid
+    ) -> Result<(), RolesError> {
+        info!("Roles::user_role_add()");
+
+        let role = self.role_get(&tenant_id, &role_id).await?;
+        if !role.active() {
+            return Err(RolesError::InactiveRole);
+        }
+
+        if let Err(e) = self.data.user_role_add(&tenant_id, &user_id, &role_id).await {
+            error!("unable to assign role: {:?}", e);
+            return Err(RolesError::DataError(e));
+        }
+        self.permissions.invalidate(&user_id, &tenant_id);
+        return Ok(());
+    }
+
+    /// remove a role from a member of the tenant. the last owner of the
+    /// tenant keeps the owner role
+    pub async fn user_role_remove(
+        &self,
+        tenant_id: &uuid::Uuid,
+        user_id: &uuid::Uuid,
+        role_id: &uuid::Uuid
+    ) -> Result<(), RolesError> {
+        info!("Roles::user_role_remove()");
+
+        match self.data.user_role_remove(&tenant_id, &user_id, &role_id).await {
+            Err(e) => {
+                error!("unable to remove role assignment: {:?}", e);
+                return Err(RolesError::DataError(e));
+            }
+            Ok(false) => {
+                return Err(RolesError::LastOwner);
+            }
+            Ok(true) => {
+                self.permissions.invalidate(&user_id, &tenant_id);
+                return Ok(());
+            }
+        }
+    }
+
+    pub async fn user_roles_fetch(
+        &self,
+        tenant_id: &uuid::Uuid,
+        user_id: &uuid::Uuid
+    ) -> Result<Vec<Role>, RolesError> {
+        info!("Roles::user_roles_fetch()");
+
+        match self.data.user_roles_fetch(&tenant_id, &user_id).await {
+            Err(e) => {
+                error!("unable to retrieve user roles: {:?}", e);
+                return Err(RolesError::DataError(e));
+            }
+            Ok(roles) => {
+                return Ok(roles);
+            }
+        }
+    }
+
+    /// add the built-in roles to a new tenant and assign the owner role
+    /// to the user who added the tenant, within the unit of work that
+    /// adds the tenant
+    pub async fn tenant_roles_seed(
+        &self,
+        uow: &UnitOfWork,
+        tenant_id: &uuid::Uuid,
+        owner_id: &uuid::Uuid
+    ) -> Result<(), RolesError> {
+        info!("Roles::tenant_roles_seed()");
+
+        let catalog = self.permissions_fetch().await?;
+        for (slug, name, description) in BUILT_IN_ROLES {
+            let role = Role::new(
+                &uuid::Uuid::new_v4(),
+                &tenant_id,
+                &true,
+                &name,
+                &slug,
+                &description,
+                &true
+            );
+            if let Err(e) = self.data.role_add_in(
+                &uow,
+                &role.id(),
+                &role.tenant_id(),
+                &role.name(),
+                &role.slug(),
+                &role.description(),
+                &role.built_in()
+            ).await {
+                error!("unable to add role: {:?}", e);
+                return Err(RolesError::DataError(e));
+            }
+
+            let permissions = Self::built_in_permissions(&slug, &catalog);
+            debug!("seeding role {} with permissions: {:?}", slug, permissions);
+            if let Err(e) = self.data.role_permissions_set_in(&uow, &tenant_id, &role.id(), &permissions).await {
+                error!("unable to set role permissions: {:?}", e);
+                return Err(RolesError::DataError(e));
+            }
+
+            if slug == ROLE_OWNER {
+                if let Err(e) = self.data.user_role_add_in(&uow, &tenant_id, &owner_id, &role.id()).await {
+                    error!("unable to assign owner role: {:?}", e);
+                    return Err(RolesError::DataError(e));
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    async fn add(&self, role: &Role) -> Result<(), RolesError> {
+        if let Err(e) = self.data.role_add(
+            &role.id(),
+            &role.tenant_id(),
+            &role.name(),
+            &role.slug(),
+            &role.description(),
+            &role.built_in()
+        ).await {
+            error!("unable to add role: {:?}", e);
+            return Err(RolesError::DataError(e));
+        }
+        return Ok(());
+    }
+
+    /// retrieve a role that can be changed, i.e. is not built-in
+    async fn custom_role_get(
+        &self,
+        tenant_id: &uuid::Uuid,
+        role_id: &uuid::Uuid
+    ) -> Result<Role, RolesError> {
+        let role = self.role_get(&tenant_id, &role_id).await?;
+        if role.built_in() {
+            return Err(RolesError::BuiltInRole);
+        }
+        return Ok(role);
+    }
+
+    /// permissions granted by a built-in role
+    fn built_in_permissions(slug: &str, catalog: &[Permission]) -> Vec<String> {
+        return catalog.iter()
+            .map(|p| p.name())
+            .filter(|name| {
+                match slug {
+                    ROLE_OWNER => true,
+                    ROLE_ADMIN => !OWNER_PERMISSIONS.contains(&name.as_str()),
+                    ROLE_MEMBER => name.ends_with(".read"),
+                    _ => false
+                }
+            })
+            .collect();
+    }
+
+    /// url friendly identifier derived from a role name, e.g. "Sales Team" becomes "sales-team"
+    fn slug(name: &str) -> String {
+        return name.to_lowercase()
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<&str>>()
+            .join("-");
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn catalog() -> Vec<Permission> {
+        return vec![
+            Permission::new("tenants.read", ""),
+            Permission::new("tenants.update", ""),
+            Permission::new("tenants.set_active", ""),
+            Permission::new("tenants.set_mfa", ""),
+            Permission::new("roles.read", ""),
+            Permission::new("roles.assign", "")
+        ];
+    }
+
+    #[test]
+    fn test_slug() {
+        assert_eq!(Roles::slug("Sales Team"), "sales-team");
+        assert_eq!(Roles::slug("  HR / Payroll  "), "hr-payroll");
+        assert_eq!(Roles::slug("***"), "");
+    }
+
+    #[test]
+    fn test_built_in_permissions() {
+        assert_eq!(Roles::built_in_permissions(ROLE_OWNER, &catalog()).len(), 6);
+        assert_eq!(
+            Roles::built_in_permissions(ROLE_ADMIN, &catalog()),
+            vec!["tenants.read", "tenants.update", "roles.read", "roles.assign"]
+        );
+        assert_eq!(
+            Roles::built_in_permissions(ROLE_MEMBER, &catalog()),
+            vec!["tenants.read", "roles.read"]
+        );
+    }
+}
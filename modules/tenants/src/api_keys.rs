@@ -0,0 +1,177 @@
+use log::{
+    info,
+    debug,
+    error
+};
+
+use rand::{
+    thread_rng,
+    Rng,
+    distributions::Alphanumeric
+};
+use chrono::prelude::*;
+
+use configuration::ApplicationConfiguration;
+use tokenizer::Tokenizer;
+
+use common::{
+    Error,
+    api_key::ApiKey
+};
+
+use crate::data::api_keys::ApiKeysData;
+
+
+/// prefix of every generated key, makes keys easy to recognize in secret scanners
+const KEY_PREFIX: &str = "ik_";
+
+/// number of characters of the key stored in clear to identify it
+const DISPLAY_PREFIX_LENGTH: usize = 11;
+
+
+#[derive(Debug, Clone)]
+pub struct ApiKeys {
+    cfg: ApplicationConfiguration,
+    data: ApiKeysData
+}
+
+
+impl ApiKeys {
+
+    pub fn new(
+        cfg: ApplicationConfiguration,
+        data: data::Data
+    ) -> Self {
+        return Self {
+            cfg: cfg,
+            data: ApiKeysData::new(data)
+        };
+    }
+
+    /// create an api key for a tenant. returns the key record and the key
+    /// itself, which cannot be retrieved again
+    pub async fn add(
+        &self,
+        tenant_id: &uuid::Uuid,
+        name: &str,
+        permissions: &[String],
+        expires: Option<DateTime<Utc>>,
+        created_by: &uuid::Uuid
+    ) -> Result<(ApiKey, String), Error> {
+        info!("ApiKeys::add()");
+
+        if name.trim().is_empty() {
+            return Err(Error::ValidationError(String::from("name is required")));
+        }
+        if permissions.is_empty() {
+            return Err(Error::ValidationError(String::from("at least one permission is required")));
+        }
+        if let Some(expires) = expires {
+            if expires <= Utc::now() {
+                return Err(Error::ValidationError(String::from("expiry must be in the future")));
+            }
+        }
+
+        let secret: String = thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(40)
+            .map(char::from)
+            .collect();
+        let key = format!("{}{}", KEY_PREFIX, secret);
+        let prefix: String = key.chars().take(DISPLAY_PREFIX_LENGTH).collect();
+
+        let api_key_id = uuid::Uuid::new_v4();
+        match self.data.api_key_add(
+            &api_key_id,
+            &tenant_id,
+            &name,
+            &prefix,
+            &Tokenizer::digest(&key),
+            &permissions,
+            &expires,
+            &created_by
+        ).await {
+            Err(e) => {
+                error!("unable to add api key record: {:?}", e);
+                return Err(e.into());
+            }
+            Ok(_) => {
+                let api_key = ApiKey::new(
+                    &api_key_id,
+                    &tenant_id,
+                    &name,
+                    &prefix,
+                    Some(permissions.to_vec()),
+                    &Utc::now(),
+                    expires,
+                    None,
+                    false
+                );
+                return Ok((api_key, key));
+            }
+        }
+    }
+
+    /// retrieve api keys of a tenant
+    pub async fn fetch(
+        &self,
+        tenant_id: &uuid::Uuid
+    ) -> Result<Vec<ApiKey>, Error> {
+        info!("ApiKeys::fetch()");
+
+        match self.data.api_keys_fetch(&tenant_id).await {
+            Err(e) => {
+                error!("unable to retrieve api keys: {:?}", e);
+                return Err(e.into());
+            }
+            Ok(api_keys) => {
+                return Ok(api_keys);
+            }
+        }
+    }
+
+    pub async fn revoke(
+        &self,
+        tenant_id: &uuid::Uuid,
+        api_key_id: &uuid::Uuid
+    ) -> Result<(), Error> {
+        info!("ApiKeys::revoke()");
+
+        match self.data.api_key_revoke(&tenant_id, &api_key_id).await {
+            Err(e) => {
+                error!("unable to revoke api key: {:?}", e);
+                return Err(e.into());
+            }
+            Ok(_) => {
+                return Ok(());
+            }
+        }
+    }
+
+    /// resolve a key presented by a client. returns None if the key is
+    /// unknown, revoked or expired
+    pub async fn authenticate(
+        &self,
+        key: &str
+    ) -> Result<Option<ApiKey>, Error> {
+        info!("ApiKeys::authenticate()");
+
+        if !key.starts_with(KEY_PREFIX) {
+            return Ok(None);
+        }
+
+        match self.data.api_key_use(&Tokenizer::digest(&key)).await {
+            Err(e) => {
+                error!("unable to retrieve api key: {:?}", e);
+                return Err(e.into());
+            }
+            Ok(Some(api_key)) if api_key.is_valid(&Utc::now()) => {
+                return Ok(Some(api_key));
+            }
+            Ok(api_key) => {
+                debug!("rejected api key: {:?}", api_key);
+                return Ok(None);
+            }
+        }
+    }
+}
@@ -0,0 +1,221 @@
+use log::{
+    info,
+    debug,
+    error
+};
+
+use deadpool_postgres::Pool;
+use tokio_postgres::row::Row;
+
+use chrono::prelude::*;
+
+use data::pg::DataError;
+use data::pg::timed;
+use common::api_key::ApiKey;
+
+
+#[derive(Debug, Clone)]
+pub struct ApiKeysData {
+    pool: Pool
+}
+
+
+impl ApiKeysData {
+
+    pub fn new(data: data::Data) -> Self {
+        return Self {
+            pool: data.get_pg_pool().unwrap()
+        };
+    }
+
+    fn api_key(row: &Row) -> ApiKey {
+        return ApiKey::new(
+            &row.get("id"),
+            &row.get("tenant_id"),
+            row.get("name"),
+            row.get("prefix"),
+            row.get("permissions"),
+            &row.get("created"),
+            row.get("expires"),
+            row.get("last_used"),
+            row.get("revoked")
+        );
+    }
+
+    /// add api key record. only the digest of the key is stored
+    pub async fn api_key_add(
+        &self,
+        api_key_id: &uuid::Uuid,
+        tenant_id: &uuid::Uuid,
+        name: &str,
+        prefix: &str,
+        digest: &str,
+        permissions: &[String],
+        expires: &Option<DateTime<Utc>>,
+        created_by: &uuid::Uuid
+    ) -> Result<(), DataError> {
+        info!("ApiKeysData::api_key_add()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "call tenants.api_key_add($1,$2,$3,$4,$5,$6,$7,$8)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("tenants.api_key_add", client.execute(
+            &stmt,
+            &[
+                &api_key_id,
+                &tenant_id,
+                &name,
+                &prefix,
+                &digest,
+                &permissions,
+                &expires,
+                &created_by
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(_) => {
+                return Ok(());
+            }
+        }
+    }
+
+    /// retrieve api keys of a tenant, including revoked and expired keys
+    pub async fn api_keys_fetch(
+        &self,
+        tenant_id: &uuid::Uuid
+    ) -> Result<Vec<ApiKey>, DataError> {
+        info!("ApiKeysData::api_keys_fetch()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "select * from tenants.api_keys_fetch($1)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("tenants.api_keys_fetch", client.query(
+            &stmt,
+            &[
+                &tenant_id
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(rows) => {
+                return Ok(rows.iter().map(Self::api_key).collect());
+            }
+        }
+    }
+
+    /// revoke an api key of a tenant
+    pub async fn api_key_revoke(
+        &self,
+        tenant_id: &uuid::Uuid,
+        api_key_id: &uuid::Uuid
+    ) -> Result<(), DataError> {
+        info!("ApiKeysData::api_key_revoke()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "call tenants.api_key_revoke($1,$2)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("tenants.api_key_revoke", client.execute(
+            &stmt,
+            &[
+                &tenant_id,
+                &api_key_id
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(_) => {
+                return Ok(());
+            }
+        }
+    }
+
+    /// retrieve an api key by the digest of the key and record its use.
+    /// returns None if the key is unknown
+    pub async fn api_key_use(
+        &self,
+        digest: &str
+    ) -> Result<Option<ApiKey>, DataError> {
+        info!("ApiKeysData::api_key_use()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "select * from tenants.api_key_use($1)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("tenants.api_key_use", client.query_opt(
+            &stmt,
+            &[
+                &digest
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(None) => {
+                return Ok(None);
+            }
+            Ok(Some(row)) => {
+                debug!("row: {:?}", row);
+                return Ok(Some(Self::api_key(&row)));
+            }
+        }
+    }
+}
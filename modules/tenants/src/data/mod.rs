@@ -1,2 +1,3 @@
 pub mod tenants;
-pub mod organizations;
\ No newline at end of file
+pub mod organizations;
+pub mod api_keys;
\ No newline at end of file
@@ -25,7 +25,11 @@ use configuration::{
     ProviderType
 };
 
+use tokio_postgres::row::Row;
+
 use data::pg::DataError;
+use data::pg::timed;
+use common::organization::Organization;
 
 
 #[derive(Debug, Clone)]
@@ -92,7 +96,7 @@ impl OrganizationsData {
         let result = self.pool.get().await;
         if let Err(e) = result {
             error!("unable to retrieve database client: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::Unavailable);
         }
         let client = result.unwrap();
 
@@ -101,11 +105,11 @@ impl OrganizationsData {
         ).await;
         if let Err(e) = result {
             error!("unable to prepare database statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         }
         let stmt = result.unwrap();
 
-        match client.execute(
+        match timed("tenants.organization_add", client.execute(
             &stmt,
             &[
                 &tenant_id,
@@ -113,10 +117,10 @@ impl OrganizationsData {
                 &name,
                 &description
             ]
-        ).await {
+        )).await {
             Err(e) => {
                 error!("unable to execute statement: {:?}", e);
-                return Err(DataError::DatabaseError);
+                return Err(DataError::from(&e));
             }
             Ok(_) => {
                 return Ok(());
@@ -135,7 +139,7 @@ impl OrganizationsData {
         let result = self.pool.get().await;
         if let Err(e) = result {
             error!("unable to retrieve database client: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::Unavailable);
         }
         let client = result.unwrap();
 
@@ -144,24 +148,161 @@ impl OrganizationsData {
         ).await;
         if let Err(e) = result {
             error!("unable to prepare database statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         }
         let stmt = result.unwrap();
 
-        match client.execute(
+        match timed("tenants.organization_set_active", client.execute(
             &stmt,
             &[
                 &organization_id,
                 &active
             ]
-        ).await {
+        )).await {
             Err(e) => {
                 error!("unable to execute statement: {:?}", e);
-                return Err(DataError::DatabaseError);
+                return Err(DataError::from(&e));
             }
             Ok(_) => {
                 return Ok(());
             }
         }
     }
-}
\ No newline at end of file
+
+    /// fetch organizations of a tenant
+    pub async fn organizations_fetch(
+        &self,
+        tenant_id: &uuid::Uuid
+    ) -> Result<Vec<Organization>, DataError> {
+        info!("OrganizationsData::organizations_fetch()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "select * from tenants.organizations_fetch($1)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("tenants.organizations_fetch", client.query(
+            &stmt,
+            &[
+                &tenant_id
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(rows) => {
+                return Ok(rows.iter().map(Self::organization).collect());
+            }
+        }
+    }
+
+    /// retrieve an organization of a tenant
+    pub async fn organization_get(
+        &self,
+        tenant_id: &uuid::Uuid,
+        organization_id: &uuid::Uuid
+    ) -> Result<Option<Organization>, DataError> {
+        info!("OrganizationsData::organization_get()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "select * from tenants.organization_get($1,$2)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("tenants.organization_get", client.query_opt(
+            &stmt,
+            &[
+                &tenant_id,
+                &organization_id
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(row) => {
+                return Ok(row.as_ref().map(Self::organization));
+            }
+        }
+    }
+
+    /// remove an organization of a tenant
+    pub async fn organization_remove(
+        &self,
+        tenant_id: &uuid::Uuid,
+        organization_id: &uuid::Uuid
+    ) -> Result<(), DataError> {
+        info!("OrganizationsData::organization_remove()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "call tenants.organization_remove($1,$2)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("tenants.organization_remove", client.execute(
+            &stmt,
+            &[
+                &tenant_id,
+                &organization_id
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(_) => {
+                return Ok(());
+            }
+        }
+    }
+
+    fn organization(row: &Row) -> Organization {
+        let id: uuid::Uuid = row.get("id");
+        let tenant_id: uuid::Uuid = row.get("tenant_id");
+        let active: bool = row.get("active");
+        let name: String = row.get("name");
+        let description: String = row.get("description");
+
+        return Organization::new(
+            &id,
+            &tenant_id,
+            &active,
+            &name,
+            &description
+        );
+    }
+}
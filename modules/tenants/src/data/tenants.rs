@@ -8,6 +8,7 @@ use std::str::FromStr;
 
 use deadpool::managed::Object;
 use deadpool_postgres::{ 
+    Client,
     Manager, 
     ManagerConfig, 
     Pool, 
@@ -27,12 +28,18 @@ use configuration::{
 };
 
 use data::pg::DataError;
+use data::pg::timed;
+use data::pg::pools::PgPools;
+use data::pg::unit_of_work::UnitOfWork;
 use common::tenant::Tenant;
 
 
 #[derive(Debug, Clone)]
 pub struct TenantsData {
-    pool: Pool
+    pool: Pool,
+
+    /// read-only queries may be served by a replica
+    pools: PgPools
 }
 
 
@@ -78,7 +85,8 @@ impl TenantsData {
 
     pub fn new(data: data::Data) -> Self {
         return Self {
-            pool: data.get_pg_pool().unwrap()
+            pool: data.get_pg_pool().unwrap(),
+            pools: data.get_pg_pools().unwrap()
         };
     }
 
@@ -91,7 +99,7 @@ impl TenantsData {
         let result = self.pool.get().await;
         if let Err(e) = result {
             error!("unable to retrieve database client: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::Unavailable);
         }
         let client = result.unwrap();
 
@@ -100,21 +108,24 @@ impl TenantsData {
         ).await;
         if let Err(e) = result {
             error!("unable to prepare database statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         }
         let stmt = result.unwrap();
 
-        match client.query_one(
+        match timed("tenants.tenant_by_id", client.query_opt(
             &stmt,
             &[
                 &tenant_id
             ]
-        ).await {
+        )).await {
             Err(e) => {
                 error!("unable to execute statement: {:?}", e);
-                return Err(DataError::DatabaseError);
+                return Err(DataError::from(&e));
             }
-            Ok(row) => {
+            Ok(None) => {
+                return Err(DataError::NotFound);
+            }
+            Ok(Some(row)) => {
                 debug!("row: {:?}", row);
 
                 let tenant_id: uuid::Uuid = row.get("id");
@@ -141,7 +152,7 @@ impl TenantsData {
         let result = self.pool.get().await;
         if let Err(e) = result {
             error!("unable to retrieve database client: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::Unavailable);
         }
         let client = result.unwrap();
 
@@ -150,19 +161,19 @@ impl TenantsData {
         ).await;
         if let Err(e) = result {
             error!("unable to prepare database statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         }
         let stmt = result.unwrap();
 
-        match client.query_one(
+        match timed("tenants.tenant_by_name", client.query_one(
             &stmt,
             &[
                 &name
             ]
-        ).await {
+        )).await {
             Err(e) => {
                 error!("unable to execute statement: {:?}", e);
-                return Err(DataError::DatabaseError);
+                return Err(DataError::from(&e));
             }
             Ok(row) => {
                 debug!("row: {:?}", row);
@@ -191,7 +202,7 @@ impl TenantsData {
         let result = self.pool.get().await;
         if let Err(e) = result {
             error!("unable to retrieve database client: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::Unavailable);
         }
         let client = result.unwrap();
 
@@ -200,19 +211,19 @@ impl TenantsData {
         ).await;
         if let Err(e) = result {
             error!("unable to prepare database statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         }
         let stmt = result.unwrap();
 
-        match client.query_one(
+        match timed("tenants.tenant_by_slug", client.query_one(
             &stmt,
             &[
                 &data::pg::slug::Slug::new(&slug)
             ]
-        ).await {
+        )).await {
             Err(e) => {
                 error!("unable to execute statement: {:?}", e);
-                return Err(DataError::DatabaseError);
+                return Err(DataError::from(&e));
             }
             Ok(row) => {
                 debug!("row: {:?}", row);
@@ -238,10 +249,10 @@ impl TenantsData {
     ) -> Result<Vec<Tenant>, DataError> {
         info!("Data::tenants_fetch");
 
-        let result = self.pool.get().await;
+        let result = self.pools.read().await;
         if let Err(e) = result {
             error!("unable to retrieve database client: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::Unavailable);
         }
         let client = result.unwrap();
 
@@ -250,17 +261,17 @@ impl TenantsData {
         ).await;
         if let Err(e) = result {
             error!("unable to prepare database statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         }
         let stmt = result.unwrap();
 
-        match client.query(
+        match timed("tenants.tenants_fetch", client.query(
             &stmt,
             &[]
-        ).await {
+        )).await {
             Err(e) => {
                 error!("unable to execute statement: {:?}", e);
-                return Err(DataError::DatabaseError);
+                return Err(DataError::from(&e));
             }
             Ok(rows) => {
                 debug!("rows: {:?}", rows);
@@ -297,20 +308,56 @@ impl TenantsData {
         let result = self.pool.get().await;
         if let Err(e) = result {
             error!("unable to retrieve database tenant: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::Unavailable);
         }
         let client = result.unwrap();
 
+        return Self::tenant_add_on(
+            &client,
+            &id,
+            &name,
+            &slug,
+            &description
+        ).await;
+    }
+
+    /// add a tenant as part of a unit of work
+    pub async fn tenant_add_in(
+        &self,
+        uow: &UnitOfWork,
+        id: &uuid::Uuid,
+        name: &str,
+        slug: &str,
+        description: &str
+    ) -> Result<(), DataError> {
+        info!("Data::tenant_add_in()");
+
+        return Self::tenant_add_on(
+            uow.client(),
+            &id,
+            &name,
+            &slug,
+            &description
+        ).await;
+    }
+
+    async fn tenant_add_on(
+        client: &Client,
+        id: &uuid::Uuid,
+        name: &str,
+        slug: &str,
+        description: &str
+    ) -> Result<(), DataError> {
         let result = client.prepare_cached(
             "call tenants.tenant_add($1, $2, $3, $4)"
         ).await;
         if let Err(e) = result {
             error!("unable to prepare database statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         }
         let stmt = result.unwrap();
 
-        match client.execute(
+        match timed("tenants.tenant_add", client.execute(
             &stmt,
             &[
                 &id,
@@ -318,10 +365,10 @@ impl TenantsData {
                 &data::pg::slug::Slug::new(&slug),
                 &description
             ]
-        ).await {
+        )).await {
             Err(e) => {
                 error!("unable to execute statement: {:?}", e);
-                return Err(DataError::DatabaseError);
+                return Err(DataError::from(&e));
             }
             Ok(_) => {
                 return Ok(());
@@ -342,7 +389,7 @@ impl TenantsData {
         let result = self.pool.get().await;
         if let Err(e) = result {
             error!("unable to retrieve database tenant: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::Unavailable);
         }
         let client = result.unwrap();
 
@@ -351,11 +398,11 @@ impl TenantsData {
         ).await;
         if let Err(e) = result {
             error!("unable to prepare database statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         }
         let stmt = result.unwrap();
 
-        match client.execute(
+        match timed("tenants.tenant_update", client.execute(
             &stmt,
             &[
                 &id,
@@ -363,10 +410,10 @@ impl TenantsData {
                 &data::pg::slug::Slug::new(&slug),
                 &description
             ]
-        ).await {
+        )).await {
             Err(e) => {
                 error!("unable to execute statement: {:?}", e);
-                return Err(DataError::DatabaseError);
+                return Err(DataError::from(&e));
             }
             Ok(_) => {
                 return Ok(());
@@ -375,6 +422,51 @@ impl TenantsData {
     }
 
 
+    /// update only the given tenant fields, absent fields keep their value
+    pub async fn tenant_patch(
+        &self,
+        id: &uuid::Uuid,
+        name: Option<&str>,
+        slug: Option<&str>,
+        description: Option<&str>
+    ) -> Result<(), DataError> {
+        info!("Data::tenant_patch()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database tenant: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "call tenants.tenant_patch($1, $2, $3, $4)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("tenants.tenant_patch", client.execute(
+            &stmt,
+            &[
+                &id,
+                &name,
+                &slug.map(data::pg::slug::Slug::new),
+                &description
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(_) => {
+                return Ok(());
+            }
+        }
+    }
+
     pub async fn tenant_set_active(
         &self,
         tenant_id: &uuid::Uuid,
@@ -385,7 +477,7 @@ impl TenantsData {
         let result = self.pool.get().await;
         if let Err(e) = result {
             error!("unable to retrieve database tenant: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::Unavailable);
         }
         let client = result.unwrap();
 
@@ -394,20 +486,60 @@ impl TenantsData {
         ).await;
         if let Err(e) = result {
             error!("unable to prepare database statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         }
         let stmt = result.unwrap();
 
-        match client.execute(
+        match timed("tenants.set_active", client.execute(
             &stmt,
             &[
                 &tenant_id,
                 &active
             ]
-        ).await {
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(_) => {
+                return Ok(());
+            }
+        }
+    }
+
+    pub async fn tenant_set_mfa_required(
+        &self,
+        tenant_id: &uuid::Uuid,
+        required: &bool
+    ) -> Result<(), DataError> {
+        info!("Data::tenant_set_mfa_required()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database tenant: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "call tenants.set_mfa_required($1, $2)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("tenants.set_mfa_required", client.execute(
+            &stmt,
+            &[
+                &tenant_id,
+                &required
+            ]
+        )).await {
             Err(e) => {
                 error!("unable to execute statement: {:?}", e);
-                return Err(DataError::DatabaseError);
+                return Err(DataError::from(&e));
             }
             Ok(_) => {
                 return Ok(());
@@ -424,28 +556,28 @@ impl TenantsData {
         let result = self.pool.get().await;
         if let Err(e) = result {
             error!("unable to retrieve database tenant: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::Unavailable);
         }
         let client = result.unwrap();
 
         let result = client.prepare_cached(
-            "call iam.tenant_set_active($1)"
+            "select * from tenants.tenant_users_fetch($1)"
         ).await;
         if let Err(e) = result {
             error!("unable to prepare database statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         }
         let stmt = result.unwrap();
 
-        match client.query(
+        match timed("tenants.tenant_users_fetch", client.query(
             &stmt,
             &[
                 &tenant_id
             ]
-        ).await {
+        )).await {
             Err(e) => {
                 error!("unable to execute statement: {:?}", e);
-                return Err(DataError::DatabaseError);
+                return Err(DataError::from(&e));
             }
             Ok(results) => {
                 debug!("Data::tenant_users_fetch(): {:?}", results);
@@ -478,7 +610,7 @@ impl TenantsData {
         let result = self.pool.get().await;
         if let Err(e) = result {
             error!("unable to retrieve database tenant: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::Unavailable);
         }
         let client = result.unwrap();
 
@@ -487,18 +619,18 @@ impl TenantsData {
         ).await;
         if let Err(e) = result {
             error!("unable to prepare database statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         }
         let stmt = result.unwrap();
 
-        match client.query_one(
+        match timed("tenants.tenant_default_fetch", client.query_one(
             &stmt,
             &[
             ]
-        ).await {
+        )).await {
             Err(e) => {
                 error!("unable to execute statement: {:?}", e);
-                return Err(DataError::DatabaseError);
+                return Err(DataError::from(&e));
             }
             Ok(row) => {
                 debug!("Data::tenant_default_fetch(): {:?}", row);
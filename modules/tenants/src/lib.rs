@@ -2,6 +2,7 @@ pub mod data;
 pub mod tenants;
 pub mod organizations;
 pub mod members;
+pub mod api_keys;
 
 #[cfg(test)]
 mod tests {
@@ -12,8 +12,10 @@ use serde::{
 use configuration::ApplicationConfiguration;
 
 use common::{
-    Error
+    Error,
+    organization::Organization
 };
+use data::pg::DataError;
 
 use crate::data::organizations::OrganizationsData;
 
@@ -62,9 +64,12 @@ impl Organizations {
             &name,
             &description
         ).await {
+            Err(DataError::Conflict) => {
+                return Err(Error::Conflict(String::from("organization already exists")));
+            }
             Err(e) => {
                 error!("unable to add organization record: {:?}", e);
-                return Err(Error::ToBeImplemented(String::from("Organizations::add()")));
+                return Err(e.into());
             }
             Ok(()) => {
                 return Ok(());
@@ -85,11 +90,70 @@ impl Organizations {
         ).await {
             Err(e) => {
                 error!("unable to set organization active status: {:?}", e);
-                return Err(Error::ToBeImplemented(String::from("Organizations::set_active()")));
+                return Err(e.into());
             }
             Ok(()) => {
                 return Ok(());
             }
         }
     }
-}
\ No newline at end of file
+
+    pub async fn fetch(
+        &self,
+        tenant_id: &uuid::Uuid
+    ) -> Result<Vec<Organization>, Error> {
+        info!("Organizations::fetch()");
+
+        match self.data.organizations_fetch(&tenant_id).await {
+            Err(e) => {
+                error!("unable to fetch organization records: {:?}", e);
+                return Err(e.into());
+            }
+            Ok(organizations) => {
+                return Ok(organizations);
+            }
+        }
+    }
+
+    pub async fn get(
+        &self,
+        tenant_id: &uuid::Uuid,
+        organization_id: &uuid::Uuid
+    ) -> Result<Organization, Error> {
+        info!("Organizations::get()");
+
+        match self.data.organization_get(&tenant_id, &organization_id).await {
+            Err(e) => {
+                error!("unable to retrieve organization record: {:?}", e);
+                return Err(e.into());
+            }
+            Ok(None) => {
+                return Err(Error::NotFound(String::from("organization not found")));
+            }
+            Ok(Some(organization)) => {
+                return Ok(organization);
+            }
+        }
+    }
+
+    pub async fn remove(
+        &self,
+        tenant_id: &uuid::Uuid,
+        organization_id: &uuid::Uuid
+    ) -> Result<(), Error> {
+        info!("Organizations::remove()");
+
+        // confirm the organization belongs to the tenant
+        self.get(&tenant_id, &organization_id).await?;
+
+        match self.data.organization_remove(&tenant_id, &organization_id).await {
+            Err(e) => {
+                error!("unable to remove organization record: {:?}", e);
+                return Err(e.into());
+            }
+            Ok(()) => {
+                return Ok(());
+            }
+        }
+    }
+}
@@ -11,19 +11,16 @@ use serde::{
 
 use configuration::ApplicationConfiguration;
 use common::{
+    Error,
     tenant::Tenant,
     user::User
 };
 
-use crate::data::tenants::TenantsData;
-
+use data::pg::DataError;
+use data::pg::unit_of_work::UnitOfWork;
 
+use crate::data::tenants::TenantsData;
 
-#[derive(Debug)]
-pub enum TenantsError {
-    ToBeImplemented(String),
-    ConfigurationError
-}
 
 #[derive(Debug, Clone)]
 pub struct Tenants {
@@ -54,13 +51,13 @@ impl Tenants {
     }
 
     /// retrieve tenants
-    pub async fn tenants(&self) -> Result<Vec<Tenant>, TenantsError> {
+    pub async fn tenants(&self) -> Result<Vec<Tenant>, Error> {
         info!("Tenants::tenants()");
 
         match self.data.tenants_fetch().await {
             Err(e) => {
                 error!("unable to retrieve tenants: {:?}", e);
-                return Err(TenantsError::ToBeImplemented(String::from("Tenants::tenants()")));
+                return Err(e.into());
             }
             Ok(tenants) => {
                 return Ok(tenants);
@@ -77,13 +74,16 @@ impl Tenants {
     pub async fn tenant_by_id(
         &self,
         tenant_id: &uuid::Uuid
-    ) -> Result<Tenant, TenantsError> {
+    ) -> Result<Tenant, Error> {
         info!("Tenants::tenant_by_name()");
 
         match self.data.tenant_by_id(&tenant_id).await {
+            Err(DataError::NotFound) => {
+                return Err(Error::NotFound(String::from("tenant not found")));
+            }
             Err(e) => {
                 error!("unable to retrieve tenant: {:?}", e);
-                return Err(TenantsError::ToBeImplemented(String::from("Tenants::tenant_by_id()")));
+                return Err(e.into());
             }
             Ok(tenant) => {
                 return Ok(tenant);
@@ -95,13 +95,13 @@ impl Tenants {
     pub async fn tenant_by_name(
         &self,
         name: &str
-    ) -> Result<Tenant, TenantsError> {
+    ) -> Result<Tenant, Error> {
         info!("Tenants::tenant_by_name()");
 
         match self.data.tenant_by_name(name).await {
             Err(e) => {
                 error!("unable to retrieve tenant: {:?}", e);
-                return Err(TenantsError::ToBeImplemented(String::from("Tenants::tenant_by_name()")));
+                return Err(e.into());
             }
             Ok(tenant) => {
                 return Ok(tenant);
@@ -113,13 +113,13 @@ impl Tenants {
     pub async fn tenant_by_slug(
         &self,
         slug: &str
-    ) -> Result<Tenant, TenantsError> {
+    ) -> Result<Tenant, Error> {
         info!("Tenants::tenant_by_slug()");
 
         match self.data.tenant_by_slug(slug).await {
             Err(e) => {
                 error!("unable to retrieve tenant: {:?}", e);
-                return Err(TenantsError::ToBeImplemented(String::from("Tenants::tenant_by_slug()")));
+                return Err(e.into());
             }
             Ok(tenant) => {
                 return Ok(tenant);
@@ -133,18 +133,48 @@ impl Tenants {
         name: &str,
         slug: &str,
         description: &str
-    ) -> Result<(), TenantsError> {
+    ) -> Result<(), Error> {
         info!("Tenants::tenant_add()");
 
-        match self.data.tenant_add(
+        let result = self.data.tenant_add(
             &id,
             &name,
             &slug,
             &description
-        ).await {
+        ).await;
+        return Self::tenant_add_result(result);
+    }
+
+    /// add a tenant as part of a unit of work
+    pub async fn tenant_add_in(
+        &self,
+        uow: &UnitOfWork,
+        id: uuid::Uuid,
+        name: &str,
+        slug: &str,
+        description: &str
+    ) -> Result<(), Error> {
+        info!("Tenants::tenant_add_in()");
+
+        let result = self.data.tenant_add_in(
+            &uow,
+            &id,
+            &name,
+            &slug,
+            &description
+        ).await;
+        return Self::tenant_add_result(result);
+    }
+
+    fn tenant_add_result(result: Result<(), DataError>) -> Result<(), Error> {
+        match result {
+            Err(DataError::Conflict) => {
+                info!("tenant slug already in use");
+                return Err(Error::Conflict(String::from("tenant slug is already in use")));
+            }
             Err(e) => {
                 error!("unable to add tenant: {:?}", e);
-                return Err(TenantsError::ToBeImplemented(String::from("Tenants::tenant_add()")));
+                return Err(e.into());
             }
             Ok(_) => {
                 return Ok(());
@@ -158,7 +188,7 @@ impl Tenants {
         name: &str,
         slug: &str,
         description: &str
-    ) -> Result<(), TenantsError> {
+    ) -> Result<(), Error> {
         info!("Tenants::tenant_update()");
 
         match self.data.tenant_update(
@@ -167,9 +197,43 @@ impl Tenants {
             &slug,
             &description
         ).await {
+            Err(DataError::Conflict) => {
+                info!("tenant slug already in use");
+                return Err(Error::Conflict(String::from("tenant slug is already in use")));
+            }
             Err(e) => {
                 error!("unable to update tenant: {:?}", e);
-                return Err(TenantsError::ToBeImplemented(String::from("Tenants::tenant_update()")));
+                return Err(e.into());
+            }
+            Ok(_) => {
+                return Ok(());
+            }
+        }
+    }
+
+    /// update only the given tenant fields
+    pub async fn tenant_patch(
+        &self,
+        id: &uuid::Uuid,
+        name: Option<&str>,
+        slug: Option<&str>,
+        description: Option<&str>
+    ) -> Result<(), Error> {
+        info!("Tenants::tenant_patch()");
+
+        match self.data.tenant_patch(
+            &id,
+            name,
+            slug,
+            description
+        ).await {
+            Err(DataError::Conflict) => {
+                info!("tenant slug already in use");
+                return Err(Error::Conflict(String::from("tenant slug is already in use")));
+            }
+            Err(e) => {
+                error!("unable to update tenant: {:?}", e);
+                return Err(e.into());
             }
             Ok(_) => {
                 return Ok(());
@@ -181,7 +245,7 @@ impl Tenants {
         &self,
         tenant_id: &uuid::Uuid,
         active: &bool
-    ) -> Result<(), TenantsError> {
+    ) -> Result<(), Error> {
         info!("Tenants::tenant_set_active()");
 
         match self.data.tenant_set_active(
@@ -190,7 +254,29 @@ impl Tenants {
         ).await {
             Err(e) => {
                 error!("unable to set tenant active status: {:?}", e);
-                return Err(TenantsError::ToBeImplemented(String::from("Tenants::tenant_set_active()")));
+                return Err(e.into());
+            }
+            Ok(_) => {
+                return Ok(());
+            }
+        }
+    }
+
+    /// require two-factor authentication for all members of a tenant
+    pub async fn tenant_set_mfa_required(
+        &self,
+        tenant_id: &uuid::Uuid,
+        required: &bool
+    ) -> Result<(), Error> {
+        info!("Tenants::tenant_set_mfa_required()");
+
+        match self.data.tenant_set_mfa_required(
+            &tenant_id,
+            &required
+        ).await {
+            Err(e) => {
+                error!("unable to set tenant two-factor authentication requirement: {:?}", e);
+                return Err(e.into());
             }
             Ok(_) => {
                 return Ok(());
@@ -201,7 +287,7 @@ impl Tenants {
     pub async fn tenant_users_fetch(
         &self,
         tenant_id: &uuid::Uuid
-    ) -> Result<Vec<common::user::User>, TenantsError> {
+    ) -> Result<Vec<common::user::User>, Error> {
         info!("Tenants::tenant_users_fetch()");
 
         match self.data.tenant_users_fetch(
@@ -209,7 +295,7 @@ impl Tenants {
         ).await {
             Err(e) => {
                 error!("unable to retrieve tenant users: {:?}", e);
-                return Err(TenantsError::ToBeImplemented(String::from("Tenants::tenant_users_fetch()")));
+                return Err(e.into());
             }
             Ok(users) => {
                 return Ok(users);
@@ -220,7 +306,7 @@ impl Tenants {
     pub async fn tenant_default_fetch(
         &self,
         tenant_id: &uuid::Uuid
-    ) -> Result<common::tenant::Tenant, TenantsError> {
+    ) -> Result<common::tenant::Tenant, Error> {
         info!("Tenants::tenant_default_fetch()");
 
         match self.data.tenant_default_fetch(
@@ -228,7 +314,7 @@ impl Tenants {
         ).await {
             Err(e) => {
                 error!("unable to retrieve default tenant: {:?}", e);
-                return Err(TenantsError::ToBeImplemented(String::from("Tenants::tenant_default_fetch()")));
+                return Err(e.into());
             }
             Ok(tenant) => {
                 return Ok(tenant);
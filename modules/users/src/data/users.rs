@@ -28,14 +28,11 @@ use configuration::{
 };
 
 use common::user::User;
+use common::session::Session;
 
-
-#[derive(Debug)]
-pub enum DataError {
-    ToBeImplemented(String),
-    ConfigurationError,
-    DatabaseError
-}
+use data::pg::DataError;
+use data::pg::timed;
+use data::pg::sessions;
 
 
 #[derive(Debug, Clone)]
@@ -100,7 +97,7 @@ impl UsersData {
         let result = self.pool.get().await;
         if let Err(e) = result {
             error!("unable to retrieve database client: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::Unavailable);
         }
         let client = result.unwrap();
 
@@ -109,19 +106,19 @@ impl UsersData {
         ).await;
         if let Err(e) = result {
             error!("unable to prepare database statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         }
         let stmt = result.unwrap();
 
-        match client.query_one(
+        match timed("iam.user_by_id", client.query_one(
             &stmt,
             &[
                 &id
             ]
-        ).await {
+        )).await {
             Err(e) => {
                 error!("unable to execute statement: {:?}", e);
-                return Err(DataError::DatabaseError);
+                return Err(DataError::from(&e));
             }
             Ok(row) => {
                 debug!("row: {:?}", row);
@@ -156,7 +153,7 @@ impl UsersData {
         let result = self.pool.get().await;
         if let Err(e) = result {
             error!("unable to retrieve database client: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::Unavailable);
         }
         let client = result.unwrap();
 
@@ -165,19 +162,19 @@ impl UsersData {
         ).await;
         if let Err(e) = result {
             error!("unable to prepare database statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         }
         let stmt = result.unwrap();
 
-        match client.query_one(
+        match timed("iam.user_by_email", client.query_one(
             &stmt,
             &[
                 &data::pg::email::Email::new(&email)
             ]
-        ).await {
+        )).await {
             Err(e) => {
                 error!("unable to execute statement: {:?}", e);
-                return Err(DataError::DatabaseError);
+                return Err(DataError::from(&e));
             }
             Ok(row) => {
                 debug!("row: {:?}", row);
@@ -213,7 +210,7 @@ impl UsersData {
         let result = self.pool.get().await;
         if let Err(e) = result {
             error!("unable to retrieve database client: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::Unavailable);
         }
         let client = result.unwrap();
 
@@ -222,19 +219,19 @@ impl UsersData {
         ).await;
         if let Err(e) = result {
             error!("unable to prepare database statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         }
         let stmt = result.unwrap();
 
-        if let Err(e) = client.execute(
+        if let Err(e) = timed("iam.user_active", client.execute(
             &stmt, 
             &[
                 &user_id,
                 &active
             ]
-        ).await {
+        )).await {
             error!("unable to execute statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         } else {
             return Ok(());
         }
@@ -251,7 +248,7 @@ impl UsersData {
         let result = self.pool.get().await;
         if let Err(e) = result {
             error!("unable to retrieve database client: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::Unavailable);
         }
         let client = result.unwrap();
 
@@ -260,19 +257,19 @@ impl UsersData {
         ).await;
         if let Err(e) = result {
             error!("unable to prepare database statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         }
         let stmt = result.unwrap();
 
-        if let Err(e) = client.execute(
+        if let Err(e) = timed("iam.user_set_pw", client.execute(
             &stmt, 
             &[
                 &user_id,
                 &password
             ]
-        ).await {
+        )).await {
             error!("unable to execute statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         } else {
             return Ok(());
         }
@@ -289,7 +286,7 @@ impl UsersData {
         let result = self.pool.get().await;
         if let Err(e) = result {
             error!("unable to retrieve database client: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::Unavailable);
         }
         let client = result.unwrap();
 
@@ -298,19 +295,19 @@ impl UsersData {
         ).await;
         if let Err(e) = result {
             error!("unable to prepare database statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         }
         let stmt = result.unwrap();
 
-        if let Err(e) = client.execute(
+        if let Err(e) = timed("iam.user_tenant_add", client.execute(
             &stmt, 
             &[
                 &user_id,
                 &tenant_id
             ]
-        ).await {
+        )).await {
             error!("unable to execute statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         } else {
             return Ok(());
         }
@@ -328,7 +325,7 @@ impl UsersData {
         let result = self.pool.get().await;
         if let Err(e) = result {
             error!("unable to retrieve database client: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::Unavailable);
         }
         let client = result.unwrap();
 
@@ -337,20 +334,20 @@ impl UsersData {
         ).await;
         if let Err(e) = result {
             error!("unable to prepare database statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         }
         let stmt = result.unwrap();
 
-        if let Err(e) = client.execute(
+        if let Err(e) = timed("iam.user_tenant_set_active", client.execute(
             &stmt, 
             &[
                 &user_id,
                 &tenant_id,
                 &active
             ]
-        ).await {
+        )).await {
             error!("unable to execute statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         } else {
             return Ok(());
         }
@@ -402,7 +399,7 @@ impl UsersData {
         let result = self.pool.get().await;
         if let Err(e) = result {
             error!("unable to retrieve database client: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::Unavailable);
         }
         let client = result.unwrap();
 
@@ -411,19 +408,19 @@ impl UsersData {
         ).await;
         if let Err(e) = result {
             error!("unable to prepare database statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         }
         let stmt = result.unwrap();
 
-        if let Err(e) = client.execute(
+        if let Err(e) = timed("iam.user_tenant_set_default", client.execute(
             &stmt, 
             &[
                 &user_id,
                 &tenant_id
             ]
-        ).await {
+        )).await {
             error!("unable to execute statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         } else {
             return Ok(());
         }
@@ -439,7 +436,7 @@ impl UsersData {
         let result = self.pool.get().await;
         if let Err(e) = result {
             error!("unable to retrieve database client: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::Unavailable);
         }
         let client = result.unwrap();
 
@@ -448,19 +445,19 @@ impl UsersData {
         ).await;
         if let Err(e) = result {
             error!("unable to prepare database statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         }
         let stmt = result.unwrap();
 
-        match client.query(
+        match timed("iam.user_tenants_fetch", client.query(
             &stmt,
             &[
                 &user_id
             ]
-        ).await {
+        )).await {
             Err(e) => {
                 error!("unable to execute statement: {:?}", e);
-                return Err(DataError::DatabaseError);
+                return Err(DataError::from(&e));
             }
             Ok(rows) => {
                 // debug!("rows: {:?}", rows);
@@ -492,7 +489,7 @@ impl UsersData {
         let result = self.pool.get().await;
         if let Err(e) = result {
             error!("unable to retrieve database client: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::Unavailable);
         }
         let client = result.unwrap();
 
@@ -501,19 +498,19 @@ impl UsersData {
         ).await;
         if let Err(e) = result {
             error!("unable to prepare database statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         }
         let stmt = result.unwrap();
 
-        match client.query_one(
+        match timed("iam.user_tenant_fetch_default", client.query_one(
             &stmt,
             &[
                 &user_id
             ]
-        ).await {
+        )).await {
             Err(e) => {
                 error!("unable to execute statement: {:?}", e);
-                return Err(DataError::DatabaseError);
+                return Err(DataError::from(&e));
             }
             Ok(row) => {
                 debug!("rows: {:?}", row);
@@ -534,14 +531,191 @@ impl UsersData {
         }
     }
 
-    /// retrieve permissions assigned to a user
+    /// retrieve names of the permissions granted to a user in a tenant
+    /// through the user's roles
     pub async fn user_permissions(
         &self,
         user_id: &uuid::Uuid,
         tenant_id: &uuid::Uuid
-    ) -> Result<(), DataError> {
+    ) -> Result<Vec<String>, DataError> {
         info!("Data::user_permissions");
 
-        return Ok(());
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "select * from iam.user_permissions_fetch($1, $2)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("iam.user_permissions_fetch", client.query(
+            &stmt,
+            &[
+                &user_id,
+                &tenant_id
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(rows) => {
+                let permissions = rows.iter().map(|r| {
+                    let name: String = r.get("name");
+                    return name;
+                }).collect();
+                return Ok(permissions);
+            }
+        }
+    }
+
+    /// retrieve active sessions of a user
+    pub async fn user_sessions(
+        &self,
+        user_id: &uuid::Uuid
+    ) -> Result<Vec<Session>, DataError> {
+        info!("Data::user_sessions()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "select * from iam.user_sessions_fetch($1)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("iam.user_sessions_fetch", client.query(
+            &stmt,
+            &[
+                &user_id
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(rows) => {
+                let sessions = rows.iter().map(|r| {
+                    let session_id: uuid::Uuid = r.get("family_id");
+                    let tenant_id: uuid::Uuid = r.get("tenant_id");
+                    let created: DateTime<Utc> = r.get("created");
+                    let last_used: DateTime<Utc> = r.get("last_used");
+                    let expires: DateTime<Utc> = r.get("expires");
+
+                    return Session::new(
+                        &session_id,
+                        &tenant_id,
+                        &created,
+                        &last_used,
+                        &expires
+                    );
+                }).collect();
+                return Ok(sessions);
+            }
+        }
+    }
+
+    /// revoke a session of a user
+    pub async fn user_session_revoke(
+        &self,
+        user_id: &uuid::Uuid,
+        session_id: &uuid::Uuid
+    ) -> Result<(), DataError> {
+        info!("Data::user_session_revoke()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "call iam.user_session_revoke($1, $2)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        if let Err(e) = timed("iam.user_session_revoke", client.execute(
+            &stmt,
+            &[
+                &user_id,
+                &session_id
+            ]
+        )).await {
+            error!("unable to execute statement: {:?}", e);
+            return Err(DataError::from(&e));
+        } else {
+            return Ok(());
+        }
+    }
+
+    /// revoke every session of a user except for the one specified
+    pub async fn user_sessions_revoke(
+        &self,
+        user_id: &uuid::Uuid,
+        except_session_id: &uuid::Uuid
+    ) -> Result<(), DataError> {
+        info!("Data::user_sessions_revoke()");
+        return sessions::user_sessions_revoke(&self.pool, &user_id, &except_session_id).await;
+    }
+
+    /// check if a session has not been revoked or expired
+    pub async fn session_active(
+        &self,
+        session_id: &uuid::Uuid
+    ) -> Result<bool, DataError> {
+        info!("Data::session_active()");
+
+        let result = self.pool.get().await;
+        if let Err(e) = result {
+            error!("unable to retrieve database client: {:?}", e);
+            return Err(DataError::Unavailable);
+        }
+        let client = result.unwrap();
+
+        let result = client.prepare_cached(
+            "select * from iam.session_active($1)"
+        ).await;
+        if let Err(e) = result {
+            error!("unable to prepare database statement: {:?}", e);
+            return Err(DataError::from(&e));
+        }
+        let stmt = result.unwrap();
+
+        match timed("iam.session_active", client.query_one(
+            &stmt,
+            &[
+                &session_id
+            ]
+        )).await {
+            Err(e) => {
+                error!("unable to execute statement: {:?}", e);
+                return Err(DataError::from(&e));
+            }
+            Ok(row) => {
+                let active: bool = row.get(0);
+                return Ok(active);
+            }
+        }
     }
-}
\ No newline at end of file
+}
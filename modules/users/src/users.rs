@@ -12,17 +12,46 @@ use serde::{
 
 use configuration::ApplicationConfiguration;
 use mailer::Mailer;
-use common::{user::User, tenant::Tenant};
+use common::{user::User, tenant::Tenant, session::Session};
 
 use data::Data;
+use data::pg::DataError;
+use common::password::{
+    Password,
+    PasswordRule
+};
+
+use common::cache::{
+    TtlCache,
+    PermissionCache
+};
+
+
+/// number of seconds user lookups made while authenticating requests are cached
+const CACHE_TTL_SECONDS: u64 = 30;
 
 
 #[derive(Debug)]
 pub enum UsersError {
-    ToBeImplemented(String),
+    DataError(DataError),
     ConfigurationError,
     MailerError,
-    ValidationError
+    ValidationError,
+    TokenGenerationError,
+    PasswordPolicy(Vec<PasswordRule>)
+}
+
+impl From<UsersError> for common::Error {
+    fn from(e: UsersError) -> Self {
+        match e {
+            UsersError::DataError(e) => common::Error::from(e),
+            UsersError::ConfigurationError => common::Error::ConfigurationError(String::from("users are not configured")),
+            UsersError::MailerError => common::Error::Unavailable(String::from("unable to send email")),
+            UsersError::ValidationError => common::Error::ValidationError(String::from("invalid request")),
+            UsersError::TokenGenerationError => common::Error::Internal(String::from("unable to generate token")),
+            UsersError::PasswordPolicy(_) => common::Error::ValidationError(String::from("password does not meet the requirements"))
+        }
+    }
 }
 
 
@@ -31,7 +60,11 @@ pub struct Users {
     cfg: ApplicationConfiguration,
     data: crate::data::users::UsersData,
     mailer: Mailer,
-    tokenizer: tokenizer::Tokenizer
+    tokenizer: tokenizer::Tokenizer,
+    password: Password,
+    users_by_email: TtlCache<String, User>,
+    tenants_by_user: TtlCache<uuid::Uuid, Vec<Tenant>>,
+    permissions_by_user: PermissionCache
 }
 
 impl Users {
@@ -40,7 +73,8 @@ impl Users {
         cfg: ApplicationConfiguration,
         mailer: Mailer,
         tokenizer: tokenizer::Tokenizer,
-        data: Data
+        data: Data,
+        permissions: PermissionCache
     ) -> Self {
         // if let Ok(data) = Data::new(&cfg) {
         //     return Ok(Self {
@@ -53,11 +87,16 @@ impl Users {
 
         // return Err(UsersError::ConfigurationError);
         let user_data = crate::data::users::UsersData::new(data);
+        let password = Password::new(&cfg.password_policy);
         return Self {
             cfg: cfg,
             mailer: mailer,
             tokenizer: tokenizer,
-            data: user_data
+            data: user_data,
+            password: password,
+            users_by_email: TtlCache::new(std::time::Duration::from_secs(CACHE_TTL_SECONDS)),
+            tenants_by_user: TtlCache::new(std::time::Duration::from_secs(CACHE_TTL_SECONDS)),
+            permissions_by_user: permissions
         };
     }
 
@@ -72,7 +111,7 @@ impl Users {
         ).await {
             Err(e) => {
                 error!("unable to retrieve user: {:?}", e);
-                return Err(UsersError::ToBeImplemented(String::from("user_by_id")));
+                return Err(UsersError::DataError(e));
             }
             Ok(user) => {
                 debug!("Users::user_by_id(): {:?}", user);
@@ -81,26 +120,34 @@ impl Users {
         }
     }
 
+    /// retrieve a user by email address. results are cached for a short time
     pub async fn user_by_email(
         &self,
         email: &str
     ) -> Result<User, UsersError> {
         info!("Users::user_by_email()");
 
+        let key = email.to_lowercase();
+        if let Some(user) = self.users_by_email.get(&key) {
+            return Ok(user);
+        }
+
         match self.data.by_email(
             &email
         ).await {
             Err(e) => {
                 error!("unable to retrieve user: {:?}", e);
-                return Err(UsersError::ToBeImplemented(String::from("user_by_email")));
+                return Err(UsersError::DataError(e));
             }
             Ok(user) => {
                 debug!("Users::user_by_email(): {:?}", user);
+                self.users_by_email.insert(&key, &user);
                 return Ok(user);
             }
         }
     }
 
+    /// set user active status. deactivating a user revokes all of the user's sessions
     pub async fn user_set_active(
         &self,
         user_id: &uuid::Uuid,
@@ -114,28 +161,96 @@ impl Users {
         ).await {
             Err(e) => {
                 error!("unable to set user active status: {:?}", e);
-                return Err(UsersError::ToBeImplemented(String::from("user_set_active")));
+                return Err(UsersError::DataError(e));
             }
             Ok(_) => {
+                self.users_by_email.invalidate_where(|_, user| user.id() == *user_id);
+                if !active {
+                    return self.user_sessions_revoke(&user_id, &uuid::Uuid::nil()).await;
+                }
                 return Ok(());
             }
         }
     }
 
+    /// set user password. all sessions other than current_session_id are revoked
     pub async fn user_set_password(
         &self,
         user_id: &uuid::Uuid,
-        password: &str
+        email: &str,
+        password: &str,
+        current_session_id: &uuid::Uuid
     ) -> Result<(), UsersError> {
         info!("Users::user_set_password()");
 
+        if let Err(failed) = self.password.validate(&password, &email) {
+            return Err(UsersError::PasswordPolicy(failed));
+        }
+
         match self.data.user_set_password(
             &user_id,
             &password
         ).await {
             Err(e) => {
                 error!("unable to set user password: {:?}", e);
-                return Err(UsersError::ToBeImplemented(String::from("user_set_active")));
+                return Err(UsersError::DataError(e));
+            }
+            Ok(_) => {
+                return self.user_sessions_revoke(&user_id, &current_session_id).await;
+            }
+        }
+    }
+
+    /// retrieve active sessions of a user
+    pub async fn user_sessions(
+        &self,
+        user_id: &uuid::Uuid
+    ) -> Result<Vec<Session>, UsersError> {
+        info!("Users::user_sessions()");
+
+        match self.data.user_sessions(&user_id).await {
+            Err(e) => {
+                error!("unable to retrieve user sessions: {:?}", e);
+                return Err(UsersError::DataError(e));
+            }
+            Ok(sessions) => {
+                return Ok(sessions);
+            }
+        }
+    }
+
+    /// revoke a session of a user
+    pub async fn user_session_revoke(
+        &self,
+        user_id: &uuid::Uuid,
+        session_id: &uuid::Uuid
+    ) -> Result<(), UsersError> {
+        info!("Users::user_session_revoke()");
+
+        match self.data.user_session_revoke(&user_id, &session_id).await {
+            Err(e) => {
+                error!("unable to revoke user session: {:?}", e);
+                return Err(UsersError::DataError(e));
+            }
+            Ok(_) => {
+                return Ok(());
+            }
+        }
+    }
+
+    /// revoke every session of a user except for the one specified.
+    /// pass a nil uuid to revoke all sessions
+    pub async fn user_sessions_revoke(
+        &self,
+        user_id: &uuid::Uuid,
+        except_session_id: &uuid::Uuid
+    ) -> Result<(), UsersError> {
+        info!("Users::user_sessions_revoke()");
+
+        match self.data.user_sessions_revoke(&user_id, &except_session_id).await {
+            Err(e) => {
+                error!("unable to revoke user sessions: {:?}", e);
+                return Err(UsersError::DataError(e));
             }
             Ok(_) => {
                 return Ok(());
@@ -143,6 +258,24 @@ impl Users {
         }
     }
 
+    /// check if a session is still active
+    pub async fn session_active(
+        &self,
+        session_id: &uuid::Uuid
+    ) -> Result<bool, UsersError> {
+        info!("Users::session_active()");
+
+        match self.data.session_active(&session_id).await {
+            Err(e) => {
+                error!("unable to check session: {:?}", e);
+                return Err(UsersError::DataError(e));
+            }
+            Ok(active) => {
+                return Ok(active);
+            }
+        }
+    }
+
     pub async fn user_tenant_add(
         &self,
         user_id: &uuid::Uuid,
@@ -156,10 +289,11 @@ impl Users {
         ).await {
             Err(e) => {
                 error!("error: {:?}", e);
-                return Err(UsersError::ToBeImplemented(String::from("user_join_tenant")));
+                return Err(UsersError::DataError(e));
             }
             Ok(result) => {
                 debug!("result: {:?}", result);
+                self.tenants_invalidate(&user_id);
                 return Ok(());
             }
         }
@@ -180,10 +314,11 @@ impl Users {
         ).await {
             Err(e) => {
                 error!("error: {:?}", e);
-                return Err(UsersError::ToBeImplemented(String::from("user_tenant_set_active")));
+                return Err(UsersError::DataError(e));
             }
             Ok(result) => {
                 debug!("result: {:?}", result);
+                self.tenants_invalidate(&user_id);
                 return Ok(());
             }
         }
@@ -202,33 +337,73 @@ impl Users {
         ).await {
             Err(e) => {
                 error!("error: {:?}", e);
-                return Err(UsersError::ToBeImplemented(String::from("user_tenant_set_default")));
+                return Err(UsersError::DataError(e));
             }
             Ok(result) => {
                 debug!("result: {:?}", result);
+                self.tenants_invalidate(&user_id);
                 return Ok(());
             }
         }
     }
 
+    /// retrieve tenants of a user. results are cached for a short time
     pub async fn user_tenants(
         &self,
         user_id: &uuid::Uuid
     ) -> Result<Vec<Tenant>, UsersError> {
         info!("user_tenants");
 
+        if let Some(tenants) = self.tenants_by_user.get(&user_id) {
+            return Ok(tenants);
+        }
+
         match self.data.user_tenants(&user_id).await {
             Err(e) => {
                 error!("error: {:?}", e);
-                return Err(UsersError::ToBeImplemented(String::from("user_tenants")));
+                return Err(UsersError::DataError(e));
             }
             Ok(result) => {
                 // debug!("//TODO result: {:?}", result);
+                self.tenants_by_user.insert(&user_id, &result);
                 return Ok(result);
             }
         }
     }
 
+    /// discard cached tenants and permissions of a user after the user's
+    /// tenant memberships changed
+    fn tenants_invalidate(&self, user_id: &uuid::Uuid) {
+        self.tenants_by_user.invalidate(&user_id);
+        self.permissions_by_user.invalidate_user(&user_id);
+    }
+
+    /// retrieve permissions granted to a user in a tenant. results are
+    /// cached in the permission cache shared with the roles module, which
+    /// discards them when roles change
+    pub async fn user_permissions(
+        &self,
+        user_id: &uuid::Uuid,
+        tenant_id: &uuid::Uuid
+    ) -> Result<Vec<String>, UsersError> {
+        info!("Users::user_permissions()");
+
+        if let Some(permissions) = self.permissions_by_user.get(&user_id, &tenant_id) {
+            return Ok(permissions);
+        }
+
+        match self.data.user_permissions(&user_id, &tenant_id).await {
+            Err(e) => {
+                error!("unable to retrieve user permissions: {:?}", e);
+                return Err(UsersError::DataError(e));
+            }
+            Ok(permissions) => {
+                self.permissions_by_user.insert(&user_id, &tenant_id, &permissions);
+                return Ok(permissions);
+            }
+        }
+    }
+
     pub async fn user_tenant_default(
         &self,
         user_id: &uuid::Uuid
@@ -238,7 +413,7 @@ impl Users {
         match self.data.user_tenants_default(&user_id).await {
             Err(e) => {
                 error!("error: {:?}", e);
-                return Err(UsersError::ToBeImplemented(String::from("user_tenant_default")));
+                return Err(UsersError::DataError(e));
             }
             Ok(result) => {
                 debug!("//TODO result: {:?}", result);
@@ -251,15 +426,16 @@ impl Users {
     pub fn current_user_set_tenant(
         &self,
         email: &str,
-        tenant_id: &uuid::Uuid
+        tenant_id: &uuid::Uuid,
+        session_id: &uuid::Uuid
     ) -> Result<String, UsersError> {
         info!("current_user_set_tenant");
 
-        if let Ok(token) = self.tokenizer.generate(&email, &tenant_id) {
+        if let Ok(token) = self.tokenizer.generate(&email, &tenant_id, &session_id) {
             return Ok(token);
         } else {
             error!("unable to generate new token");
-            return Err(UsersError::ToBeImplemented(String::from("current_user_set_tenant")));
+            return Err(UsersError::TokenGenerationError);
         }
     }
 }
\ No newline at end of file
@@ -5,14 +5,25 @@ use log::{
 };
 
 use configuration::ApplicationConfiguration;
+use data::pg::DataError;
 
 #[derive(Debug)]
 pub enum CountriesError {
-    ToBeImplemented(String),
+    DataError(DataError),
     ConfigurationError,
     ValidationError
 }
 
+impl From<CountriesError> for common::Error {
+    fn from(e: CountriesError) -> Self {
+        match e {
+            CountriesError::DataError(e) => common::Error::from(e),
+            CountriesError::ConfigurationError => common::Error::ConfigurationError(String::from("countries are not configured")),
+            CountriesError::ValidationError => common::Error::ValidationError(String::from("invalid request"))
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct Countries {
     data: crate::data::countries::Data
@@ -44,7 +55,7 @@ impl Countries {
         match self.data.fetch_all().await {
             Err(e) => {
                 error!("unable to fetch countries");
-                return Err(CountriesError::ToBeImplemented(String::from("countries()")));
+                return Err(CountriesError::DataError(e));
             }
             Ok(countries) => {
                 return Ok(countries);
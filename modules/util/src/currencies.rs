@@ -5,14 +5,25 @@ use log::{
 };
 
 use configuration::ApplicationConfiguration;
+use data::pg::DataError;
 
 #[derive(Debug)]
 pub enum CurrenciesError {
-    ToBeImplemented(String),
+    DataError(DataError),
     ConfigurationError,
     ValidationError
 }
 
+impl From<CurrenciesError> for common::Error {
+    fn from(e: CurrenciesError) -> Self {
+        match e {
+            CurrenciesError::DataError(e) => common::Error::from(e),
+            CurrenciesError::ConfigurationError => common::Error::ConfigurationError(String::from("currencies are not configured")),
+            CurrenciesError::ValidationError => common::Error::ValidationError(String::from("invalid request"))
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct Currencies {
     data: crate::data::currencies::Data
@@ -44,7 +55,7 @@ impl Currencies {
         match self.data.fetch_all().await {
             Err(e) => {
                 error!("unable to fetch currencies");
-                return Err(CurrenciesError::ToBeImplemented(String::from("currencies()")));
+                return Err(CurrenciesError::DataError(e));
             }
             Ok(currencies) => {
                 return Ok(currencies);
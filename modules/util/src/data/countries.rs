@@ -18,6 +18,8 @@ use tokio_postgres::{
 use tokio_postgres::config::{ Config };
 
 use data::pg::DataError;
+use data::pg::timed;
+use data::pg::pools::PgPools;
 
 
 use configuration::{
@@ -29,7 +31,8 @@ use configuration::{
 
 #[derive(Debug, Clone)]
 pub struct Data {
-    pool: Pool
+    /// the queries are read-only and may be served by a replica
+    pools: PgPools
 }
 
 impl Data {
@@ -75,17 +78,17 @@ impl Data {
         data: data::Data
     ) -> Self {
         return Self {
-            pool: data.get_pg_pool().unwrap()
+            pools: data.get_pg_pools().unwrap()
         };
     }
 
     pub async fn fetch_all(&self) -> Result<Vec<common::country::Country>, DataError> {
         info!("Data::fetch_all()");
 
-        let result = self.pool.get().await;
+        let result = self.pools.read().await;
         if let Err(e) = result {
             error!("unable to retrieve database client: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::Unavailable);
         }
         let client = result.unwrap();
 
@@ -94,17 +97,17 @@ impl Data {
         ).await;
         if let Err(e) = result {
             error!("unable to prepare database statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         }
         let stmt = result.unwrap();
 
-        match client.query(
+        match timed("common.countries_fetch", client.query(
             &stmt,
             &[]
-        ).await {
+        )).await {
             Err(e) => {
                 error!("unable to execute statement: {:?}", e);
-                return Err(DataError::DatabaseError);
+                return Err(DataError::from(&e));
             }
             Ok(rows) => {
                 // debug!("rows: {:?}", rows);
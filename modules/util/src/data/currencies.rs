@@ -17,6 +17,8 @@ use tokio_postgres::{
 use tokio_postgres::config::{ Config };
 
 use data::pg::DataError;
+use data::pg::timed;
+use data::pg::pools::PgPools;
 
 
 use configuration::{
@@ -28,7 +30,8 @@ use configuration::{
 
 #[derive(Debug, Clone)]
 pub struct Data {
-    pool: Pool
+    /// the queries are read-only and may be served by a replica
+    pools: PgPools
 }
 
 impl Data {
@@ -74,17 +77,17 @@ impl Data {
         data: data::Data
     ) -> Self {
         return Self {
-            pool: data.get_pg_pool().unwrap()
+            pools: data.get_pg_pools().unwrap()
         };
     }
 
     pub async fn fetch_all(&self) -> Result<Vec<common::currency::Currency>, DataError> {
         info!("Data::fetch_all()");
 
-        let result = self.pool.get().await;
+        let result = self.pools.read().await;
         if let Err(e) = result {
             error!("unable to retrieve database client: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::Unavailable);
         }
         let client = result.unwrap();
 
@@ -93,17 +96,17 @@ impl Data {
         ).await;
         if let Err(e) = result {
             error!("unable to prepare database statement: {:?}", e);
-            return Err(DataError::DatabaseError);
+            return Err(DataError::from(&e));
         }
         let stmt = result.unwrap();
 
-        match client.query(
+        match timed("common.currencies_fetch", client.query(
             &stmt,
             &[]
-        ).await {
+        )).await {
             Err(e) => {
                 error!("unable to execute statement: {:?}", e);
-                return Err(DataError::DatabaseError);
+                return Err(DataError::from(&e));
             }
             Ok(rows) => {
                 // debug!("rows: {:?}", rows);
@@ -0,0 +1,33 @@
+use std::env;
+use std::process::Command;
+
+
+/// embed the git commit and build time for /status/info. IDEAS_GIT_COMMIT
+/// and IDEAS_BUILD_TIME override them, e.g. when building without .git
+fn main() {
+    println!("cargo:rerun-if-changed=../.git/HEAD");
+    println!("cargo:rerun-if-changed=../.git/refs");
+    println!("cargo:rerun-if-env-changed=IDEAS_GIT_COMMIT");
+    println!("cargo:rerun-if-env-changed=IDEAS_BUILD_TIME");
+
+    let commit = env::var("IDEAS_GIT_COMMIT").ok()
+        .or_else(git_commit)
+        .unwrap_or_else(|| String::from("unknown"));
+    let build_time = env::var("IDEAS_BUILD_TIME").ok()
+        .unwrap_or_else(|| chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true));
+
+    println!("cargo:rustc-env=IDEAS_GIT_COMMIT={}", commit);
+    println!("cargo:rustc-env=IDEAS_BUILD_TIME={}", build_time);
+}
+
+fn git_commit() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let commit = String::from_utf8(output.stdout).ok()?;
+    return Some(String::from(commit.trim()));
+}
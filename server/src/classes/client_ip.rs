@@ -0,0 +1,84 @@
+use std::net::IpAddr;
+
+use actix_web::HttpRequest;
+use ipnet::IpNet;
+
+
+/// address of the client making a request. X-Forwarded-For entries are
+/// only followed while the hop that added them is a trusted proxy, so a
+/// client cannot choose the address its requests are counted under
+pub fn client_ip(
+    request: &HttpRequest,
+    trusted_proxies: &[String]
+) -> Option<String> {
+    let peer = request.peer_addr()?.ip();
+    let forwarded: Vec<&str> = request.headers()
+        .get_all("x-forwarded-for")
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|hop| hop.trim())
+        .collect();
+    return Some(resolve(peer, &forwarded, &trusted(trusted_proxies)).to_string());
+}
+
+fn trusted(trusted_proxies: &[String]) -> Vec<IpNet> {
+    return trusted_proxies.iter()
+        .filter_map(|proxy| {
+            return proxy.parse::<IpNet>().ok()
+                .or_else(|| proxy.parse::<IpAddr>().ok().map(IpNet::from));
+        })
+        .collect();
+}
+
+/// walk the forwarded hops from the nearest, the last one added, and stop
+/// at the first address that is not a trusted proxy
+fn resolve(
+    peer: IpAddr,
+    forwarded: &[&str],
+    trusted: &[IpNet]
+) -> IpAddr {
+    let mut client = peer;
+    for hop in forwarded.iter().rev() {
+        if !trusted.iter().any(|net| net.contains(&client)) {
+            break;
+        }
+        match hop.parse::<IpAddr>() {
+            Err(_) => {
+                break;
+            }
+            Ok(ip) => {
+                client = ip;
+            }
+        }
+    }
+    return client;
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(s: &str) -> IpAddr {
+        return s.parse().unwrap();
+    }
+
+    #[test]
+    fn test_resolve() {
+        let proxies = trusted(&[String::from("10.0.0.0/8"), String::from("192.168.1.5")]);
+
+        // no trusted proxies, the header is ignored
+        assert_eq!(resolve(ip("203.0.113.9"), &["198.51.100.1"], &[]), ip("203.0.113.9"));
+        // the peer is not a proxy
+        assert_eq!(resolve(ip("203.0.113.9"), &["198.51.100.1"], &proxies), ip("203.0.113.9"));
+        // a trusted proxy names the client
+        assert_eq!(resolve(ip("10.1.2.3"), &["198.51.100.1"], &proxies), ip("198.51.100.1"));
+        // entries the client added before the first untrusted hop are ignored
+        assert_eq!(
+            resolve(ip("10.1.2.3"), &["1.2.3.4", "198.51.100.1", "192.168.1.5"], &proxies),
+            ip("198.51.100.1")
+        );
+        // garbage stops the walk at the last trusted hop
+        assert_eq!(resolve(ip("10.1.2.3"), &["not-an-ip"], &proxies), ip("10.1.2.3"));
+    }
+}
@@ -0,0 +1,148 @@
+use std::collections::BTreeMap;
+use std::ops::Deref;
+
+use log::{
+    debug
+};
+
+use futures::future::LocalBoxFuture;
+use serde::de::DeserializeOwned;
+use validator::{
+    Validate,
+    ValidationErrors
+};
+
+use actix_web::{
+    FromRequest,
+    HttpRequest,
+    dev::Payload,
+    error::JsonPayloadError,
+    web
+};
+
+use common::Error;
+
+
+/// json body that is validated before the handler runs. failures are
+/// returned as 422 with the messages of each failing field
+#[derive(Debug)]
+pub struct Validated<T>(pub T);
+
+impl<T> Deref for Validated<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        return &self.0;
+    }
+}
+
+impl<T> FromRequest for Validated<T>
+where
+    T: DeserializeOwned + Validate + 'static
+{
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(request: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let json = web::Json::<T>::from_request(request, payload);
+        return Box::pin(async move {
+            let value = match json.await {
+                Err(e) => {
+                    // bodies that do not match the request type are invalid
+                    // requests rather than malformed ones
+                    if let Some(JsonPayloadError::Deserialize(d)) = e.as_error::<JsonPayloadError>() {
+                        return Err(Error::ValidationError(d.to_string()).into());
+                    }
+                    return Err(e);
+                }
+                Ok(value) => value.into_inner()
+            };
+
+            if let Err(errors) = value.validate() {
+                debug!("request is not valid: {:?}", errors);
+                return Err(Error::InvalidFields(fields(&errors)).into());
+            }
+            return Ok(Validated(value));
+        });
+    }
+}
+
+/// messages of the failing fields keyed by field name
+fn fields(errors: &ValidationErrors) -> BTreeMap<String, Vec<String>> {
+    return errors.field_errors()
+        .iter()
+        .map(|(field, errors)| {
+            let messages = errors.iter()
+                .map(|e| match &e.message {
+                    Some(message) => message.to_string(),
+                    None => e.code.to_string()
+                })
+                .collect();
+            return (field.to_string(), messages);
+        })
+        .collect();
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web::{
+        App,
+        HttpResponse,
+        http::StatusCode,
+        test
+    };
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, Validate)]
+    struct TestRequest {
+        #[validate(custom(function = "crate::classes::validators::not_blank"))]
+        name: String,
+        #[validate(email(message = "must be a valid email address"))]
+        email: String
+    }
+
+    async fn handler(params: Validated<TestRequest>) -> HttpResponse {
+        return HttpResponse::Ok().body(params.name.clone());
+    }
+
+    #[actix_web::test]
+    async fn test_valid_request() {
+        let app = test::init_service(App::new().route("/", web::post().to(handler))).await;
+        let request = test::TestRequest::post()
+            .uri("/")
+            .set_json(serde_json::json!({ "name": "acme", "email": "admin@acme.test" }))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn test_invalid_fields() {
+        let app = test::init_service(App::new().route("/", web::post().to(handler))).await;
+        let request = test::TestRequest::post()
+            .uri("/")
+            .set_json(serde_json::json!({ "name": " ", "email": "acme" }))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let body: serde_json::Value = test::read_body_json(response).await;
+        assert_eq!(body["code"], "validation");
+        assert_eq!(body["data"]["fields"]["name"][0], "must not be blank");
+        assert_eq!(body["data"]["fields"]["email"][0], "must be a valid email address");
+    }
+
+    #[actix_web::test]
+    async fn test_missing_field() {
+        let app = test::init_service(App::new().route("/", web::post().to(handler))).await;
+        let request = test::TestRequest::post()
+            .uri("/")
+            .set_json(serde_json::json!({ "name": "acme" }))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    }
+}
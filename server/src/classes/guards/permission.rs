@@ -33,11 +33,17 @@ impl Permission {
 impl Guard for Permission {
 
     fn check(&self, context: &GuardContext<'_>) -> bool {
-        // debug!("Permission::check() (context): {:?}", context);
         if let Some(user) = context.req_data().get::<CurrentUser>() {
-            debug!("//TODO Permission::check() (user): {:?}", user);
+            if user.has_permission(&self.permission) {
+                return true;
+            }
+            match user.api_key_id() {
+                Some(api_key_id) => debug!("permission {} not granted to api key {:?}", self.permission, api_key_id),
+                None => debug!("permission {} not granted to user {:?}", self.permission, user.id())
+            }
+            return false;
         }
-        debug!("//TODO Permission::check()");
-        return true;
+        error!("Permission::check() current user not found");
+        return false;
     }
-}
\ No newline at end of file
+}
@@ -1,3 +1,6 @@
-// pub mod extractors;
+pub mod client_ip;
+pub mod extractors;
 pub mod guards;
-pub mod user;
\ No newline at end of file
+pub mod routes;
+pub mod user;
+pub mod validators;
\ No newline at end of file
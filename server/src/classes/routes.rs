@@ -0,0 +1,232 @@
+use log::debug;
+
+use actix_web::{
+    FromRequest,
+    Handler,
+    Responder,
+    Route as ActixRoute,
+    guard::Guard,
+    http::Method,
+    web
+};
+
+
+/// method and path of a route served by the api
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteEntry {
+    pub method: Method,
+    pub path: String,
+    /// get routes answering that the service is up, kept for clients of
+    /// the legacy endpoints. these are not documented
+    pub placeholder: bool
+}
+
+
+/// routes of a scope. resources are registered with actix and the method
+/// and path of every route is kept, so the routes served can be listed,
+/// e.g. to check that they are documented
+pub struct Routes {
+    prefix: String,
+    resources: Vec<actix_web::Resource>,
+    entries: Vec<RouteEntry>
+}
+
+impl Routes {
+
+    pub fn new(prefix: &str) -> Self {
+        return Self {
+            prefix: prefix.to_string(),
+            resources: Vec::new(),
+            entries: Vec::new()
+        };
+    }
+
+    pub fn service(&mut self, resource: Resource) -> &mut Self {
+        let path = join(&self.prefix, &resource.path);
+        for (method, placeholder) in resource.methods {
+            self.entries.push(RouteEntry {
+                method: method,
+                path: path.clone(),
+                placeholder: placeholder
+            });
+        }
+        self.resources.push(resource.resource);
+        return self;
+    }
+
+    pub fn configure(&mut self, config: fn(&mut Routes)) -> &mut Self {
+        config(self);
+        return self;
+    }
+
+    pub fn entries(&self) -> &[RouteEntry] {
+        return &self.entries;
+    }
+
+    /// register the resources with actix
+    pub fn register(self, cfg: &mut web::ServiceConfig) {
+        for resource in self.resources {
+            cfg.service(resource);
+        }
+    }
+}
+
+
+/// actix scope serving the routes of a config function
+pub fn scope(prefix: &str, config: fn(&mut Routes)) -> actix_web::Scope {
+    let mut routes = Routes::new(prefix);
+    config(&mut routes);
+    for entry in routes.entries() {
+        debug!("route: {} {}", entry.method, entry.path);
+    }
+    return web::scope(prefix).configure(move |cfg| routes.register(cfg));
+}
+
+fn join(prefix: &str, path: &str) -> String {
+    let path = format!("/{}/{}", prefix.trim_matches('/'), path.trim_matches('/'));
+    let path = path.replace("//", "/");
+    if path.len() > 1 {
+        return path.trim_end_matches('/').to_string();
+    }
+    return path;
+}
+
+
+/// resource whose routes are recorded in the route table
+pub struct Resource {
+    path: String,
+    resource: actix_web::Resource,
+    methods: Vec<(Method, bool)>
+}
+
+pub fn resource(path: &str) -> Resource {
+    return Resource {
+        path: path.to_string(),
+        resource: web::resource(path),
+        methods: Vec::new()
+    };
+}
+
+impl Resource {
+
+    pub fn route(mut self, route: Route) -> Self {
+        self.methods.push((route.method.clone(), route.placeholder));
+        self.resource = self.resource.route(route.route.method(route.method));
+        return self;
+    }
+
+    /// service answering requests none of the routes accept
+    pub fn default_service(mut self, route: ActixRoute) -> Self {
+        self.resource = self.resource.default_service(route);
+        return self;
+    }
+}
+
+
+/// route of a resource along with its method
+pub struct Route {
+    method: Method,
+    placeholder: bool,
+    route: ActixRoute
+}
+
+pub fn method(method: Method) -> Route {
+    return Route {
+        method: method,
+        placeholder: false,
+        route: web::route()
+    };
+}
+
+pub fn get() -> Route {
+    return method(Method::GET);
+}
+
+pub fn post() -> Route {
+    return method(Method::POST);
+}
+
+pub fn patch() -> Route {
+    return method(Method::PATCH);
+}
+
+pub fn delete() -> Route {
+    return method(Method::DELETE);
+}
+
+/// get route answering that the service is up
+pub fn placeholder() -> Route {
+    let mut route = get();
+    route.placeholder = true;
+    return route;
+}
+
+impl Route {
+
+    pub fn guard<G: Guard + 'static>(mut self, guard: G) -> Self {
+        self.route = self.route.guard(guard);
+        return self;
+    }
+
+    pub fn to<F, Args>(mut self, handler: F) -> Self
+    where
+        F: Handler<Args>,
+        Args: FromRequest + 'static,
+        F::Output: Responder + 'static
+    {
+        self.route = self.route.to(handler);
+        return self;
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web::{
+        App,
+        HttpResponse,
+        http::StatusCode,
+        test::{
+            TestRequest,
+            call_service,
+            init_service
+        }
+    };
+
+    async fn ok() -> HttpResponse {
+        return HttpResponse::Ok().finish();
+    }
+
+    fn config(routes: &mut Routes) {
+        routes
+            .service(
+                resource("")
+                    .route(placeholder().to(ok))
+                    .route(post().to(ok))
+            )
+            .service(
+                resource("/{id}")
+                    .route(patch().to(ok))
+            )
+        ;
+    }
+
+    #[actix_web::test]
+    async fn test_routes() {
+        let mut routes = Routes::new("/items");
+        routes.configure(config);
+        assert_eq!(routes.entries(), &[
+            RouteEntry { method: Method::GET, path: String::from("/items"), placeholder: true },
+            RouteEntry { method: Method::POST, path: String::from("/items"), placeholder: false },
+            RouteEntry { method: Method::PATCH, path: String::from("/items/{id}"), placeholder: false }
+        ]);
+
+        let app = init_service(App::new().service(scope("/items", config))).await;
+        let request = TestRequest::patch().uri("/items/1").to_request();
+        assert_eq!(call_service(&app, request).await.status(), StatusCode::OK);
+        let request = TestRequest::delete().uri("/items/1").to_request();
+        assert_eq!(call_service(&app, request).await.status(), StatusCode::METHOD_NOT_ALLOWED);
+    }
+}
@@ -38,6 +38,7 @@ use tokenizer::Tokenizer;
 
 use common::user::User;
 use common::tenant::Tenant;
+use common::api_key::ApiKey;
 use users::users::Users;
 
 
@@ -66,7 +67,14 @@ pub struct CurrentUser {
     id: uuid::Uuid,
     email: String,
     tenant_id: uuid::Uuid,
-    tenants: Vec<Tenant>
+    session_id: uuid::Uuid,
+    tenants: Vec<Tenant>,
+    /// permissions granted to the user in the current tenant
+    permissions: Vec<String>,
+    /// set if the request was authenticated using an api key
+    api_key_id: Option<uuid::Uuid>,
+    /// permissions the principal is restricted to, if any
+    scope: Option<Vec<String>>
 }
 
 impl CurrentUser {
@@ -75,13 +83,37 @@ impl CurrentUser {
         id: &uuid::Uuid,
         email: &str,
         tenant_id: &uuid::Uuid,
-        tenants: Vec<Tenant>
+        session_id: &uuid::Uuid,
+        tenants: Vec<Tenant>,
+        permissions: Vec<String>
     ) -> Self {
         return Self {
             id: id.clone(),
             email: String::from(email),
             tenant_id: tenant_id.clone(),
-            tenants: tenants.clone()
+            session_id: session_id.clone(),
+            tenants: tenants.clone(),
+            permissions: permissions,
+            api_key_id: None,
+            scope: None
+        };
+    }
+
+    /// principal for a request authenticated using an api key. the principal
+    /// has no email and is not considered an authenticated user
+    pub fn api_key(
+        api_key: &ApiKey,
+        tenants: Vec<Tenant>
+    ) -> Self {
+        return Self {
+            id: api_key.id(),
+            email: String::from(""),
+            tenant_id: api_key.tenant_id(),
+            session_id: uuid::Uuid::nil(),
+            tenants: tenants,
+            permissions: Vec::new(),
+            api_key_id: Some(api_key.id()),
+            scope: api_key.permissions()
         };
     }
 
@@ -90,7 +122,11 @@ impl CurrentUser {
             id: uuid::Uuid::nil(),
             email: String::from(""),
             tenant_id: uuid::Uuid::nil(),
-            tenants: Vec::new()
+            session_id: uuid::Uuid::nil(),
+            tenants: Vec::new(),
+            permissions: Vec::new(),
+            api_key_id: None,
+            scope: None
         };
     }
 
@@ -110,9 +146,35 @@ impl CurrentUser {
         return self.tenant_id.clone();
     }
 
+    pub fn session_id(&self) -> uuid::Uuid {
+        return self.session_id.clone();
+    }
+
     pub fn tenants(&self) -> Vec<Tenant> {
         return self.tenants.clone();
     }
+
+    pub fn permissions(&self) -> Vec<String> {
+        return self.permissions.clone();
+    }
+
+    /// check if the principal has been granted a permission. an api key is
+    /// granted the permissions of its scope only, a key without a scope is
+    /// granted none
+    pub fn has_permission(&self, permission: &str) -> bool {
+        if self.is_api_key() {
+            return self.scope.as_ref().is_some_and(|scope| scope.iter().any(|p| p == permission));
+        }
+        return self.is_authenticated() && self.permissions.iter().any(|p| p == permission);
+    }
+
+    pub fn is_api_key(&self) -> bool {
+        return self.api_key_id.is_some();
+    }
+
+    pub fn api_key_id(&self) -> Option<uuid::Uuid> {
+        return self.api_key_id.clone();
+    }
 }
 
 
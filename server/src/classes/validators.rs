@@ -0,0 +1,56 @@
+use validator::ValidationError;
+
+
+/// longest slug accepted for tenants and roles
+pub const SLUG_MAX_LENGTH: usize = 100;
+
+
+/// value must contain more than whitespace
+pub fn not_blank(value: &str) -> Result<(), ValidationError> {
+    if value.trim().is_empty() {
+        let mut e = ValidationError::new("not_blank");
+        e.message = Some("must not be blank".into());
+        return Err(e);
+    }
+    return Ok(());
+}
+
+/// lowercase letters and digits separated by single hyphens
+pub fn slug(value: &str) -> Result<(), ValidationError> {
+    let valid = !value.is_empty()
+        && value.len() <= SLUG_MAX_LENGTH
+        && value.split('-').all(|part| {
+            !part.is_empty() && part.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+        });
+    if !valid {
+        let mut e = ValidationError::new("slug");
+        e.message = Some("must be lowercase letters and digits separated by hyphens".into());
+        return Err(e);
+    }
+    return Ok(());
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_not_blank() {
+        assert!(not_blank("acme").is_ok());
+        assert!(not_blank("").is_err());
+        assert!(not_blank("  \t").is_err());
+    }
+
+    #[test]
+    fn test_slug() {
+        assert!(slug("acme").is_ok());
+        assert!(slug("acme-corp-2").is_ok());
+        assert!(slug("").is_err());
+        assert!(slug("Acme").is_err());
+        assert!(slug("acme corp").is_err());
+        assert!(slug("-acme").is_err());
+        assert!(slug("acme--corp").is_err());
+        assert!(slug(&"a".repeat(SLUG_MAX_LENGTH + 1)).is_err());
+    }
+}
@@ -15,30 +15,60 @@ use serde::{
     Deserialize
 };
 use serde_json::json;
+use validator::Validate;
+use utoipa::{
+    OpenApi,
+    ToSchema
+};
 
 use crate::endpoints::{
-    ApiResponse
+    ApiResponse,
+    default_options,
+    default_service
+};
+use crate::classes::routes::{
+    self,
+    Routes
 };
 use crate::classes::user::CurrentUser;
+use crate::classes::extractors::validated::Validated;
+use crate::classes::validators;
+use crate::classes::guards::permission::Permission;
 
 
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
 struct AccountAddRequest {
+    #[validate(custom(function = "validators::not_blank"))]
     pub account_id: String,
+    #[validate(custom(function = "validators::not_blank"), length(max = 100, message = "must be at most 100 characters"))]
     pub name: String,
+    #[validate(length(max = 1000, message = "must be at most 1000 characters"))]
     pub description: String,
+    #[validate(range(min = 1, message = "must be a valid id"))]
     pub currency_id: i16
 }
 
 
 
-pub fn config(cfg: &mut web::ServiceConfig) {
+#[derive(OpenApi)]
+#[openapi(paths(
+    account_add_post
+))]
+pub struct ApiDoc;
+
+
+pub fn config(cfg: &mut Routes) {
     cfg
         .service(
-            web::resource("add")
-                .route(web::get().to(account_add_get))
-                .route(web::post().to(account_add_post))
+            routes::resource("add")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::placeholder().to(account_add_get))
+                .route(routes::post()
+                    .guard(Permission::new("accounting.accounts.add"))
+                    .to(account_add_post)
+                )
+                .default_service(web::to(default_service))
         )
     ;
 }
@@ -50,9 +80,22 @@ async fn account_add_get() -> impl Responder {
     return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
 }
 
+/// add an account
+#[utoipa::path(
+    post,
+    path = "/add",
+    request_body = AccountAddRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 422, description = "request is not valid", body = ApiResponse)
+    ),
+    security(("bearer" = ["accounting.accounts.add"]), ("api_key" = ["accounting.accounts.add"]))
+)]
 async fn account_add_post(
     user: CurrentUser,
-    params: web::Json<AccountAddRequest>
+    params: Validated<AccountAddRequest>
 ) -> impl Responder {
     info!("account_add_post()");
     debug!("params: {:?}", params);
@@ -5,8 +5,10 @@ use log::{
 };
 
 use actix_web::{
+    HttpRequest,
     HttpResponse, 
     Responder,
+    ResponseError,
     web
 };
 use serde::{
@@ -14,66 +16,255 @@ use serde::{
     Deserialize
 };
 use serde_json::json;
+use validator::Validate;
+use utoipa::{
+    OpenApi,
+    ToSchema
+};
 
-use http::header::AUTHORIZATION;
+use http::header::{
+    AUTHORIZATION,
+    RETRY_AFTER
+};
+use chrono::prelude::*;
 
 
 use configuration::ApplicationConfiguration;
 use crate::endpoints::{
     ApiResponse,
-    default_options
+    error_response,
+    default_options,
+    default_service
+};
+use crate::classes::routes::{
+    self,
+    Routes
 };
+use crate::classes::client_ip::client_ip;
+use crate::classes::user::CurrentUser;
+use crate::classes::extractors::validated::Validated;
+use crate::classes::guards::authenticated::Authenticated;
 
-use auth::auth::{Auth, AuthError};
+use tokenizer::Tokenizer;
+use auth::auth::{Auth, AuthError, SignInResult};
+use common::Error;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
 struct AuthRegistrationRequest {
+    #[schema(value_type = String, format = "uuid")]
     pub id: uuid::Uuid,
+    #[validate(email(message = "must be a valid email address"))]
     pub email: String
 }
 
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 struct AuthRegistrationInfoRequest {
     pub token: String
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 struct AuthRegistrationCompleteRequest {
     pub token: String,
     pub password: String
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 struct SignInRequest {
     pub email: String,
     pub password: String
 }
 
-pub fn config(cfg: &mut web::ServiceConfig) {
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+struct MfaVerifyRequest {
+    pub mfa_token: String,
+    pub code: String
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+struct MfaEnrollRequest {
+    /// required if the user is not signed in yet
+    #[serde(default)]
+    pub mfa_token: Option<String>
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+struct MfaConfirmRequest {
+    #[serde(default)]
+    pub mfa_token: Option<String>,
+    pub code: String
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+struct MfaDisableRequest {
+    pub code: String
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+struct AccountUnlockRequest {
+    pub token: String
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+struct PasswordForgotRequest {
+    #[validate(email(message = "must be a valid email address"))]
+    pub email: String
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+struct PasswordResetRequest {
+    pub token: String,
+    pub password: String
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+struct OidcAuthorizeRequest {
+    pub provider: String
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+struct OidcCallbackRequest {
+    pub state: String,
+    pub code: String
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+struct TokenRefreshRequest {
+    pub refresh_token: String
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+struct SignOutRequest {
+    /// revoke every session of the user instead of only the current one
+    #[serde(default)]
+    pub everywhere: bool
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    register_post,
+    register_info_post,
+    register_complete_post,
+    auth_signin_post,
+    mfa_verify_post,
+    mfa_enroll_post,
+    mfa_confirm_post,
+    mfa_disable_post,
+    account_unlock_post,
+    password_forgot_post,
+    password_reset_post,
+    oidc_authorize_post,
+    oidc_callback_post,
+    token_refresh_post,
+    auth_signout_post,
+    jwks_get
+))]
+pub struct ApiDoc;
+
+
+pub fn config(cfg: &mut Routes) {
     cfg
         .service(
-            web::resource("/register")
-                .route(web::method(http::Method::OPTIONS).to(default_options))
-                .route(web::get().to(register_get))
-                .route(web::post().to(register_post))
+            routes::resource("/register")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::placeholder().to(register_get))
+                .route(routes::post().to(register_post))
         ).service(
-            web::resource("/register/info")
-                .route(web::method(http::Method::OPTIONS).to(default_options))
-                .route(web::get().to(register_info_get))
-                .route(web::post().to(register_info_post))
+            routes::resource("/register/info")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::placeholder().to(register_info_get))
+                .route(routes::post().to(register_info_post))
+        )
+        .service(
+            routes::resource("/register/complete")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::placeholder().to(register_complete_get))
+                .route(routes::post().to(register_complete_post))
+        )
+        .service(
+            routes::resource("/sign-in")
+            .route(routes::method(http::Method::OPTIONS).to(default_options))
+            .route(routes::placeholder().to(auth_signin_get))
+            .route(routes::post().to(auth_signin_post))
+        )
+        .service(
+            routes::resource("/mfa/verify")
+            .route(routes::method(http::Method::OPTIONS).to(default_options))
+            .route(routes::placeholder().to(mfa_verify_get))
+            .route(routes::post().to(mfa_verify_post))
+        )
+        .service(
+            routes::resource("/mfa/enroll")
+            .route(routes::method(http::Method::OPTIONS).to(default_options))
+            .route(routes::placeholder().to(mfa_enroll_get))
+            .route(routes::post().to(mfa_enroll_post))
         )
         .service(
-            web::resource("/register/complete")
-                .route(web::method(http::Method::OPTIONS).to(default_options))
-                .route(web::get().to(register_complete_get))
-                .route(web::post().to(register_complete_post))
+            routes::resource("/mfa/confirm")
+            .route(routes::method(http::Method::OPTIONS).to(default_options))
+            .route(routes::placeholder().to(mfa_confirm_get))
+            .route(routes::post().to(mfa_confirm_post))
         )
         .service(
-            web::resource("/sign-in")
-            .route(web::method(http::Method::OPTIONS).to(default_options))
-            .route(web::get().to(auth_signin_get))
-            .route(web::post().to(auth_signin_post))
+            routes::resource("/mfa/disable")
+            .route(routes::method(http::Method::OPTIONS).to(default_options))
+            .route(routes::placeholder().to(mfa_disable_get))
+            .route(routes::post()
+                .guard(Authenticated::new())
+                .to(mfa_disable_post)
+            )
+            .default_service(web::to(default_service))
+        )
+        .service(
+            routes::resource("/unlock")
+            .route(routes::method(http::Method::OPTIONS).to(default_options))
+            .route(routes::placeholder().to(account_unlock_get))
+            .route(routes::post().to(account_unlock_post))
+        )
+        .service(
+            routes::resource("/password/forgot")
+            .route(routes::method(http::Method::OPTIONS).to(default_options))
+            .route(routes::placeholder().to(password_forgot_get))
+            .route(routes::post().to(password_forgot_post))
+        )
+        .service(
+            routes::resource("/password/reset")
+            .route(routes::method(http::Method::OPTIONS).to(default_options))
+            .route(routes::placeholder().to(password_reset_get))
+            .route(routes::post().to(password_reset_post))
+        )
+        .service(
+            routes::resource("/oidc/authorize")
+            .route(routes::method(http::Method::OPTIONS).to(default_options))
+            .route(routes::placeholder().to(oidc_authorize_get))
+            .route(routes::post().to(oidc_authorize_post))
+        )
+        .service(
+            routes::resource("/oidc/callback")
+            .route(routes::method(http::Method::OPTIONS).to(default_options))
+            .route(routes::placeholder().to(oidc_callback_get))
+            .route(routes::post().to(oidc_callback_post))
+        )
+        .service(
+            routes::resource("/token/refresh")
+            .route(routes::method(http::Method::OPTIONS).to(default_options))
+            .route(routes::placeholder().to(token_refresh_get))
+            .route(routes::post().to(token_refresh_post))
+        )
+        .service(
+            routes::resource("/signout")
+            .route(routes::method(http::Method::OPTIONS).to(default_options))
+            .route(routes::placeholder().to(auth_signout_get))
+            .route(routes::post()
+                .guard(Authenticated::new())
+                .to(auth_signout_post)
+            )
+            .default_service(web::to(default_service))
+        )
+        .service(
+            routes::resource("/.well-known/jwks.json")
+            .route(routes::method(http::Method::OPTIONS).to(default_options))
+            .route(routes::get().to(jwks_get))
         )
     ;
 }
@@ -84,10 +275,21 @@ async fn register_get() -> impl Responder {
 }
 
 
+/// start a registration and email the sign up link
+#[utoipa::path(
+    post,
+    path = "/register",
+    request_body = AuthRegistrationRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 422, description = "request is not valid", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    )
+)]
 async fn register_post(
     // cfg: web::Data<ApplicationConfiguration>,
     auth: web::Data<Auth>,
-    params: web::Json<AuthRegistrationRequest>
+    params: Validated<AuthRegistrationRequest>
 ) -> impl Responder {
     info!("register_post()");
 
@@ -96,12 +298,7 @@ async fn register_post(
         &params.email
     ).await {
         error!("unable to register: {:?}", e);
-        return HttpResponse::InternalServerError()
-            .json(ApiResponse::new(
-                false,
-                "an error occured while trying to register",
-                None
-            ));
+        return error_response(e, "an error occured while trying to register");
     } else {
         return HttpResponse::Ok()
             .json(ApiResponse::new(
@@ -119,6 +316,16 @@ async fn register_info_get() -> impl Responder {
 }
 
 
+/// retrieve the registration of a sign up token
+#[utoipa::path(
+    post,
+    path = "/register/info",
+    request_body = AuthRegistrationInfoRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    )
+)]
 async fn register_info_post(
     auth: web::Data<Auth>,
     params: web::Json<AuthRegistrationInfoRequest>
@@ -128,12 +335,7 @@ async fn register_info_post(
     match auth.get_registration_info(&params.token).await {
         Err(e) => {
             error!("unable to get registration info: {:?}", e);
-            return HttpResponse::InternalServerError()
-                .json(ApiResponse::new(
-                    false,
-                    "unable to retrieve registration info",
-                    None
-                ));
+            return error_response(e, "unable to retrieve registration info");
         }
         Ok(info) => {
             debug!("info: {:?}", info);
@@ -157,6 +359,17 @@ async fn register_complete_get() -> impl Responder {
 }
 
 
+/// complete a registration by setting the password
+#[utoipa::path(
+    post,
+    path = "/register/complete",
+    request_body = AuthRegistrationCompleteRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 422, description = "request is not valid", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    )
+)]
 async fn register_complete_post(
     auth: web::Data<Auth>,
     params: web::Json<AuthRegistrationCompleteRequest>
@@ -164,14 +377,15 @@ async fn register_complete_post(
     info!("register_complete_post()");
 
     match auth.complete_registration(&params.token, &params.password).await {
+        Err(AuthError::PasswordPolicy(failed)) => {
+            return Error::ValidationError(String::from("password does not meet the requirements"))
+                .error_response_with(json!({
+                    "failed": failed
+                }));
+        }
         Err(e) => {
             error!("an error occured while trying to complete the registration: {:?}", e);
-            return HttpResponse::InternalServerError()
-                .json(ApiResponse::new(
-                    false,
-                    "an error occured while trying to complete the registration",
-                    None
-                ));
+            return error_response(e, "an error occured while trying to complete the registration");
         }
         Ok(_) => {
             return HttpResponse::Ok()
@@ -190,42 +404,665 @@ async fn auth_signin_get() -> impl Responder {
     return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
 }
 
+/// sign in with email and password
+#[utoipa::path(
+    post,
+    path = "/sign-in",
+    request_body = SignInRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 429, description = "too many failed attempts", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    )
+)]
 async fn auth_signin_post(
+    request: HttpRequest,
+    cfg: web::Data<ApplicationConfiguration>,
     auth: web::Data<Auth>,
     params: web::Json<SignInRequest>
 ) -> impl Responder {
     info!("auth_signin_post()");
 
+    let ip = client_ip(&request, &cfg.trusted_proxies);
     match auth.user_authenticate(
         &params.email,
-        &params.password
+        &params.password,
+        ip.as_deref()
     ).await {
         Err(e) => {
-            if matches!(e, AuthError::IncorrectUsernameAndPassword) {
+            match e {
+                AuthError::IncorrectUsernameAndPassword => {
+                    return Error::Unauthorized(String::from("incorrect username and password combination"))
+                        .error_response();
+                }
+                _ => {
+                    return auth_error_response(e, "unable to authenticate user");
+                }
+            }
+        }
+        Ok(result) => {
+            return sign_in_response(result);
+        }
+    }
+}
+
+/// response for a sign in that either completed or requires two-factor authentication
+fn sign_in_response(result: SignInResult) -> HttpResponse {
+    match result {
+        SignInResult::MfaRequired { mfa_token, enroll } => {
+            return HttpResponse::Ok()
+                .json(ApiResponse::new(
+                    true,
+                    "two-factor authentication required",
+                    Some(json!({
+                        "mfa_required": true,
+                        "mfa_token": mfa_token,
+                        "enroll": enroll
+                    }))
+                ));
+        }
+        SignInResult::Authenticated(tokens) => {
+            return HttpResponse::Ok()
+                .append_header((AUTHORIZATION, format!("Bearer {}", tokens.access_token)))
+                .json(ApiResponse::new(
+                    true,
+                    "user is authentic",
+                    Some(json!({
+                        "refresh_token": tokens.refresh_token
+                    }))
+                ));
+        }
+    }
+}
+
+/// response for errors common to the sign in and two-factor authentication endpoints
+fn auth_error_response(e: AuthError, message: &str) -> HttpResponse {
+    match e {
+        AuthError::InvalidMfaToken => {
+            return Error::Unauthorized(String::from("sign in has expired, please sign in again"))
+                .error_response();
+        }
+        AuthError::InvalidMfaCode => {
+            return Error::ValidationError(String::from("invalid code"))
+                .error_response();
+        }
+        AuthError::MfaAlreadyEnabled => {
+            return Error::Conflict(String::from("two-factor authentication is already enabled"))
+                .error_response();
+        }
+        AuthError::MfaNotEnabled => {
+            return Error::Conflict(String::from("two-factor authentication is not enabled"))
+                .error_response();
+        }
+        AuthError::MfaRequiredByTenant => {
+            return Error::Forbidden(String::from("two-factor authentication is required by a tenant"))
+                .error_response();
+        }
+        AuthError::AccountLocked(until) | AuthError::TooManyAttempts(until) => {
+            let retry_after = std::cmp::max((until - Utc::now()).num_seconds(), 1);
+            let e = Error::TooManyRequests(String::from("too many failed sign in attempts, please try again later"));
+            return HttpResponse::build(e.status_code())
+                .append_header((RETRY_AFTER, retry_after.to_string()))
+                .json(ApiResponse::error(&e).with_data(json!({
+                    "retry_after": retry_after
+                })));
+        }
+        _ => {
+            error!("{}: {:?}", message, e);
+            return error_response(e, message);
+        }
+    }
+}
+
+async fn mfa_verify_get() -> impl Responder {
+    info!("mfa_verify_get()");
+
+    return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
+}
+
+/// complete a sign in with a two-factor authentication code
+#[utoipa::path(
+    post,
+    path = "/mfa/verify",
+    request_body = MfaVerifyRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 429, description = "too many failed attempts", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    )
+)]
+async fn mfa_verify_post(
+    request: HttpRequest,
+    cfg: web::Data<ApplicationConfiguration>,
+    auth: web::Data<Auth>,
+    params: web::Json<MfaVerifyRequest>
+) -> impl Responder {
+    info!("mfa_verify_post()");
+
+    let ip = client_ip(&request, &cfg.trusted_proxies);
+    match auth.mfa_verify(
+        &params.mfa_token,
+        &params.code,
+        ip.as_deref()
+    ).await {
+        Err(e) => {
+            return auth_error_response(e, "unable to verify code");
+        }
+        Ok(tokens) => {
+            return HttpResponse::Ok()
+                .append_header((AUTHORIZATION, format!("Bearer {}", tokens.access_token)))
+                .json(ApiResponse::new(
+                    true,
+                    "user is authentic",
+                    Some(json!({
+                        "refresh_token": tokens.refresh_token
+                    }))
+                ));
+        }
+    }
+}
+
+async fn mfa_enroll_get() -> impl Responder {
+    info!("mfa_enroll_get()");
+
+    return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
+}
+
+/// start enrollment for the signed in user, or for a user signing in to a
+/// tenant that requires two-factor authentication using the mfa token
+#[utoipa::path(
+    post,
+    path = "/mfa/enroll",
+    request_body = MfaEnrollRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse)
+    )
+)]
+async fn mfa_enroll_post(
+    auth: web::Data<Auth>,
+    user: CurrentUser,
+    params: web::Json<MfaEnrollRequest>
+) -> impl Responder {
+    info!("mfa_enroll_post()");
+
+    let result = if user.is_authenticated() {
+        auth.mfa_enroll(&user.email()).await
+    } else if let Some(mfa_token) = &params.mfa_token {
+        auth.mfa_enroll_pending(&mfa_token).await
+    } else {
+        Err(AuthError::InvalidMfaToken)
+    };
+
+    match result {
+        Err(e) => {
+            return auth_error_response(e, "unable to enroll two-factor authentication");
+        }
+        Ok(enrollment) => {
+            return HttpResponse::Ok()
+                .json(ApiResponse::new(
+                    true,
+                    "scan the code with an authenticator app and confirm with the generated code",
+                    Some(json!({
+                        "secret": enrollment.secret,
+                        "uri": enrollment.uri
+                    }))
+                ));
+        }
+    }
+}
+
+async fn mfa_confirm_get() -> impl Responder {
+    info!("mfa_confirm_get()");
+
+    return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
+}
+
+/// confirm enrollment. when confirming with an mfa token the pending sign in
+/// is completed as well
+#[utoipa::path(
+    post,
+    path = "/mfa/confirm",
+    request_body = MfaConfirmRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse)
+    )
+)]
+async fn mfa_confirm_post(
+    request: HttpRequest,
+    cfg: web::Data<ApplicationConfiguration>,
+    auth: web::Data<Auth>,
+    user: CurrentUser,
+    params: web::Json<MfaConfirmRequest>
+) -> impl Responder {
+    info!("mfa_confirm_post()");
+
+    if user.is_authenticated() {
+        match auth.mfa_confirm(&user.email(), &params.code).await {
+            Err(e) => {
+                return auth_error_response(e, "unable to confirm two-factor authentication");
+            }
+            Ok(recovery_codes) => {
                 return HttpResponse::Ok()
                     .json(ApiResponse::new(
-                        false,
-                        "incorrect username and password combination",
-                        None
-                    ));
-            } else {
-                error!("unable to authenticate user: {:?}", e);
-                return HttpResponse::InternalServerError()
-                    .json(ApiResponse::new(
-                        false,
-                        "unable to authenticate user",
-                        None
+                        true,
+                        "two-factor authentication enabled",
+                        Some(json!({
+                            "recovery_codes": recovery_codes
+                        }))
                     ));
             }
         }
-        Ok(token) => {
+    }
+
+    let mfa_token = match &params.mfa_token {
+        None => {
+            return auth_error_response(AuthError::InvalidMfaToken, "");
+        }
+        Some(mfa_token) => mfa_token
+    };
+    let ip = client_ip(&request, &cfg.trusted_proxies);
+    match auth.mfa_confirm_pending(&mfa_token, &params.code, ip.as_deref()).await {
+        Err(e) => {
+            return auth_error_response(e, "unable to confirm two-factor authentication");
+        }
+        Ok((recovery_codes, tokens)) => {
             return HttpResponse::Ok()
-                .append_header((AUTHORIZATION, format!("Bearer {}", token)))
+                .append_header((AUTHORIZATION, format!("Bearer {}", tokens.access_token)))
                 .json(ApiResponse::new(
                     true,
-                    "user is authentic",
+                    "two-factor authentication enabled",
+                    Some(json!({
+                        "recovery_codes": recovery_codes,
+                        "refresh_token": tokens.refresh_token
+                    }))
+                ));
+        }
+    }
+}
+
+async fn mfa_disable_get() -> impl Responder {
+    info!("mfa_disable_get()");
+
+    return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
+}
+
+/// disable two-factor authentication
+#[utoipa::path(
+    post,
+    path = "/mfa/disable",
+    request_body = MfaDisableRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse)
+    ),
+    security(("bearer" = []))
+)]
+async fn mfa_disable_post(
+    auth: web::Data<Auth>,
+    user: CurrentUser,
+    params: web::Json<MfaDisableRequest>
+) -> impl Responder {
+    info!("mfa_disable_post()");
+
+    match auth.mfa_disable(&user.email(), &params.code).await {
+        Err(e) => {
+            return auth_error_response(e, "unable to disable two-factor authentication");
+        }
+        Ok(_) => {
+            return HttpResponse::Ok()
+                .json(ApiResponse::new(
+                    true,
+                    "two-factor authentication disabled",
                     None
+                ));
+        }
+    }
+}
+
+async fn account_unlock_get() -> impl Responder {
+    info!("account_unlock_get()");
+
+    return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
+}
+
+/// unlock an account with an unlock token
+#[utoipa::path(
+    post,
+    path = "/unlock",
+    request_body = AccountUnlockRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 422, description = "request is not valid", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    )
+)]
+async fn account_unlock_post(
+    auth: web::Data<Auth>,
+    params: web::Json<AccountUnlockRequest>
+) -> impl Responder {
+    info!("account_unlock_post()");
+
+    match auth.account_unlock(&params.token).await {
+        Err(AuthError::InvalidUnlockToken) => {
+            return Error::ValidationError(String::from("unlock link is invalid or has expired"))
+                .error_response();
+        }
+        Err(e) => {
+            error!("unable to unlock account: {:?}", e);
+            return error_response(e, "an error occured while trying to unlock the account");
+        }
+        Ok(_) => {
+            return HttpResponse::Ok()
+                .json(ApiResponse::new(
+                    true,
+                    "account has been unlocked",
+                    None
+                ));
+        }
+    }
+}
+
+async fn password_forgot_get() -> impl Responder {
+    info!("password_forgot_get()");
+
+    return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
+}
+
+/// email a password reset link
+#[utoipa::path(
+    post,
+    path = "/password/forgot",
+    request_body = PasswordForgotRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 422, description = "request is not valid", body = ApiResponse)
+    )
+)]
+async fn password_forgot_post(
+    auth: web::Data<Auth>,
+    params: Validated<PasswordForgotRequest>
+) -> impl Responder {
+    info!("password_forgot_post()");
+
+    // the response is the same whether or not the email exists
+    if let Err(e) = auth.password_forgot(&params.email).await {
+        error!("unable to send password reset email: {:?}", e);
+    }
+    return HttpResponse::Ok()
+        .json(ApiResponse::new(
+            true,
+            "if the email is registered, a link to reset the password has been sent",
+            None
+        ));
+}
+
+async fn password_reset_get() -> impl Responder {
+    info!("password_reset_get()");
+
+    return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
+}
+
+/// reset the password with a password reset token
+#[utoipa::path(
+    post,
+    path = "/password/reset",
+    request_body = PasswordResetRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 422, description = "request is not valid", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    )
+)]
+async fn password_reset_post(
+    auth: web::Data<Auth>,
+    params: web::Json<PasswordResetRequest>
+) -> impl Responder {
+    info!("password_reset_post()");
+
+    match auth.password_reset(&params.token, &params.password).await {
+        Err(e) => {
+            match e {
+                AuthError::PasswordPolicy(failed) => {
+                    return Error::ValidationError(String::from("password does not meet the requirements"))
+                        .error_response_with(json!({
+                            "failed": failed
+                        }));
+                }
+                AuthError::InvalidPasswordResetToken => {
+                    return Error::ValidationError(String::from("password reset link is invalid or has expired"))
+                        .error_response();
+                }
+                _ => {
+                    error!("unable to reset password: {:?}", e);
+                    return error_response(e, "an error occured while trying to reset the password");
+                }
+            }
+        }
+        Ok(_) => {
+            return HttpResponse::Ok()
+                .json(ApiResponse::new(
+                    true,
+                    "password has been reset",
+                    None
+                ));
+        }
+    }
+}
+
+async fn oidc_authorize_get() -> impl Responder {
+    info!("oidc_authorize_get()");
+
+    return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
+}
+
+/// start a sign in with an identity provider
+#[utoipa::path(
+    post,
+    path = "/oidc/authorize",
+    request_body = OidcAuthorizeRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 404, description = "not found", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    )
+)]
+async fn oidc_authorize_post(
+    auth: web::Data<Auth>,
+    params: web::Json<OidcAuthorizeRequest>
+) -> impl Responder {
+    info!("oidc_authorize_post()");
+
+    match auth.oidc_authorize(&params.provider).await {
+        Err(AuthError::OidcProviderNotFound) => {
+            return Error::NotFound(String::from("identity provider not found"))
+                .error_response();
+        }
+        Err(AuthError::OidcFailed) => {
+            return Error::Unavailable(String::from("identity provider is unavailable"))
+                .error_response();
+        }
+        Err(e) => {
+            error!("unable to start openid connect sign in: {:?}", e);
+            return error_response(e, "unable to start sign in");
+        }
+        Ok(url) => {
+            return HttpResponse::Ok()
+                .json(ApiResponse::new(
+                    true,
+                    "redirect to identity provider",
+                    Some(json!({
+                        "url": url
+                    }))
+                ));
+        }
+    }
+}
+
+async fn oidc_callback_get() -> impl Responder {
+    info!("oidc_callback_get()");
+
+    return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
+}
+
+/// complete a sign in with an identity provider
+#[utoipa::path(
+    post,
+    path = "/oidc/callback",
+    request_body = OidcCallbackRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 422, description = "request is not valid", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    )
+)]
+async fn oidc_callback_post(
+    request: HttpRequest,
+    cfg: web::Data<ApplicationConfiguration>,
+    auth: web::Data<Auth>,
+    params: web::Json<OidcCallbackRequest>
+) -> impl Responder {
+    info!("oidc_callback_post()");
+
+    let ip = client_ip(&request, &cfg.trusted_proxies);
+    match auth.oidc_callback(&params.state, &params.code, ip.as_deref()).await {
+        Err(AuthError::InvalidOidcState) | Err(AuthError::OidcProviderNotFound) => {
+            return Error::ValidationError(String::from("sign in has expired, please sign in again"))
+                .error_response();
+        }
+        Err(AuthError::OidcFailed) => {
+            return Error::Unauthorized(String::from("identity provider sign in failed"))
+                .error_response();
+        }
+        Err(AuthError::OidcEmailNotVerified) => {
+            return Error::Forbidden(String::from("identity provider has not verified the email address"))
+                .error_response();
+        }
+        Err(AuthError::OidcAccountNotLinked) => {
+            return Error::Forbidden(String::from("an account with this email address already exists, sign in with its password"))
+                .error_response();
+        }
+        Err(e) => {
+            return auth_error_response(e, "unable to complete sign in");
+        }
+        Ok(result) => {
+            return sign_in_response(result);
+        }
+    }
+}
+
+async fn token_refresh_get() -> impl Responder {
+    info!("token_refresh_get()");
+
+    return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
+}
+
+/// exchange a refresh token for new tokens
+#[utoipa::path(
+    post,
+    path = "/token/refresh",
+    request_body = TokenRefreshRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    )
+)]
+async fn token_refresh_post(
+    auth: web::Data<Auth>,
+    params: web::Json<TokenRefreshRequest>
+) -> impl Responder {
+    info!("token_refresh_post()");
+
+    match auth.token_refresh(&params.refresh_token).await {
+        Err(e) => {
+            match e {
+                AuthError::InvalidRefreshToken | AuthError::RefreshTokenReused => {
+                    return Error::Unauthorized(String::from("refresh token is invalid or has expired"))
+                        .error_response();
+                }
+                _ => {
+                    error!("unable to refresh token: {:?}", e);
+                    return error_response(e, "unable to refresh token");
+                }
+            }
+        }
+        Ok(tokens) => {
+            return HttpResponse::Ok()
+                .append_header((AUTHORIZATION, format!("Bearer {}", tokens.access_token)))
+                .json(ApiResponse::new(
+                    true,
+                    "token refreshed",
+                    Some(json!({
+                        "refresh_token": tokens.refresh_token
+                    }))
                 ))
         }
     }
 }
+
+async fn auth_signout_get() -> impl Responder {
+    info!("auth_signout_get()");
+
+    return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
+}
+
+/// sign out and revoke the session
+#[utoipa::path(
+    post,
+    path = "/signout",
+    request_body = SignOutRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = []))
+)]
+async fn auth_signout_post(
+    auth: web::Data<Auth>,
+    user: CurrentUser,
+    params: web::Json<SignOutRequest>
+) -> impl Responder {
+    info!("auth_signout_post()");
+
+    match auth.sign_out(
+        &user.id(),
+        &user.session_id(),
+        params.everywhere
+    ).await {
+        Err(e) => {
+            error!("unable to sign out: {:?}", e);
+            return error_response(e, "an error occured while trying to sign out");
+        }
+        Ok(_) => {
+            return HttpResponse::Ok()
+                .json(ApiResponse::new(
+                    true,
+                    "successfully signed out",
+                    None
+                ));
+        }
+    }
+}
+
+
+/// public keys used to verify access tokens
+#[utoipa::path(
+    get,
+    path = "/.well-known/jwks.json",
+    responses(
+        (status = 200, description = "json web key set", body = Object)
+    )
+)]
+async fn jwks_get(
+    tokenizer: web::Data<Tokenizer>
+) -> impl Responder {
+    info!("jwks_get()");
+    return HttpResponse::Ok().json(tokenizer.jwks());
+}
@@ -56,7 +56,7 @@ pub fn config(cfg: &mut web::ServiceConfig) {
                 .route(web::method(http::Method::OPTIONS).to(default_options))
                 .route(web::get().to(client_get_get))
                 .route(web::post()
-                    // .guard(Permission::new("permission.test"))
+                    // .guard(Permission::new("clients.read"))
                     // .guard(Authenticated::new())
                     .to(client_get_post)
                 )
@@ -67,7 +67,7 @@ pub fn config(cfg: &mut web::ServiceConfig) {
                 .route(web::method(http::Method::OPTIONS).to(default_options))
                 .route(web::get().to(client_active_get))
                 .route(web::post()
-                    // .guard(Permission::new("permission.test"))
+                    // .guard(Permission::new("clients.set_active"))
                     .guard(Authenticated::new())
                     .to(client_active_post)
                 )
@@ -78,7 +78,7 @@ pub fn config(cfg: &mut web::ServiceConfig) {
                 .route(web::method(http::Method::OPTIONS).to(default_options))
                 .route(web::get().to(client_add_get))
                 .route(web::post()
-                    // .guard(Permission::new("permission.test"))
+                    // .guard(Permission::new("clients.add"))
                     .guard(Authenticated::new())
                     .to(client_add_post)
                 )
@@ -89,7 +89,7 @@ pub fn config(cfg: &mut web::ServiceConfig) {
                 .route(web::method(http::Method::OPTIONS).to(default_options))
                 .route(web::get().to(client_users_get))
                 .route(web::post()
-                    // .guard(Permission::new("permission.test"))
+                    // .guard(Permission::new("clients.members.read"))
                     .guard(Authenticated::new())
                     .to(client_users_post)
                 )
@@ -100,7 +100,7 @@ pub fn config(cfg: &mut web::ServiceConfig) {
                 .route(web::method(http::Method::OPTIONS).to(default_options))
                 .route(web::get().to(client_clients_get))
                 .route(web::post()
-                    // .guard(Permission::new("permission.test"))
+                    // .guard(Permission::new("clients.read"))
                     .guard(Authenticated::new())
                     .to(client_clients_post)
                 )
@@ -10,19 +10,32 @@ use actix_web::{
     web
 };
 use serde_json::json;
+use utoipa::OpenApi;
 
 use crate::endpoints::{
-    ApiResponse
+    ApiResponse,
+    error_response
 };
+use crate::classes::routes::{
+    self,
+    Routes
+};
+
+
 
+#[derive(OpenApi)]
+#[openapi(paths(
+    countries_post
+))]
+pub struct ApiDoc;
 
 
-pub fn config(cfg: &mut web::ServiceConfig) {
+pub fn config(cfg: &mut Routes) {
     cfg
         .service(
-            web::resource("")
-                .route(web::get().to(countries_get))
-                .route(web::post().to(countries_post))
+            routes::resource("")
+                .route(routes::placeholder().to(countries_get))
+                .route(routes::post().to(countries_post))
         )
     ;
 }
@@ -35,6 +48,15 @@ async fn countries_get() -> impl Responder {
 }
 
 
+/// list countries
+#[utoipa::path(
+    post,
+    path = "",
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    )
+)]
 async fn countries_post(
     countries: web::Data<util::countries::Countries>
 ) -> impl Responder {
@@ -43,12 +65,7 @@ async fn countries_post(
     match countries.countries().await {
         Err(e) => {
             error!("unable to fetch countries: {:?}", e);
-            return HttpResponse::InternalServerError()
-                .json(ApiResponse::new(
-                    false,
-                    "an error occured while trying to fetch countries",
-                    None
-                ));
+            return error_response(e, "an error occured while trying to fetch countries");
         }
         Ok(countries) => {
             return HttpResponse::Ok()
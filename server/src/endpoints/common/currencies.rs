@@ -10,19 +10,32 @@ use actix_web::{
     web
 };
 use serde_json::json;
+use utoipa::OpenApi;
 
 use crate::endpoints::{
-    ApiResponse
+    ApiResponse,
+    error_response
 };
+use crate::classes::routes::{
+    self,
+    Routes
+};
+
+
 
+#[derive(OpenApi)]
+#[openapi(paths(
+    currencies_post
+))]
+pub struct ApiDoc;
 
 
-pub fn config(cfg: &mut web::ServiceConfig) {
+pub fn config(cfg: &mut Routes) {
     cfg
         .service(
-            web::resource("")
-                .route(web::get().to(currencies_get))
-                .route(web::post().to(currencies_post))
+            routes::resource("")
+                .route(routes::placeholder().to(currencies_get))
+                .route(routes::post().to(currencies_post))
         )
     ;
 }
@@ -35,6 +48,15 @@ async fn currencies_get() -> impl Responder {
 }
 
 
+/// list currencies
+#[utoipa::path(
+    post,
+    path = "",
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    )
+)]
 async fn currencies_post(
     currencies: web::Data<util::currencies::Currencies>
 ) -> impl Responder {
@@ -43,12 +65,7 @@ async fn currencies_post(
     match currencies.currencies().await {
         Err(e) => {
             error!("unable to fetch currencies: {:?}", e);
-            return HttpResponse::InternalServerError()
-                .json(ApiResponse::new(
-                    false,
-                    "an error occured while trying to fetch currencies",
-                    None
-                ));
+            return error_response(e, "an error occured while trying to fetch currencies");
         }
         Ok(currencies) => {
             return HttpResponse::Ok()
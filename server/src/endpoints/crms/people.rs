@@ -17,17 +17,29 @@ use serde::{
 use serde_json::json;
 
 use crate::endpoints::{
-    ApiResponse
+    ApiResponse,
+    default_options,
+    default_service
+};
+use crate::classes::routes::{
+    self,
+    Routes
 };
 use crate::classes::user::CurrentUser;
+use crate::classes::guards::permission::Permission;
 
 
-pub fn config(cfg: &mut web::ServiceConfig) {
+pub fn config(cfg: &mut Routes) {
     cfg
         .service(
-            web::resource("add")
-                .route(web::get().to(people_add_get))
-                .route(web::post().to(people_add_post))
+            routes::resource("add")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::placeholder().to(people_add_get))
+                .route(routes::post()
+                    .guard(Permission::new("crms.people.add"))
+                    .to(people_add_post)
+                )
+                .default_service(web::to(default_service))
         )
     ;
 }
@@ -48,9 +60,9 @@ async fn people_add_post(
     // debug!("params: {:?}", params);
 
     return HttpResponse::Ok()
-        .json(ApiResponse {
-            success: false,
-            message: String::from("Service is up. version: 1.0.0.0.dev"),
-            data: None
-        });
+        .json(ApiResponse::new(
+            false,
+            "Service is up. version: 1.0.0.0.dev",
+            None
+        ));
 }
\ No newline at end of file
@@ -15,47 +15,89 @@ use serde::{
     Deserialize
 };
 use serde_json::json;
+use validator::Validate;
+use utoipa::{
+    OpenApi,
+    ToSchema
+};
 
 use crate::endpoints::{
-    ApiResponse
+    ApiResponse,
+    error_response,
+    default_options,
+    default_service,
+    tenant_not_current
+};
+use crate::classes::routes::{
+    self,
+    Routes
 };
+use ::common::hr::people::People;
+
 use crate::classes::user::CurrentUser;
+use crate::classes::extractors::validated::Validated;
+use crate::classes::validators;
+use crate::classes::guards::permission::Permission;
 
 
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
 struct EmployeeAddRequest {
-    pub tenant_id: uuid::Uuid,
+    #[schema(value_type = String, format = "uuid")]
     pub people_id: uuid::Uuid,
+    #[validate(custom(function = "validators::not_blank"), length(max = 100, message = "must be at most 100 characters"))]
     pub given_name: String,
     pub middle_name: String,
+    #[validate(custom(function = "validators::not_blank"), length(max = 100, message = "must be at most 100 characters"))]
     pub family_name: String,
     pub prefix: String,
     pub suffix: String,
+    #[validate(range(min = 1, message = "must be a valid id"))]
     pub gender_id: i16,
+    #[validate(range(min = 1, message = "must be a valid id"))]
     pub ethnicity_id: i16,
+    #[validate(range(min = 1, message = "must be a valid id"))]
     pub marital_state_id: i16
 }
 
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 struct TenantEmployeesRequest {
+    #[schema(value_type = String, format = "uuid")]
     pub tenant_id: uuid::Uuid
 }
 
 
 
-pub fn config(cfg: &mut web::ServiceConfig) {
+#[derive(OpenApi)]
+#[openapi(paths(
+    employee_add_post,
+    employee_fetch_post
+))]
+pub struct ApiDoc;
+
+
+pub fn config(cfg: &mut Routes) {
     cfg
         .service(
-            web::resource("add")
-                .route(web::get().to(employee_add_get))
-                .route(web::post().to(employee_add_post))
+            routes::resource("add")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::placeholder().to(employee_add_get))
+                .route(routes::post()
+                    .guard(Permission::new("hr.employees.add"))
+                    .to(employee_add_post)
+                )
+                .default_service(web::to(default_service))
         )
         .service(
-            web::resource("fetch")
-                .route(web::get().to(employee_fetch_get))
-                .route(web::post().to(employee_fetch_post))
+            routes::resource("fetch")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::placeholder().to(employee_fetch_get))
+                .route(routes::post()
+                    .guard(Permission::new("hr.employees.read"))
+                    .to(employee_fetch_post)
+                )
+                .default_service(web::to(default_service))
         )
     ;
 }
@@ -68,35 +110,44 @@ async fn employee_add_get() -> impl Responder {
 }
 
 
+/// add an employee
+#[utoipa::path(
+    post,
+    path = "/add",
+    request_body = EmployeeAddRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 422, description = "request is not valid", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = ["hr.employees.add"]), ("api_key" = ["hr.employees.add"]))
+)]
 async fn employee_add_post(
     user: CurrentUser,
     people: web::Data<people::people::People>,
     employees: web::Data<hr::employees::Employees>,
-    params: web::Json<EmployeeAddRequest>
+    params: Validated<EmployeeAddRequest>
 ) -> impl Responder {
     info!("employee_add_post()");
     debug!("params: {:?}", params);
 
     if let Ok(p) = people.by_id(&params.people_id).await {
-        if let Ok(_) = employees.add(
+        if let Err(e) = employees.add(
             &user.tenant_id(), 
             &params.people_id,
             &params.people_id
         ).await {
-            info!("added employee record");
-        } else {
-            return HttpResponse::Ok()
-                .json(ApiResponse::new(
-                    false,
-                    "unable to add employee record",
-                    None
-                ));
+            error!("unable to add employee record: {:?}", e);
+            return error_response(e, "unable to add employee record");
         }
+        info!("added employee record");
     } else {
-        // add people record
-        if let Ok(_) =  people.add(
-            &user.tenant_id(),
+        // add people record together with the employee record
+        let record = People::new(
             &params.people_id,
+            &true,
             &params.given_name,
             &params.middle_name,
             &params.family_name,
@@ -105,22 +156,16 @@ async fn employee_add_post(
             &params.gender_id,
             &params.ethnicity_id,
             &params.marital_state_id
+        );
+        if let Err(e) = employees.add_with_people(
+            &user.tenant_id(),
+            &params.people_id,
+            &record
         ).await {
-            if let Ok(_) = employees.add(
-                &user.tenant_id(), 
-                &params.people_id,
-                &params.people_id
-            ).await {
-                info!("added employee record");
-            } else {
-                return HttpResponse::Ok()
-                    .json(ApiResponse::new(
-                        false,
-                        "unable to add employee record",
-                        None
-                    ));
-            }
+            error!("unable to add employee record: {:?}", e);
+            return error_response(e, "unable to add employee record");
         }
+        info!("added employee record");
     }
 
     return HttpResponse::Ok()
@@ -139,6 +184,19 @@ async fn employee_fetch_get() -> impl Responder {
 }
 
 
+/// list employees
+#[utoipa::path(
+    post,
+    path = "/fetch",
+    request_body = TenantEmployeesRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = ["hr.employees.read"]), ("api_key" = ["hr.employees.read"]))
+)]
 async fn employee_fetch_post(
     user: CurrentUser,
     employees: web::Data<hr::employees::Employees>,
@@ -147,15 +205,14 @@ async fn employee_fetch_post(
     info!("employee_fetch_post()");
     debug!("params: {:?}", params);
 
-    match employees.fetch(&params.tenant_id).await {
+    if params.tenant_id != user.tenant_id() {
+        return tenant_not_current();
+    }
+
+    match employees.fetch(&user.tenant_id()).await {
         Err(e) => {
             error!("unable to fetch employee records: {:?}", e);
-            return HttpResponse::InternalServerError()
-                .json(ApiResponse::new(
-                    false,
-                    "unable to fetch employee records",
-                    None
-                ));
+            return error_response(e, "unable to fetch employee records");
         }
         Ok(employees) => {
             return HttpResponse::Ok()
@@ -0,0 +1,36 @@
+use log::{
+    info
+};
+
+use actix_web::{
+    HttpResponse, 
+    Responder,
+    web
+};
+use crate::classes::routes::{
+    self,
+    Routes
+};
+
+
+pub fn config(cfg: &mut Routes) {
+    cfg
+        .service(
+            routes::resource("")
+                .route(routes::get().to(metrics_get))
+        )
+    ;
+}
+
+/// metrics in the prometheus text exposition format
+async fn metrics_get(
+    data: web::Data<data::Data>
+) -> impl Responder {
+    info!("metrics_get()");
+
+    // pool gauges are sampled when scraped
+    data.observe_pools();
+    return HttpResponse::Ok()
+        .content_type(::metrics::content_type())
+        .body(::metrics::render());
+}
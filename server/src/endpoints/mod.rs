@@ -1,13 +1,16 @@
 pub mod status;
+pub mod metrics;
 pub mod auth;
 pub mod user;
 pub mod common;
 // pub mod clients;
 pub mod tenants;
+pub mod roles;
 pub mod accounting;
 pub mod crms;
 pub mod hr;
 pub mod inventory;
+pub mod v1;
 
 
 use log::{
@@ -17,42 +20,62 @@ use log::{
 
 
 
-use serde::{
-    Serialize,
-    Deserialize
-};
-use serde_json::Value;
-
 use actix_web::{
     HttpResponse, 
-    Responder
+    Responder,
+    ResponseError,
+    web
+};
+
+use ::common::{
+    Error,
+    ErrorCode
 };
 
 // use crate::classes::extractors::user::User;
 use crate::classes::user::CurrentUser;
+use crate::classes::routes::{
+    self,
+    Routes
+};
 
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ApiResponse {
-    success: bool,
-    message: String,
-    data: Option<Value>
+pub use ::common::response::ApiResponse;
+
+
+/// registers the routes of a scope
+pub type Config = fn(&mut Routes);
+
+/// scopes served by the api and the config function registering the
+/// routes of each. the openapi document nests the same paths. metrics
+/// are served by a listener of their own
+pub const SCOPES: [(&str, Config); 12] = [
+    ("/status", status::config),
+    ("/countries", common::countries::config),
+    ("/currencies", common::currencies::config),
+    ("/auth", auth::config),
+    ("/user", user::config),
+    // ("/clients", clients::client::config),
+    ("/tenants", tenants::tenants::config),
+    ("/organizations", tenants::organizations::config),
+    ("/api-keys", tenants::api_keys::config),
+    ("/roles", roles::config),
+    ("/accounting/accounts", accounting::accounts::config),
+    // ("/crms/people", crms::people::config),
+    ("/hr/employees", hr::employees::config),
+    ("/api/v1", v1::config)
+];
+
+/// register the scopes of the api
+pub fn config(cfg: &mut web::ServiceConfig) {
+    for (prefix, config) in SCOPES {
+        cfg.service(routes::scope(prefix, config));
+    }
 }
 
-
-impl ApiResponse {
-
-    pub fn new(
-        success: bool,
-        message: &str,
-        data: Option<Value>
-    ) -> Self {
-        return Self {
-            success: success,
-            message: message.to_string(),
-            data: data
-        };
-    }
+/// register the scope of the metrics listener
+pub fn metrics_config(cfg: &mut web::ServiceConfig) {
+    cfg.service(routes::scope("/metrics", metrics::config));
 }
 
 
@@ -62,25 +85,36 @@ pub async fn default_options() -> impl Responder {
         .finish();
 }
 
+/// response for requests naming a tenant other than the current tenant.
+/// permissions are granted for the current tenant only
+pub fn tenant_not_current() -> HttpResponse {
+    return Error::Forbidden(String::from("tenant is not the current tenant"))
+        .error_response();
+}
+
+/// response for a request that failed with a module error. internal errors
+/// are reported with the message describing the request, other errors with
+/// their own message
+pub fn error_response(e: impl Into<Error>, message: &str) -> HttpResponse {
+    let e: Error = e.into();
+    if e.code() == ErrorCode::Internal {
+        return Error::Internal(String::from(message))
+            .error_response();
+    }
+    return e.error_response();
+}
+
 pub async fn default_service(
     user: CurrentUser
 ) -> impl Responder {
     info!("endpoints::default_service()");
     // debug!("user: {:?}", user);
 
-    if user.is_authenticated() {
-        return HttpResponse::Forbidden()
-            .json(ApiResponse::new(
-                false,
-                "user does not have permissions",
-                None
-            ));
+    if user.is_authenticated() || user.is_api_key() {
+        return Error::Forbidden(String::from("user does not have permissions"))
+            .error_response();
     } else {
-        return HttpResponse::Unauthorized()
-            .json(ApiResponse::new(
-                false,
-                "user is not authenticated",
-                None
-            ));
+        return Error::Unauthorized(String::from("user is not authenticated"))
+            .error_response();
     }
 }
\ No newline at end of file
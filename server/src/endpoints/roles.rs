@@ -0,0 +1,857 @@
+use log::{
+    info,
+    debug,
+    error
+};
+
+use actix_web::{
+    HttpResponse,
+    Responder,
+    web
+};
+use serde::{
+    Serialize,
+    Deserialize
+};
+use serde_json::json;
+use validator::Validate;
+use utoipa::{
+    OpenApi,
+    ToSchema
+};
+
+use crate::endpoints::{
+    ApiResponse,
+    error_response,
+    default_options,
+    default_service
+};
+use crate::classes::routes::{
+    self,
+    Routes
+};
+use crate::classes::user::CurrentUser;
+use crate::classes::extractors::validated::Validated;
+use crate::classes::validators;
+use crate::classes::guards::permission::Permission;
+
+use roles::roles::{
+    Roles,
+    RolesError
+};
+use common::Error;
+
+
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+struct RoleAddRequest {
+    #[validate(custom(function = "validators::not_blank"), length(max = 100, message = "must be at most 100 characters"))]
+    pub name: String,
+    #[serde(default)]
+    #[validate(length(max = 1000, message = "must be at most 1000 characters"))]
+    pub description: String
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+struct RoleRequest {
+    #[schema(value_type = String, format = "uuid")]
+    pub role_id: uuid::Uuid
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+struct RoleUpdateRequest {
+    #[schema(value_type = String, format = "uuid")]
+    pub role_id: uuid::Uuid,
+    #[validate(custom(function = "validators::not_blank"), length(max = 100, message = "must be at most 100 characters"))]
+    pub name: String,
+    #[serde(default)]
+    #[validate(length(max = 1000, message = "must be at most 1000 characters"))]
+    pub description: String
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+struct RoleSetActiveRequest {
+    #[schema(value_type = String, format = "uuid")]
+    pub role_id: uuid::Uuid,
+    pub active: bool
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+struct RolePermissionsSetRequest {
+    #[schema(value_type = String, format = "uuid")]
+    pub role_id: uuid::Uuid,
+    pub permissions: Vec<String>
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+struct UserRoleRequest {
+    #[schema(value_type = String, format = "uuid")]
+    pub user_id: uuid::Uuid,
+    #[schema(value_type = String, format = "uuid")]
+    pub role_id: uuid::Uuid
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+struct UserRolesRequest {
+    #[schema(value_type = String, format = "uuid")]
+    pub user_id: uuid::Uuid
+}
+
+
+/// roles are managed within the current tenant of the user. permissions can
+/// only be delegated by users who hold them
+#[derive(OpenApi)]
+#[openapi(paths(
+    role_add_post,
+    roles_fetch_post,
+    role_get_post,
+    role_update_post,
+    role_set_active_post,
+    role_remove_post,
+    permissions_fetch_post,
+    role_permissions_fetch_post,
+    role_permissions_set_post,
+    user_role_add_post,
+    user_role_remove_post,
+    user_roles_fetch_post
+))]
+pub struct ApiDoc;
+
+
+pub fn config(cfg: &mut Routes) {
+    cfg
+        .service(
+            routes::resource("/add")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::placeholder().to(role_add_get))
+                .route(routes::post()
+                    .guard(Permission::new("roles.add"))
+                    .to(role_add_post)
+                )
+                .default_service(web::to(default_service))
+        )
+        .service(
+            routes::resource("/fetch")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::placeholder().to(roles_fetch_get))
+                .route(routes::post()
+                    .guard(Permission::new("roles.read"))
+                    .to(roles_fetch_post)
+                )
+                .default_service(web::to(default_service))
+        )
+        .service(
+            routes::resource("/get")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::placeholder().to(role_get_get))
+                .route(routes::post()
+                    .guard(Permission::new("roles.read"))
+                    .to(role_get_post)
+                )
+                .default_service(web::to(default_service))
+        )
+        .service(
+            routes::resource("/update")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::placeholder().to(role_update_get))
+                .route(routes::post()
+                    .guard(Permission::new("roles.update"))
+                    .to(role_update_post)
+                )
+                .default_service(web::to(default_service))
+        )
+        .service(
+            routes::resource("/set/active")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::placeholder().to(role_set_active_get))
+                .route(routes::post()
+                    .guard(Permission::new("roles.update"))
+                    .to(role_set_active_post)
+                )
+                .default_service(web::to(default_service))
+        )
+        .service(
+            routes::resource("/remove")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::placeholder().to(role_remove_get))
+                .route(routes::post()
+                    .guard(Permission::new("roles.remove"))
+                    .to(role_remove_post)
+                )
+                .default_service(web::to(default_service))
+        )
+        .service(
+            routes::resource("/permissions")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::placeholder().to(permissions_fetch_get))
+                .route(routes::post()
+                    .guard(Permission::new("roles.read"))
+                    .to(permissions_fetch_post)
+                )
+                .default_service(web::to(default_service))
+        )
+        .service(
+            routes::resource("/permissions/fetch")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::placeholder().to(role_permissions_fetch_get))
+                .route(routes::post()
+                    .guard(Permission::new("roles.read"))
+                    .to(role_permissions_fetch_post)
+                )
+                .default_service(web::to(default_service))
+        )
+        .service(
+            routes::resource("/permissions/set")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::placeholder().to(role_permissions_set_get))
+                .route(routes::post()
+                    .guard(Permission::new("roles.update"))
+                    .to(role_permissions_set_post)
+                )
+                .default_service(web::to(default_service))
+        )
+        .service(
+            routes::resource("/users/add")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::placeholder().to(user_role_add_get))
+                .route(routes::post()
+                    .guard(Permission::new("roles.assign"))
+                    .to(user_role_add_post)
+                )
+                .default_service(web::to(default_service))
+        )
+        .service(
+            routes::resource("/users/remove")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::placeholder().to(user_role_remove_get))
+                .route(routes::post()
+                    .guard(Permission::new("roles.assign"))
+                    .to(user_role_remove_post)
+                )
+                .default_service(web::to(default_service))
+        )
+        .service(
+            routes::resource("/users/fetch")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::placeholder().to(user_roles_fetch_get))
+                .route(routes::post()
+                    .guard(Permission::new("roles.read"))
+                    .to(user_roles_fetch_post)
+                )
+                .default_service(web::to(default_service))
+        )
+    ;
+}
+
+
+/// response for errors common to the role endpoints
+pub(crate) fn roles_error_response(e: RolesError, message: &str) -> HttpResponse {
+    match e {
+        RolesError::UnknownPermissions(unknown) => {
+            return Error::ValidationError(String::from("unknown permissions"))
+                .error_response_with(json!({
+                    "unknown": unknown
+                }));
+        }
+        _ => {
+            error!("{}: {:?}", message, e);
+            return error_response(e, message);
+        }
+    }
+}
+
+/// response for attempts to delegate permissions the user does not hold
+pub(crate) fn delegation_denied(user: &CurrentUser, permissions: &[String]) -> Option<HttpResponse> {
+    let missing: Vec<&String> = permissions.iter()
+        .filter(|p| !user.has_permission(p))
+        .collect();
+    if missing.is_empty() {
+        return None;
+    }
+    return Some(Error::Forbidden(String::from("permissions can only be granted by users who hold them"))
+        .error_response_with(json!({
+            "missing": missing
+        })));
+}
+
+
+async fn role_add_get() -> impl Responder {
+    info!("role_add_get()");
+    return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
+}
+
+/// add a role
+#[utoipa::path(
+    post,
+    path = "/add",
+    request_body = RoleAddRequest,
+    responses(
+        (status = 201, description = "created", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 404, description = "not found", body = ApiResponse),
+        (status = 409, description = "conflict", body = ApiResponse),
+        (status = 422, description = "request is not valid", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = ["roles.add"]), ("api_key" = ["roles.add"]))
+)]
+async fn role_add_post(
+    user: CurrentUser,
+    roles: web::Data<Roles>,
+    params: Validated<RoleAddRequest>
+) -> impl Responder {
+    info!("role_add_post()");
+    debug!("params: {:?}", params);
+
+    match roles.role_add(&user.tenant_id(), &params.name, &params.description).await {
+        Err(e) => {
+            return roles_error_response(e, "an error occured while trying to add the role");
+        }
+        Ok(role) => {
+            return HttpResponse::Created()
+                .json(ApiResponse::new(
+                    true,
+                    "successfully added role",
+                    Some(json!({
+                        "role": role
+                    }))
+                ));
+        }
+    }
+}
+
+
+async fn roles_fetch_get() -> impl Responder {
+    info!("roles_fetch_get()");
+    return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
+}
+
+/// list roles of the current tenant
+#[utoipa::path(
+    post,
+    path = "/fetch",
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 404, description = "not found", body = ApiResponse),
+        (status = 409, description = "conflict", body = ApiResponse),
+        (status = 422, description = "request is not valid", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = ["roles.read"]), ("api_key" = ["roles.read"]))
+)]
+async fn roles_fetch_post(
+    user: CurrentUser,
+    roles: web::Data<Roles>
+) -> impl Responder {
+    info!("roles_fetch_post()");
+
+    match roles.roles_fetch(&user.tenant_id()).await {
+        Err(e) => {
+            return roles_error_response(e, "an error occured while trying to retrieve roles");
+        }
+        Ok(roles) => {
+            return HttpResponse::Ok()
+                .json(ApiResponse::new(
+                    true,
+                    "successfully retrieved roles",
+                    Some(json!({
+                        "roles": roles
+                    }))
+                ));
+        }
+    }
+}
+
+
+async fn role_get_get() -> impl Responder {
+    info!("role_get_get()");
+    return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
+}
+
+/// retrieve a role
+#[utoipa::path(
+    post,
+    path = "/get",
+    request_body = RoleRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 404, description = "not found", body = ApiResponse),
+        (status = 409, description = "conflict", body = ApiResponse),
+        (status = 422, description = "request is not valid", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = ["roles.read"]), ("api_key" = ["roles.read"]))
+)]
+async fn role_get_post(
+    user: CurrentUser,
+    roles: web::Data<Roles>,
+    params: web::Json<RoleRequest>
+) -> impl Responder {
+    info!("role_get_post()");
+    debug!("params: {:?}", params);
+
+    match roles.role_get(&user.tenant_id(), &params.role_id).await {
+        Err(e) => {
+            return roles_error_response(e, "an error occured while trying to retrieve the role");
+        }
+        Ok(role) => {
+            return HttpResponse::Ok()
+                .json(ApiResponse::new(
+                    true,
+                    "successfully retrieved role",
+                    Some(json!({
+                        "role": role
+                    }))
+                ));
+        }
+    }
+}
+
+
+async fn role_update_get() -> impl Responder {
+    info!("role_update_get()");
+    return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
+}
+
+/// update a role
+#[utoipa::path(
+    post,
+    path = "/update",
+    request_body = RoleUpdateRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 404, description = "not found", body = ApiResponse),
+        (status = 409, description = "conflict", body = ApiResponse),
+        (status = 422, description = "request is not valid", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = ["roles.update"]), ("api_key" = ["roles.update"]))
+)]
+async fn role_update_post(
+    user: CurrentUser,
+    roles: web::Data<Roles>,
+    params: Validated<RoleUpdateRequest>
+) -> impl Responder {
+    info!("role_update_post()");
+    debug!("params: {:?}", params);
+
+    match roles.role_update(
+        &user.tenant_id(),
+        &params.role_id,
+        &params.name,
+        &params.description
+    ).await {
+        Err(e) => {
+            return roles_error_response(e, "an error occured while trying to update the role");
+        }
+        Ok(_) => {
+            return HttpResponse::Ok()
+                .json(ApiResponse::new(
+                    true,
+                    "successfully updated role",
+                    None
+                ));
+        }
+    }
+}
+
+
+async fn role_set_active_get() -> impl Responder {
+    info!("role_set_active_get()");
+    return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
+}
+
+/// set the active status of a role
+#[utoipa::path(
+    post,
+    path = "/set/active",
+    request_body = RoleSetActiveRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 404, description = "not found", body = ApiResponse),
+        (status = 409, description = "conflict", body = ApiResponse),
+        (status = 422, description = "request is not valid", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = ["roles.update"]), ("api_key" = ["roles.update"]))
+)]
+async fn role_set_active_post(
+    user: CurrentUser,
+    roles: web::Data<Roles>,
+    params: web::Json<RoleSetActiveRequest>
+) -> impl Responder {
+    info!("role_set_active_post()");
+    debug!("params: {:?}", params);
+
+    match roles.role_set_active(&user.tenant_id(), &params.role_id, &params.active).await {
+        Err(e) => {
+            return roles_error_response(e, "an error occured while trying to set the role active status");
+        }
+        Ok(_) => {
+            return HttpResponse::Ok()
+                .json(ApiResponse::new(
+                    true,
+                    "successfully set role active status",
+                    None
+                ));
+        }
+    }
+}
+
+
+async fn role_remove_get() -> impl Responder {
+    info!("role_remove_get()");
+    return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
+}
+
+/// remove a role
+#[utoipa::path(
+    post,
+    path = "/remove",
+    request_body = RoleRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 404, description = "not found", body = ApiResponse),
+        (status = 409, description = "conflict", body = ApiResponse),
+        (status = 422, description = "request is not valid", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = ["roles.remove"]), ("api_key" = ["roles.remove"]))
+)]
+async fn role_remove_post(
+    user: CurrentUser,
+    roles: web::Data<Roles>,
+    params: web::Json<RoleRequest>
+) -> impl Responder {
+    info!("role_remove_post()");
+    debug!("params: {:?}", params);
+
+    match roles.role_remove(&user.tenant_id(), &params.role_id).await {
+        Err(e) => {
+            return roles_error_response(e, "an error occured while trying to remove the role");
+        }
+        Ok(_) => {
+            return HttpResponse::Ok()
+                .json(ApiResponse::new(
+                    true,
+                    "successfully removed role",
+                    None
+                ));
+        }
+    }
+}
+
+
+async fn permissions_fetch_get() -> impl Responder {
+    info!("permissions_fetch_get()");
+    return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
+}
+
+/// list the permission catalog
+#[utoipa::path(
+    post,
+    path = "/permissions",
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 404, description = "not found", body = ApiResponse),
+        (status = 409, description = "conflict", body = ApiResponse),
+        (status = 422, description = "request is not valid", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = ["roles.read"]), ("api_key" = ["roles.read"]))
+)]
+async fn permissions_fetch_post(
+    roles: web::Data<Roles>
+) -> impl Responder {
+    info!("permissions_fetch_post()");
+
+    match roles.permissions_fetch().await {
+        Err(e) => {
+            return roles_error_response(e, "an error occured while trying to retrieve permissions");
+        }
+        Ok(permissions) => {
+            return HttpResponse::Ok()
+                .json(ApiResponse::new(
+                    true,
+                    "successfully retrieved permissions",
+                    Some(json!({
+                        "permissions": permissions
+                    }))
+                ));
+        }
+    }
+}
+
+
+async fn role_permissions_fetch_get() -> impl Responder {
+    info!("role_permissions_fetch_get()");
+    return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
+}
+
+/// list permissions of a role
+#[utoipa::path(
+    post,
+    path = "/permissions/fetch",
+    request_body = RoleRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 404, description = "not found", body = ApiResponse),
+        (status = 409, description = "conflict", body = ApiResponse),
+        (status = 422, description = "request is not valid", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = ["roles.read"]), ("api_key" = ["roles.read"]))
+)]
+async fn role_permissions_fetch_post(
+    user: CurrentUser,
+    roles: web::Data<Roles>,
+    params: web::Json<RoleRequest>
+) -> impl Responder {
+    info!("role_permissions_fetch_post()");
+    debug!("params: {:?}", params);
+
+    match roles.role_permissions_fetch(&user.tenant_id(), &params.role_id).await {
+        Err(e) => {
+            return roles_error_response(e, "an error occured while trying to retrieve role permissions");
+        }
+        Ok(permissions) => {
+            return HttpResponse::Ok()
+                .json(ApiResponse::new(
+                    true,
+                    "successfully retrieved role permissions",
+                    Some(json!({
+                        "permissions": permissions
+                    }))
+                ));
+        }
+    }
+}
+
+
+async fn role_permissions_set_get() -> impl Responder {
+    info!("role_permissions_set_get()");
+    return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
+}
+
+/// replace the permissions of a role
+#[utoipa::path(
+    post,
+    path = "/permissions/set",
+    request_body = RolePermissionsSetRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 404, description = "not found", body = ApiResponse),
+        (status = 409, description = "conflict", body = ApiResponse),
+        (status = 422, description = "request is not valid", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = ["roles.update"]), ("api_key" = ["roles.update"]))
+)]
+async fn role_permissions_set_post(
+    user: CurrentUser,
+    roles: web::Data<Roles>,
+    params: web::Json<RolePermissionsSetRequest>
+) -> impl Responder {
+    info!("role_permissions_set_post()");
+    debug!("params: {:?}", params);
+
+    if let Some(response) = delegation_denied(&user, &params.permissions) {
+        return response;
+    }
+
+    match roles.role_permissions_set(
+        &user.tenant_id(),
+        &params.role_id,
+        &params.permissions
+    ).await {
+        Err(e) => {
+            return roles_error_response(e, "an error occured while trying to set role permissions");
+        }
+        Ok(_) => {
+            return HttpResponse::Ok()
+                .json(ApiResponse::new(
+                    true,
+                    "successfully set role permissions",
+                    None
+                ));
+        }
+    }
+}
+
+
+async fn user_role_add_get() -> impl Responder {
+    info!("user_role_add_get()");
+    return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
+}
+
+/// assign a role to a user
+#[utoipa::path(
+    post,
+    path = "/users/add",
+    request_body = UserRoleRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 404, description = "not found", body = ApiResponse),
+        (status = 409, description = "conflict", body = ApiResponse),
+        (status = 422, description = "request is not valid", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = ["roles.assign"]), ("api_key" = ["roles.assign"]))
+)]
+async fn user_role_add_post(
+    user: CurrentUser,
+    roles: web::Data<Roles>,
+    params: web::Json<UserRoleRequest>
+) -> impl Responder {
+    info!("user_role_add_post()");
+    debug!("params: {:?}", params);
+
+    let tenant_id = user.tenant_id();
+    match roles.role_permissions_fetch(&tenant_id, &params.role_id).await {
+        Err(e) => {
+            return roles_error_response(e, "an error occured while trying to assign the role");
+        }
+        Ok(permissions) => {
+            if let Some(response) = delegation_denied(&user, &permissions) {
+                return response;
+            }
+        }
+    }
+
+    match roles.user_role_add(&tenant_id, &params.user_id, &params.role_id).await {
+        Err(e) => {
+            return roles_error_response(e, "an error occured while trying to assign the role");
+        }
+        Ok(_) => {
+            return HttpResponse::Ok()
+                .json(ApiResponse::new(
+                    true,
+                    "successfully assigned role",
+                    None
+                ));
+        }
+    }
+}
+
+
+async fn user_role_remove_get() -> impl Responder {
+    info!("user_role_remove_get()");
+    return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
+}
+
+/// remove a role from a user
+#[utoipa::path(
+    post,
+    path = "/users/remove",
+    request_body = UserRoleRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 404, description = "not found", body = ApiResponse),
+        (status = 409, description = "conflict", body = ApiResponse),
+        (status = 422, description = "request is not valid", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = ["roles.assign"]), ("api_key" = ["roles.assign"]))
+)]
+async fn user_role_remove_post(
+    user: CurrentUser,
+    roles: web::Data<Roles>,
+    params: web::Json<UserRoleRequest>
+) -> impl Responder {
+    info!("user_role_remove_post()");
+    debug!("params: {:?}", params);
+
+    // removing a role requires the permissions it grants, as assigning it does
+    let tenant_id = user.tenant_id();
+    match roles.role_permissions_fetch(&tenant_id, &params.role_id).await {
+        Err(e) => {
+            return roles_error_response(e, "an error occured while trying to remove the role assignment");
+        }
+        Ok(permissions) => {
+            if let Some(response) = delegation_denied(&user, &permissions) {
+                return response;
+            }
+        }
+    }
+
+    match roles.user_role_remove(&tenant_id, &params.user_id, &params.role_id).await {
+        Err(e) => {
+            return roles_error_response(e, "an error occured while trying to remove the role assignment");
+        }
+        Ok(_) => {
+            return HttpResponse::Ok()
+                .json(ApiResponse::new(
+                    true,
+                    "successfully removed role assignment",
+                    None
+                ));
+        }
+    }
+}
+
+
+async fn user_roles_fetch_get() -> impl Responder {
+    info!("user_roles_fetch_get()");
+    return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
+}
+
+/// list roles of a user
+#[utoipa::path(
+    post,
+    path = "/users/fetch",
+    request_body = UserRolesRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 404, description = "not found", body = ApiResponse),
+        (status = 409, description = "conflict", body = ApiResponse),
+        (status = 422, description = "request is not valid", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = ["roles.read"]), ("api_key" = ["roles.read"]))
+)]
+async fn user_roles_fetch_post(
+    user: CurrentUser,
+    roles: web::Data<Roles>,
+    params: web::Json<UserRolesRequest>
+) -> impl Responder {
+    info!("user_roles_fetch_post()");
+    debug!("params: {:?}", params);
+
+    match roles.user_roles_fetch(&user.tenant_id(), &params.user_id).await {
+        Err(e) => {
+            return roles_error_response(e, "an error occured while trying to retrieve user roles");
+        }
+        Ok(roles) => {
+            return HttpResponse::Ok()
+                .json(ApiResponse::new(
+                    true,
+                    "successfully retrieved user roles",
+                    Some(json!({
+                        "roles": roles
+                    }))
+                ));
+        }
+    }
+}
@@ -3,28 +3,99 @@ use log::{
     debug,
     error
 };
+use std::time::Duration;
 
 use actix_web::{
     HttpResponse, 
     Responder,
+    rt::time::timeout,
     web
 };
+use serde::Serialize;
+use serde_json::json;
+use utoipa::OpenApi;
+
+use data::pg::migrations::SchemaStatus;
+use common::Error;
 
 use crate::endpoints::{
     ApiResponse
 };
+use crate::classes::routes::{
+    self,
+    Routes
+};
+
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    status_get,
+    status_post,
+    status_live,
+    status_ready,
+    status_info
+))]
+pub struct ApiDoc;
+
+
+/// how long each readiness check may take
+const CHECK_TIMEOUT: Duration = Duration::from_secs(3);
 
 
-pub fn config(cfg: &mut web::ServiceConfig) {
+/// outcome of a readiness check
+#[derive(Debug, Serialize)]
+struct Check {
+    ok: bool,
+    detail: String
+}
+
+impl Check {
+    fn ok(detail: &str) -> Self {
+        return Self {
+            ok: true,
+            detail: String::from(detail)
+        };
+    }
+
+    fn failed(detail: &str) -> Self {
+        return Self {
+            ok: false,
+            detail: String::from(detail)
+        };
+    }
+}
+
+
+pub fn config(cfg: &mut Routes) {
     cfg
         .service(
-            web::resource("")
-                .route(web::get().to(status_get))
-                .route(web::post().to(status_post))
+            routes::resource("")
+                .route(routes::get().to(status_get))
+                .route(routes::post().to(status_post))
+        )
+        .service(
+            routes::resource("/live")
+                .route(routes::get().to(status_live))
+        )
+        .service(
+            routes::resource("/ready")
+                .route(routes::get().to(status_ready))
+        )
+        .service(
+            routes::resource("/info")
+                .route(routes::get().to(status_info))
         )
     ;
 }
 
+/// service status
+#[utoipa::path(
+    get,
+    path = "",
+    responses(
+        (status = 200, description = "service is up", body = String, content_type = "text/plain")
+    )
+)]
 async fn status_get() -> impl Responder {
     info!("status_get()");
 
@@ -32,12 +103,202 @@ async fn status_get() -> impl Responder {
 }
 
 
+/// service status
+#[utoipa::path(
+    post,
+    path = "",
+    responses(
+        (status = 200, description = "success", body = ApiResponse)
+    )
+)]
 async fn status_post() -> impl Responder {
     info!("status_post()");
     return HttpResponse::Ok()
-        .json(ApiResponse {
-            success: false,
-            message: String::from("Service is up. version: 1.0.0.0.dev"),
-            data: None
-        });
+        .json(ApiResponse::new(
+            false,
+            "Service is up. version: 1.0.0.0.dev",
+            None
+        ));
+}
+
+
+/// liveness, the process is up and serving requests
+#[utoipa::path(
+    get,
+    path = "/live",
+    responses(
+        (status = 200, description = "service is live", body = ApiResponse)
+    )
+)]
+async fn status_live() -> impl Responder {
+    info!("status_live()");
+    return HttpResponse::Ok()
+        .json(ApiResponse::new(
+            true,
+            "live",
+            None
+        ));
+}
+
+/// readiness, the database is reachable with a current schema and the
+/// mail relay accepts connections. data lists the outcome of each check
+#[utoipa::path(
+    get,
+    path = "/ready",
+    responses(
+        (status = 200, description = "service is ready", body = ApiResponse),
+        (status = 503, description = "service is degraded", body = ApiResponse)
+    )
+)]
+async fn status_ready(
+    data: web::Data<data::Data>,
+    mailer: web::Data<mailer::Mailer>
+) -> impl Responder {
+    info!("status_ready()");
+
+    let database = database_check(&data).await;
+    let schema = schema_check(&data).await;
+    let mailer = mailer_check(mailer.get_ref().clone()).await;
+    let ready = database.ok && schema.ok && mailer.ok;
+    debug!("database: {:?}, schema: {:?}, mailer: {:?}", database, schema, mailer);
+
+    let checks = json!({
+        "database": database,
+        "schema": schema,
+        "mailer": mailer
+    });
+    if ready {
+        return HttpResponse::Ok()
+            .json(ApiResponse::new(
+                true,
+                "ready",
+                Some(checks)
+            ));
+    }
+    return Error::Unavailable(String::from("degraded"))
+        .error_response_with(checks);
+}
+
+/// version, git commit and build time of the running server
+#[utoipa::path(
+    get,
+    path = "/info",
+    responses(
+        (status = 200, description = "build information", body = ApiResponse)
+    )
+)]
+async fn status_info() -> impl Responder {
+    info!("status_info()");
+    return HttpResponse::Ok()
+        .json(ApiResponse::new(
+            true,
+            "build information",
+            Some(json!({
+                "version": env!("CARGO_PKG_VERSION"),
+                "commit": env!("IDEAS_GIT_COMMIT"),
+                "buildTime": env!("IDEAS_BUILD_TIME")
+            }))
+        ));
+}
+
+/// a connection can be taken from the default provider's pool
+async fn database_check(data: &data::Data) -> Check {
+    let pool = match data.get_pg_pool() {
+        None => {
+            return Check::failed("no database provider configured");
+        }
+        Some(pool) => pool
+    };
+    match timeout(CHECK_TIMEOUT, pool.get()).await {
+        Err(_) => {
+            error!("timed out waiting for a database connection");
+            return Check::failed("timed out waiting for a connection");
+        }
+        Ok(Err(e)) => {
+            error!("unable to get a database connection: {:?}", e);
+            return Check::failed("unable to get a connection");
+        }
+        Ok(Ok(client)) => {
+            // returned to the pool before its status is read
+            drop(client);
+            let status = pool.status();
+            return Check::ok(&format!("{} of {} connections idle, at most {}", status.available, status.size, status.max_size));
+        }
+    }
+}
+
+/// the schema is not behind the embedded migrations
+async fn schema_check(data: &data::Data) -> Check {
+    match timeout(CHECK_TIMEOUT, data.schema_status()).await {
+        Err(_) => {
+            error!("timed out checking the database schema version");
+            return Check::failed("timed out checking the schema version");
+        }
+        Ok(Err(e)) => {
+            error!("unable to check database schema version: {:?}", e);
+            return Check::failed("unable to check the schema version");
+        }
+        Ok(Ok(SchemaStatus::Behind { current, latest })) => {
+            return Check::failed(&format!("version {} is behind {}", current, latest));
+        }
+        Ok(Ok(SchemaStatus::Ahead { current, latest })) => {
+            return Check::ok(&format!("version {} is ahead of {}", current, latest));
+        }
+        Ok(Ok(SchemaStatus::Current(version))) => {
+            return Check::ok(&format!("version {}", version));
+        }
+    }
+}
+
+/// the mail relay accepts connections
+async fn mailer_check(mailer: mailer::Mailer) -> Check {
+    match web::block(move || mailer.test_connection(CHECK_TIMEOUT)).await {
+        Err(e) => {
+            error!("unable to check mail relay: {:?}", e);
+            return Check::failed("unable to check the relay");
+        }
+        Ok(Err(e)) => {
+            error!("mail relay is not reachable: {:?}", e);
+            return Check::failed("relay is not reachable");
+        }
+        Ok(Ok(_)) => {
+            return Check::ok("relay is reachable");
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web::{
+        App,
+        http::StatusCode,
+        test::{
+            TestRequest,
+            call_service,
+            init_service,
+            read_body_json
+        }
+    };
+
+    #[actix_web::test]
+    async fn test_status_info() {
+        let app = init_service(
+            App::new().service(routes::scope("/status", config))
+        ).await;
+
+        let request = TestRequest::get().uri("/status/live").to_request();
+        let response = call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let request = TestRequest::get().uri("/status/info").to_request();
+        let response = call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value = read_body_json(response).await;
+        assert_eq!(body["data"]["version"], json!(env!("CARGO_PKG_VERSION")));
+        assert!(body["data"]["commit"].as_str().is_some_and(|c| !c.is_empty()));
+        assert!(body["data"]["buildTime"].is_string());
+    }
 }
@@ -0,0 +1,253 @@
+use log::{
+    info,
+    debug,
+    error
+};
+
+use actix_web::{
+    HttpResponse, 
+    Responder,
+    web
+};
+use serde::{
+    Serialize,
+    Deserialize
+};
+use serde_json::json;
+use utoipa::{
+    OpenApi,
+    ToSchema
+};
+
+use chrono::prelude::*;
+
+use crate::endpoints::{
+    ApiResponse,
+    error_response,
+    default_options,
+    default_service
+};
+use crate::classes::routes::{
+    self,
+    Routes
+};
+use crate::endpoints::roles::delegation_denied;
+use crate::classes::user::CurrentUser;
+use crate::classes::guards::{
+    authenticated::Authenticated,
+    permission::Permission
+};
+
+use tenants::api_keys::ApiKeys;
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+struct ApiKeyAddRequest {
+    pub name: String,
+    /// permissions granted to the key, the creator must hold each of them
+    pub permissions: Vec<String>,
+    #[serde(default)]
+    pub expires: Option<DateTime<Utc>>
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+struct ApiKeyRevokeRequest {
+    #[schema(value_type = String, format = "uuid")]
+    pub api_key_id: uuid::Uuid
+}
+
+
+/// api keys are managed by signed in users only, an api key cannot be used
+/// to create or revoke keys
+#[derive(OpenApi)]
+#[openapi(paths(
+    api_key_add_post,
+    api_keys_fetch_post,
+    api_key_revoke_post
+))]
+pub struct ApiDoc;
+
+
+pub fn config(cfg: &mut Routes) {
+    cfg
+        .service(
+            routes::resource("/add")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::placeholder().to(api_key_add_get))
+                .route(routes::post()
+                    .guard(Authenticated::new())
+                    .guard(Permission::new("api_keys.manage"))
+                    .to(api_key_add_post)
+                )
+                .default_service(web::to(default_service))
+        )
+        .service(
+            routes::resource("/fetch")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::placeholder().to(api_keys_fetch_get))
+                .route(routes::post()
+                    .guard(Authenticated::new())
+                    .guard(Permission::new("api_keys.manage"))
+                    .to(api_keys_fetch_post)
+                )
+                .default_service(web::to(default_service))
+        )
+        .service(
+            routes::resource("/revoke")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::placeholder().to(api_key_revoke_get))
+                .route(routes::post()
+                    .guard(Authenticated::new())
+                    .guard(Permission::new("api_keys.manage"))
+                    .to(api_key_revoke_post)
+                )
+                .default_service(web::to(default_service))
+        )
+    ;
+}
+
+
+async fn api_key_add_get() -> impl Responder {
+    info!("api_key_add_get()");
+    return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
+}
+
+
+/// add an api key, the key is only returned once
+#[utoipa::path(
+    post,
+    path = "/add",
+    request_body = ApiKeyAddRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 422, description = "request is not valid", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = ["api_keys.manage"]))
+)]
+async fn api_key_add_post(
+    user: CurrentUser,
+    api_keys: web::Data<ApiKeys>,
+    params: web::Json<ApiKeyAddRequest>
+) -> impl Responder {
+    info!("api_key_add_post()");
+    debug!("params: {:?}", params);
+
+    if let Some(response) = delegation_denied(&user, &params.permissions) {
+        return response;
+    }
+
+    match api_keys.add(
+        &user.tenant_id(),
+        &params.name,
+        &params.permissions,
+        params.expires,
+        &user.id()
+    ).await {
+        Err(e) => {
+            error!("unable to add api key: {:?}", e);
+            return error_response(e, "an error occured while trying to add the api key");
+        }
+        Ok((api_key, key)) => {
+            return HttpResponse::Ok()
+                .json(ApiResponse::new(
+                    true,
+                    "successfully added api key. the key is only shown once",
+                    Some(json!({
+                        "api_key": api_key,
+                        "key": key
+                    }))
+                ));
+        }
+    }
+}
+
+
+async fn api_keys_fetch_get() -> impl Responder {
+    info!("api_keys_fetch_get()");
+    return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
+}
+
+
+/// list api keys of the current tenant
+#[utoipa::path(
+    post,
+    path = "/fetch",
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = ["api_keys.manage"]))
+)]
+async fn api_keys_fetch_post(
+    user: CurrentUser,
+    api_keys: web::Data<ApiKeys>
+) -> impl Responder {
+    info!("api_keys_fetch_post()");
+
+    match api_keys.fetch(&user.tenant_id()).await {
+        Err(e) => {
+            error!("unable to retrieve api keys: {:?}", e);
+            return error_response(e, "an error occured while trying to retrieve api keys");
+        }
+        Ok(keys) => {
+            return HttpResponse::Ok()
+                .json(ApiResponse::new(
+                    true,
+                    "successfully retrieved api keys",
+                    Some(json!({
+                        "api_keys": keys
+                    }))
+                ));
+        }
+    }
+}
+
+
+async fn api_key_revoke_get() -> impl Responder {
+    info!("api_key_revoke_get()");
+    return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
+}
+
+
+/// revoke an api key
+#[utoipa::path(
+    post,
+    path = "/revoke",
+    request_body = ApiKeyRevokeRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = ["api_keys.manage"]))
+)]
+async fn api_key_revoke_post(
+    user: CurrentUser,
+    api_keys: web::Data<ApiKeys>,
+    params: web::Json<ApiKeyRevokeRequest>
+) -> impl Responder {
+    info!("api_key_revoke_post()");
+    debug!("params: {:?}", params);
+
+    match api_keys.revoke(&user.tenant_id(), &params.api_key_id).await {
+        Err(e) => {
+            error!("unable to revoke api key: {:?}", e);
+            return error_response(e, "an error occured while trying to revoke the api key");
+        }
+        Ok(_) => {
+            return HttpResponse::Ok()
+                .json(ApiResponse::new(
+                    true,
+                    "successfully revoked api key",
+                    None
+                ));
+        }
+    }
+}
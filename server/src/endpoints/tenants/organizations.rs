@@ -15,27 +15,56 @@ use serde::{
     Deserialize
 };
 use serde_json::json;
+use validator::Validate;
+use utoipa::{
+    OpenApi,
+    ToSchema
+};
 
 use crate::endpoints::{
-    ApiResponse
+    ApiResponse,
+    default_options,
+    default_service
+};
+use crate::classes::routes::{
+    self,
+    Routes
 };
 use crate::classes::user::CurrentUser;
+use crate::classes::extractors::validated::Validated;
+use crate::classes::validators;
+use crate::classes::guards::permission::Permission;
 
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
 struct OrganizationRequest {
+    #[schema(value_type = String, format = "uuid")]
     pub id: uuid::Uuid,
+    #[validate(custom(function = "validators::not_blank"), length(max = 100, message = "must be at most 100 characters"))]
     pub name: String,
+    #[validate(length(max = 1000, message = "must be at most 1000 characters"))]
     pub description: String
 }
 
 
-pub fn config(cfg: &mut web::ServiceConfig) {
+#[derive(OpenApi)]
+#[openapi(paths(
+    organization_add_post
+))]
+pub struct ApiDoc;
+
+
+pub fn config(cfg: &mut Routes) {
     cfg
         .service(
-            web::resource("add")
-                .route(web::get().to(organization_add_get))
-                .route(web::post().to(organization_add_post))
+            routes::resource("add")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::placeholder().to(organization_add_get))
+                .route(routes::post()
+                    .guard(Permission::new("organizations.add"))
+                    .to(organization_add_post)
+                )
+                .default_service(web::to(default_service))
         )
     ;
 }
@@ -48,9 +77,22 @@ async fn organization_add_get() -> impl Responder {
     return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
 }
 
+/// add an organization
+#[utoipa::path(
+    post,
+    path = "/add",
+    request_body = OrganizationRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 422, description = "request is not valid", body = ApiResponse)
+    ),
+    security(("bearer" = ["organizations.add"]), ("api_key" = ["organizations.add"]))
+)]
 async fn organization_add_post(
     user: CurrentUser,
-    params: web::Json<OrganizationRequest>
+    params: Validated<OrganizationRequest>
 ) -> impl Responder {
     info!("organization_add_post()");
     debug!("params: {:?}", params);
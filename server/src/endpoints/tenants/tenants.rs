@@ -7,6 +7,7 @@ use log::{
 use actix_web::{
     HttpResponse, 
     Responder,
+    ResponseError,
     web
 };
 use serde::{
@@ -14,125 +15,176 @@ use serde::{
     Deserialize
 };
 use serde_json::json;
+use validator::Validate;
+use utoipa::{
+    OpenApi,
+    ToSchema
+};
 
 
 use crate::endpoints::{
     ApiResponse,
+    error_response,
     default_options,
-    default_service
+    default_service,
+    tenant_not_current
+};
+use crate::classes::routes::{
+    self,
+    Routes
 };
 use crate::classes::user::CurrentUser;
+use crate::classes::extractors::validated::Validated;
+use crate::classes::validators;
 use crate::classes::guards::{
     authenticated::Authenticated,
     permission::Permission
 };
 
-use tenants::tenants::{Tenants, TenantsError};
+use tenants::tenants::Tenants;
+use roles::roles::Roles;
+use common::Error;
 
 
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
 struct TenantAddRequest {
+    #[schema(value_type = String, format = "uuid")]
     pub tenant_id: uuid::Uuid,
+    #[validate(custom(function = "validators::not_blank"), length(max = 100, message = "must be at most 100 characters"))]
     pub name: String,
+    #[validate(custom(function = "validators::slug"))]
     pub slug: String,
+    #[validate(length(max = 1000, message = "must be at most 1000 characters"))]
     pub description: String
 }
 
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 struct TenantGetInfoRequest {
+    #[schema(value_type = String, format = "uuid")]
     pub tenant_id: uuid::Uuid
 }
 
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 struct TenantGetInfoBySlugRequest {
     pub name: String
 }
 
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 struct TenantSetActiveRequest {
+    #[schema(value_type = String, format = "uuid")]
     pub tenant_id: uuid::Uuid,
     pub active: bool
 }
 
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+struct TenantSetMfaRequiredRequest {
+    pub required: bool
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 struct TenantMembersRequest {
     pub tenant_slug: String
 }
 
 
-pub fn config(cfg: &mut web::ServiceConfig) {
+#[derive(OpenApi)]
+#[openapi(paths(
+    tenant_add_post,
+    tenants_fetch_post,
+    tenant_update_post,
+    tenant_set_active_post,
+    tenant_set_mfa_required_post,
+    tenant_get_post,
+    tenant_get_slug_post,
+    tenant_members_fetch_post
+))]
+pub struct ApiDoc;
+
+
+pub fn config(cfg: &mut Routes) {
     cfg
         .service(
-            web::resource("/add")
-                .route(web::method(http::Method::OPTIONS).to(default_options))
-                .route(web::get().to(tenant_add_get))
-                .route(web::post()
-                    .guard(Permission::new("permission.test"))
+            routes::resource("/add")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::placeholder().to(tenant_add_get))
+                .route(routes::post()
+                    .guard(Permission::new("tenants.add"))
                     .to(tenant_add_post)
                 )
                 .default_service(web::to(default_service))
         )
         .service(
-            web::resource("/fetch")
-                .route(web::method(http::Method::OPTIONS).to(default_options))
-                .route(web::get().to(tenants_fetch_get))
-                .route(web::post()
-                    .guard(Permission::new("permission.test"))
+            routes::resource("/fetch")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::placeholder().to(tenants_fetch_get))
+                .route(routes::post()
+                    .guard(Permission::new("tenants.read"))
                     .to(tenants_fetch_post)
                 )
                 .default_service(web::to(default_service))
         )
         .service(
-            web::resource("/update")
-                .route(web::method(http::Method::OPTIONS).to(default_options))
-                .route(web::get().to(tenant_update_get))
-                .route(web::post()
-                    .guard(Permission::new("permission.test"))
+            routes::resource("/update")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::placeholder().to(tenant_update_get))
+                .route(routes::post()
+                    .guard(Permission::new("tenants.update"))
                     .to(tenant_update_post)
                 )
                 .default_service(web::to(default_service))
         )
         .service(
-            web::resource("/set/active")
-                .route(web::method(http::Method::OPTIONS).to(default_options))
-                .route(web::get().to(tenant_set_active_get))
-                .route(web::post()
-                    .guard(Permission::new("permission.test"))
+            routes::resource("/set/active")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::placeholder().to(tenant_set_active_get))
+                .route(routes::post()
+                    .guard(Permission::new("tenants.set_active"))
                     .to(tenant_set_active_post)
                 )
                 .default_service(web::to(default_service))
         )
         .service(
-            web::resource("/get")
-                .route(web::method(http::Method::OPTIONS).to(default_options))
-                .route(web::get().to(tenant_get_get))
-                .route(web::post()
-                    .guard(Permission::new("permission.test"))
+            routes::resource("/set/mfa")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::placeholder().to(tenant_set_mfa_required_get))
+                .route(routes::post()
+                    .guard(Permission::new("tenants.set_mfa"))
+                    .to(tenant_set_mfa_required_post)
+                )
+                .default_service(web::to(default_service))
+        )
+        .service(
+            routes::resource("/get")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::placeholder().to(tenant_get_get))
+                .route(routes::post()
+                    .guard(Permission::new("tenants.read"))
                     .to(tenant_get_post)
                 )
                 .default_service(web::to(default_service))
         )
         .service(
-            web::resource("/get/slug")
-                .route(web::method(http::Method::OPTIONS).to(default_options))
-                .route(web::get().to(tenant_get_slug_get))
-                .route(web::post()
-                    .guard(Permission::new("permission.test"))
+            routes::resource("/get/slug")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::placeholder().to(tenant_get_slug_get))
+                .route(routes::post()
+                    .guard(Permission::new("tenants.read"))
                     .to(tenant_get_slug_post)
                 )
                 .default_service(web::to(default_service))
         )
         .service(
-            web::resource("/members/fetch")
-                .route(web::method(http::Method::OPTIONS).to(default_options))
-                .route(web::get().to(tenant_members_fetch_get))
-                .route(web::post()
-                    .guard(Permission::new("permission.test"))
+            routes::resource("/members/fetch")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::placeholder().to(tenant_members_fetch_get))
+                .route(routes::post()
+                    .guard(Permission::new("tenants.members.read"))
                     .to(tenant_members_fetch_post)
                 )
                 .default_service(web::to(default_service))
@@ -146,37 +198,71 @@ async fn tenant_add_get() -> impl Responder {
     return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
 }
 
+/// add a tenant, the user becomes its owner
+#[utoipa::path(
+    post,
+    path = "/add",
+    request_body = TenantAddRequest,
+    responses(
+        (status = 201, description = "created", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 422, description = "request is not valid", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = ["tenants.add"]), ("api_key" = ["tenants.add"]))
+)]
 async fn tenant_add_post(
     user: CurrentUser,
+    data: web::Data<data::Data>,
     tenants: web::Data<Tenants>,
-    params: web::Json<TenantAddRequest>
+    roles: web::Data<Roles>,
+    params: Validated<TenantAddRequest>
 ) -> impl Responder {
     info!("tenant_add_post");
 
-    match tenants.tenant_add(
+    // the tenant and its roles are added together or not at all
+    let uow = match data.begin().await {
+        Err(e) => {
+            error!("tenant_add_post(): {:?}", e);
+            return error_response(e, "an error occured while trying to add a tenant");
+        }
+        Ok(uow) => uow
+    };
+
+    if let Err(e) = tenants.tenant_add_in(
+        &uow,
         params.tenant_id,
         &params.name,
         &params.slug,
         &params.description
     ).await {
-        Err(e) => {
-            error!("tenant_add_post(): {:?}", e);
-            return HttpResponse::InternalServerError()
-                .json(ApiResponse::new(
-                    false,
-                    &"an error occured while trying to add a tenant",
-                    None
-                ));
+        error!("tenant_add_post(): {:?}", e);
+        if let Err(e) = uow.rollback().await {
+            error!("unable to roll back tenant: {:?}", e);
         }
-        Ok(_) => {
-            return HttpResponse::Created()
-                .json(ApiResponse::new(
-                    true,
-                    &"successfully added tenant",
-                    None
-                ));
+        return error_response(e, "an error occured while trying to add a tenant");
+    }
+
+    // the user adding the tenant becomes its owner
+    if let Err(e) = roles.tenant_roles_seed(&uow, &params.tenant_id, &user.id()).await {
+        error!("unable to add tenant roles: {:?}", e);
+        if let Err(e) = uow.rollback().await {
+            error!("unable to roll back tenant: {:?}", e);
         }
+        return error_response(e, "an error occured while trying to add the tenant roles");
+    }
+
+    if let Err(e) = uow.commit().await {
+        error!("tenant_add_post(): {:?}", e);
+        return error_response(e, "an error occured while trying to add a tenant");
     }
+    return HttpResponse::Created()
+        .json(ApiResponse::new(
+            true,
+            &"successfully added tenant",
+            None
+        ));
 }
 
 
@@ -185,6 +271,18 @@ async fn tenants_fetch_get() -> impl Responder {
     return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
 }
 
+/// list tenants
+#[utoipa::path(
+    post,
+    path = "/fetch",
+    responses(
+        (status = 201, description = "created", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = ["tenants.read"]), ("api_key" = ["tenants.read"]))
+)]
 async fn tenants_fetch_post(
     user: CurrentUser,
     tenants: web::Data<Tenants>
@@ -194,12 +292,7 @@ async fn tenants_fetch_post(
     match tenants.tenants().await {
         Err(e) => {
             error!("tenants_fetch_post(): {:?}", e);
-            return HttpResponse::InternalServerError()
-                .json(ApiResponse::new(
-                    false,
-                    &"an error occured while trying to fetch tenants",
-                    None
-                ));
+            return error_response(e, "an error occured while trying to fetch tenants");
         }
         Ok(tenants) => {
             return HttpResponse::Created()
@@ -220,30 +313,43 @@ async fn tenant_update_get() -> impl Responder {
     return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
 }
 
+/// update the current tenant
+#[utoipa::path(
+    post,
+    path = "/update",
+    request_body = TenantAddRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 422, description = "request is not valid", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = ["tenants.update"]), ("api_key" = ["tenants.update"]))
+)]
 async fn tenant_update_post(
     user: CurrentUser,
     tenants: web::Data<Tenants>,
-    params: web::Json<TenantAddRequest>
+    params: Validated<TenantAddRequest>
 ) -> impl Responder {
     info!("tenant_update_post");
 
+    if params.tenant_id != user.tenant_id() {
+        return tenant_not_current();
+    }
+
     match tenants.tenant_update(
-        params.tenant_id,
+        user.tenant_id(),
         &params.name,
         &params.slug.as_str(),
         &params.description
     ).await {
         Err(e) => {
             error!("tenant_add_post(): {:?}", e);
-            return HttpResponse::InternalServerError()
-                .json(ApiResponse::new(
-                    false,
-                    &"an error occured while trying to update tenant",
-                    None
-                ));
+            return error_response(e, "an error occured while trying to update tenant");
         }
         Ok(_) => {
-            return HttpResponse::Created()
+            return HttpResponse::Ok()
                 .json(ApiResponse::new(
                     true,
                     &"successfully updated tenant",
@@ -260,6 +366,19 @@ async fn tenant_get_get() -> impl Responder {
 }
 
 
+/// retrieve the current tenant by id
+#[utoipa::path(
+    post,
+    path = "/get",
+    request_body = TenantGetInfoRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = ["tenants.read"]), ("api_key" = ["tenants.read"]))
+)]
 async fn tenant_get_post(
     user: CurrentUser,
     tenants: web::Data<Tenants>,
@@ -268,15 +387,14 @@ async fn tenant_get_post(
     info!("tenant_get_post()");
     debug!("params: {:?}", params);
 
-    match tenants.tenant_by_id(&params.tenant_id).await {
+    if params.tenant_id != user.tenant_id() {
+        return tenant_not_current();
+    }
+
+    match tenants.tenant_by_id(&user.tenant_id()).await {
         Err(e) => {
             error!("tenant_get_post: {:?}", e);
-            return HttpResponse::InternalServerError()
-                .json(ApiResponse::new(
-                    false,
-                    "//TODO an error occured while trying to retrieve tenant by id",
-                    None
-                ));
+            return error_response(e, "//TODO an error occured while trying to retrieve tenant by id");
         }
         Ok(tenant) => {
             return HttpResponse::Ok()
@@ -298,6 +416,19 @@ async fn tenant_get_slug_get() -> impl Responder {
 }
 
 
+/// retrieve the current tenant by slug
+#[utoipa::path(
+    post,
+    path = "/get/slug",
+    request_body = TenantGetInfoBySlugRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = ["tenants.read"]), ("api_key" = ["tenants.read"]))
+)]
 async fn tenant_get_slug_post(
     user: CurrentUser,
     tenants: web::Data<Tenants>,
@@ -309,12 +440,10 @@ async fn tenant_get_slug_post(
     match tenants.tenant_by_name(&params.name).await {
         Err(e) => {
             error!("tenant_get_slug_post: {:?}", e);
-            return HttpResponse::InternalServerError()
-                .json(ApiResponse::new(
-                    false,
-                    "//TODO an error occured while trying to retrieve tenant by slug",
-                    None
-                ));
+            return error_response(e, "//TODO an error occured while trying to retrieve tenant by slug");
+        }
+        Ok(tenant) if tenant.id() != user.tenant_id() => {
+            return tenant_not_current();
         }
         Ok(tenant) => {
             return HttpResponse::Ok()
@@ -336,6 +465,19 @@ async fn tenant_set_active_get() -> impl Responder {
 }
 
 
+/// set the active status of a tenant
+#[utoipa::path(
+    post,
+    path = "/set/active",
+    request_body = TenantSetActiveRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = ["tenants.set_active"]), ("api_key" = ["tenants.set_active"]))
+)]
 async fn tenant_set_active_post(
     user: CurrentUser,
     params: web::Json<TenantSetActiveRequest>
@@ -343,12 +485,56 @@ async fn tenant_set_active_post(
     info!("tenant_set_active_post()");
     debug!("params: {:?}", params);
 
-    return HttpResponse::InternalServerError()
-        .json(ApiResponse::new(
-            false,
-            "Service is up. version: 1.0.0.0.dev",
-            None
-        ));
+    return Error::Internal(String::from("tenant active status cannot be changed"))
+        .error_response();
+}
+
+
+async fn tenant_set_mfa_required_get() -> impl Responder {
+    info!("tenant_set_mfa_required_get()");
+    return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
+}
+
+
+/// require two-factor authentication for members of the current tenant
+#[utoipa::path(
+    post,
+    path = "/set/mfa",
+    request_body = TenantSetMfaRequiredRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = ["tenants.set_mfa"]), ("api_key" = ["tenants.set_mfa"]))
+)]
+async fn tenant_set_mfa_required_post(
+    user: CurrentUser,
+    tenants: web::Data<Tenants>,
+    params: web::Json<TenantSetMfaRequiredRequest>
+) -> impl Responder {
+    info!("tenant_set_mfa_required_post()");
+    debug!("params: {:?}", params);
+
+    // permissions are granted for the current tenant only
+    match tenants.tenant_set_mfa_required(
+        &user.tenant_id(),
+        &params.required
+    ).await {
+        Err(e) => {
+            error!("unable to set tenant two-factor authentication requirement: {:?}", e);
+            return error_response(e, "an error occured while trying to update the tenant");
+        }
+        Ok(_) => {
+            return HttpResponse::Ok()
+                .json(ApiResponse::new(
+                    true,
+                    "successfully updated tenant",
+                    None
+                ));
+        }
+    }
 }
 
 
@@ -358,6 +544,19 @@ async fn tenant_members_fetch_get() -> impl Responder {
 }
 
 
+/// list members of a tenant
+#[utoipa::path(
+    post,
+    path = "/members/fetch",
+    request_body = TenantMembersRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = ["tenants.members.read"]), ("api_key" = ["tenants.members.read"]))
+)]
 async fn tenant_members_fetch_post(
     user: CurrentUser,
     tenants: web::Data<Tenants>,
@@ -369,23 +568,13 @@ async fn tenant_members_fetch_post(
     match tenants.tenant_by_slug(&params.tenant_slug).await {
         Err(e) => {
             error!("unable to fetch tenant members");
-            return HttpResponse::InternalServerError()
-                .json(ApiResponse::new(
-                    false,
-                    "an error occured while trying to retrieve tenant members",
-                    None
-                ));
+            return error_response(e, "an error occured while trying to retrieve tenant members");
         }
         Ok(tenant) => {
             match tenants.tenant_users_fetch(&tenant.id()).await {
                 Err(e) => {
                     error!("unable to fetch tenant members");
-                    return HttpResponse::InternalServerError()
-                        .json(ApiResponse::new(
-                            false,
-                            "an error occured while trying to retrieve tenant members",
-                            None
-                        ));
+                    return error_response(e, "an error occured while trying to retrieve tenant members");
 
                 }
                 Ok(members) => {
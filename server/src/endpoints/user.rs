@@ -15,15 +15,29 @@ use serde::{
     Deserialize
 };
 use serde_json::json;
-use users::users::Users;
+use utoipa::{
+    OpenApi,
+    ToSchema
+};
+use users::users::{
+    Users,
+    UsersError
+};
 // use clients::clients::Clients;
 use tenants::tenants::Tenants;
+use common::Error;
 
 // use configuration::ApplicationConfiguration;
 use crate::endpoints::{
     ApiResponse,
+    error_response,
     default_options,
-    default_service
+    default_service,
+    tenant_not_current
+};
+use crate::classes::routes::{
+    self,
+    Routes
 };
 use crate::classes::user::CurrentUser;
 use crate::classes::guards::{
@@ -39,101 +53,143 @@ use auth::{
 }};
 
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 struct UserPasswordRequest {
     pub password: String
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 struct CurrentUserTenantSetRequest {
+    #[schema(value_type = String, format = "uuid")]
     pub tenant_id: uuid::Uuid
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 struct UserTenantJoinRequest {
     pub tenant: String
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 struct UserTenantSetActiveRequest {
+    #[schema(value_type = String, format = "uuid")]
     pub user_id: uuid::Uuid,
+    #[schema(value_type = String, format = "uuid")]
     pub tenant_id: uuid::Uuid,
     pub active: bool
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 struct UserTenantSetDefaultRequest {
+    #[schema(value_type = String, format = "uuid")]
     pub user_id: uuid::Uuid,
+    #[schema(value_type = String, format = "uuid")]
     pub tenant_id: uuid::Uuid
 }
 
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+struct UserSessionRevokeRequest {
+    #[schema(value_type = String, format = "uuid")]
+    pub session_id: uuid::Uuid
+}
+
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    current_post,
+    current_tenant_set_post,
+    user_set_password_post,
+    user_tenant_join_post,
+    user_tenant_set_active_post,
+    user_tenant_set_default_post,
+    user_sessions_post,
+    user_session_revoke_post
+))]
+pub struct ApiDoc;
 
-pub fn config(cfg: &mut web::ServiceConfig) {
+
+pub fn config(cfg: &mut Routes) {
     cfg
         .service(
-            web::resource("/current")
-                .route(web::method(http::Method::OPTIONS).to(default_options))
-                .route(web::get().to(current_get))
-                .route(web::post()
-                    .guard(Permission::new("permission.test"))
+            routes::resource("/current")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::placeholder().to(current_get))
+                .route(routes::post()
+                    .guard(Authenticated::new())
                     .to(current_post)
                 )
                 .default_service(web::to(default_service))
         )
         .service(
-            web::resource("/current/tenant/set")
-                .route(web::method(http::Method::OPTIONS).to(default_options))
-                .route(web::get().to(current_tenant_set_get))
-                .route(web::post()
-                    .guard(Permission::new("permission.test"))
+            routes::resource("/current/tenant/set")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::placeholder().to(current_tenant_set_get))
+                .route(routes::post()
+                    .guard(Authenticated::new())
                     .to(current_tenant_set_post)
                 )
                 .default_service(web::to(default_service))
         )
         .service(
-            web::resource("/password/set")
-                .route(web::method(http::Method::OPTIONS).to(default_options))
-                .route(web::get().to(user_set_password_get))
-                .route(web::post()
-                    // .guard(Permission::new("permission.test"))
+            routes::resource("/password/set")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::placeholder().to(user_set_password_get))
+                .route(routes::post()
                     .guard(Authenticated::new())
                     .to(user_set_password_post)
                 )
                 .default_service(web::to(default_service))
         )
         .service(
-            web::resource("/tenant/join")
-                .route(web::method(http::Method::OPTIONS).to(default_options))
-                .route(web::get().to(user_tenant_join_get))
-                .route(web::post()
-                    // .guard(Permission::new("permission.test"))
+            routes::resource("/tenant/join")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::placeholder().to(user_tenant_join_get))
+                .route(routes::post()
                     .guard(Authenticated::new())
                     .to(user_tenant_join_post)
                 )
                 .default_service(web::to(default_service))
         )
         .service(
-            web::resource("/tenant/active")
-                .route(web::method(http::Method::OPTIONS).to(default_options))
-                .route(web::get().to(user_tenant_set_active_get))
-                .route(web::post()
-                    // .guard(Permission::new("permission.test"))
+            routes::resource("/tenant/active")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::placeholder().to(user_tenant_set_active_get))
+                .route(routes::post()
                     .guard(Authenticated::new())
+                    .guard(Permission::new("tenants.members.set_active"))
                     .to(user_tenant_set_active_post)
                 )
                 .default_service(web::to(default_service))
         )
         .service(
-            web::resource("/tenant/default")
-                .route(web::method(http::Method::OPTIONS).to(default_options))
-                .route(web::get().to(user_tenant_set_default_get))
-                .route(web::post()
-                    // .guard(Permission::new("permission.test"))
+            routes::resource("/tenant/default")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::placeholder().to(user_tenant_set_default_get))
+                .route(routes::post()
                     .guard(Authenticated::new())
                     .to(user_tenant_set_default_post)
                 )
                 .default_service(web::to(default_service))
         )
+        .service(
+            routes::resource("/sessions")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::placeholder().to(user_sessions_get))
+                .route(routes::post()
+                    .guard(Authenticated::new())
+                    .to(user_sessions_post)
+                )
+                .default_service(web::to(default_service))
+        )
+        .service(
+            routes::resource("/sessions/revoke")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::placeholder().to(user_session_revoke_get))
+                .route(routes::post()
+                    .guard(Authenticated::new())
+                    .to(user_session_revoke_post)
+                )
+                .default_service(web::to(default_service))
+        )
     ;
 }
 
@@ -143,6 +199,18 @@ async fn current_get() -> impl Responder {
     return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
 }
 
+/// current user with tenants and permissions
+#[utoipa::path(
+    post,
+    path = "/current",
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = []))
+)]
 async fn current_post(
     // auth: web::Data<Auth>,
     users: web::Data<Users>,
@@ -156,12 +224,7 @@ async fn current_post(
     match users.user_by_email(&email).await {
         Err(e) => {
             error!("unable to get user: {:?}", e);
-            return HttpResponse::InternalServerError()
-                .json(ApiResponse::new(
-                    false,
-                    "unable to retrieve user",
-                    None
-                ));
+            return error_response(e, "unable to retrieve user");
         }
         Ok(result) => {
             return HttpResponse::Ok()
@@ -175,7 +238,8 @@ async fn current_post(
                             "middle_name": result.middle_name(),
                             "family_name": result.family_name(),
                             "tenant_id": user.tenant_id(),
-                            "tenants": user.tenants()
+                            "tenants": user.tenants(),
+                            "permissions": user.permissions()
                         }
                     }))
                 ))
@@ -188,6 +252,19 @@ async fn current_tenant_set_get() -> impl Responder {
     return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
 }
 
+/// switch the current tenant
+#[utoipa::path(
+    post,
+    path = "/current/tenant/set",
+    request_body = CurrentUserTenantSetRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = []))
+)]
 async fn current_tenant_set_post(
     users: web::Data<Users>,
     user: crate::classes::user::CurrentUser,
@@ -195,24 +272,24 @@ async fn current_tenant_set_post(
 ) -> impl Responder {
     info!("current_tenant_set_post()");
 
-    if let Ok(token) = users.current_user_set_tenant(
+    match users.current_user_set_tenant(
         &user.email(),
-        &params.tenant_id
+        &params.tenant_id,
+        &user.session_id()
     ) {
-        return HttpResponse::Ok()
-            .append_header((AUTHORIZATION, format!("Bearer {}", token)))
-            .json(ApiResponse::new(
-                true,
-                "current tenant selected",
-                None
-            ));
-    } else {
-        return HttpResponse::InternalServerError()
-            .json(ApiResponse::new(
-                false,
-                "current tenant not selected",
-                None
-            ));
+        Err(e) => {
+            error!("unable to select current tenant: {:?}", e);
+            return error_response(e, "current tenant not selected");
+        }
+        Ok(token) => {
+            return HttpResponse::Ok()
+                .append_header((AUTHORIZATION, format!("Bearer {}", token)))
+                .json(ApiResponse::new(
+                    true,
+                    "current tenant selected",
+                    None
+                ));
+        }
     }
 }
 
@@ -221,6 +298,20 @@ async fn user_set_password_get() -> impl Responder {
     return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
 }
 
+/// change the password of the current user
+#[utoipa::path(
+    post,
+    path = "/password/set",
+    request_body = UserPasswordRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 422, description = "request is not valid", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = []))
+)]
 async fn user_set_password_post(
     users: web::Data<Users>,
     user: crate::classes::user::CurrentUser,
@@ -233,16 +324,19 @@ async fn user_set_password_post(
 
     match users.user_set_password(
         &user.id(),
-        &params.password
+        &user.email(),
+        &params.password,
+        &user.session_id()
     ).await {
+        Err(UsersError::PasswordPolicy(failed)) => {
+            return Error::ValidationError(String::from("password does not meet the requirements"))
+                .error_response_with(json!({
+                    "failed": failed
+                }));
+        }
         Err(e) => {
             debug!("user_set_password_post: {:?}", e);
-            return HttpResponse::InternalServerError()
-                .json(ApiResponse::new(
-                    false,
-                    "unable to change password",
-                    None
-                ));
+            return error_response(e, "unable to change password");
         }
         Ok(_) => {
             return HttpResponse::Ok()
@@ -260,6 +354,19 @@ async fn user_tenant_join_get() -> impl Responder {
     return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
 }
 
+/// join a tenant
+#[utoipa::path(
+    post,
+    path = "/tenant/join",
+    request_body = UserTenantJoinRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = []))
+)]
 async fn user_tenant_join_post(
     // clients: web::Data<Clients>,
     tenants: web::Data<Tenants>,
@@ -274,12 +381,7 @@ async fn user_tenant_join_post(
     match tenants.tenant_by_name(&params.tenant).await {
         Err(e) => {
             error!("unable to fetch client by name: {:?}", e);
-            return HttpResponse::InternalServerError()
-                .json(ApiResponse::new(
-                    false,
-                    "an error occured while trying to join a tenant",
-                    None
-                ));
+            return error_response(e, "an error occured while trying to join a tenant");
         }
         Ok(tenant) => {
             debug!("tenant found: {:?}", tenant);
@@ -290,12 +392,7 @@ async fn user_tenant_join_post(
             ).await {
                 Err(e) => {
                     error!("user_tenant_join_post: {:?}", e);
-                    return HttpResponse::InternalServerError()
-                        .json(ApiResponse::new(
-                            false,
-                            "an error occured while trying to join a tenant",
-                            None
-                        ));
+                    return error_response(e, "an error occured while trying to join a tenant");
                 }
                 Ok(result) => {
                     debug!("user_tenant_join_post: {:?}", result);
@@ -316,6 +413,19 @@ async fn user_tenant_set_active_get() -> impl Responder {
     return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
 }
 
+/// set the active status of a member of the current tenant
+#[utoipa::path(
+    post,
+    path = "/tenant/active",
+    request_body = UserTenantSetActiveRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = ["tenants.members.set_active"]))
+)]
 async fn user_tenant_set_active_post(
     tenants: web::Data<Tenants>,
     users: web::Data<Users>,
@@ -325,20 +435,19 @@ async fn user_tenant_set_active_post(
     info!("user_tenant_set_active_post()");
     debug!("user: {:?}", user);
     debug!("params: {:?}", params);
-    
+
+    if params.tenant_id != user.tenant_id() {
+        return tenant_not_current();
+    }
+
     match users.user_tenant_set_active(
         &params.user_id,
-        &params.tenant_id,
+        &user.tenant_id(),
         &params.active
     ).await {
         Err(e) => {
             error!("unable to set user-tenant active: {:?}", e);
-            return HttpResponse::InternalServerError()
-                .json(ApiResponse::new(
-                    false,
-                    "an error occured while trying to set user-tenant active status",
-                    None
-                ));
+            return error_response(e, "an error occured while trying to set user-tenant active status");
         }
         Ok(_) => {
             return HttpResponse::Ok()
@@ -356,6 +465,19 @@ async fn user_tenant_set_default_get() -> impl Responder {
     return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
 }
 
+/// set the default tenant of the current user
+#[utoipa::path(
+    post,
+    path = "/tenant/default",
+    request_body = UserTenantSetDefaultRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = []))
+)]
 async fn user_tenant_set_default_post(
     users: web::Data<Users>,
     user: crate::classes::user::CurrentUser,
@@ -371,18 +493,100 @@ async fn user_tenant_set_default_post(
     ).await {
         Err(e) => {
             error!("unable to set default tenant: {:?}", e);
-            return HttpResponse::InternalServerError()
+            return error_response(e, "an error occured while trying to set default tenant");
+        }
+        Ok(_) => {
+            return HttpResponse::Ok()
                 .json(ApiResponse::new(
-                    false,
-                    "an error occured while trying to set default tenant",
+                    true,
+                    &"Successfully set default tenant",
                     None
                 ));
         }
+    }
+}
+
+async fn user_sessions_get() -> impl Responder {
+    info!("user_sessions_get()");
+    return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
+}
+
+/// list sessions of the current user
+#[utoipa::path(
+    post,
+    path = "/sessions",
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = []))
+)]
+async fn user_sessions_post(
+    users: web::Data<Users>,
+    user: CurrentUser
+) -> impl Responder {
+    info!("user_sessions_post()");
+
+    match users.user_sessions(&user.id()).await {
+        Err(e) => {
+            error!("unable to retrieve user sessions: {:?}", e);
+            return error_response(e, "an error occured while trying to retrieve sessions");
+        }
+        Ok(sessions) => {
+            return HttpResponse::Ok()
+                .json(ApiResponse::new(
+                    true,
+                    "successfully retrieved sessions",
+                    Some(json!({
+                        "current": user.session_id(),
+                        "sessions": sessions
+                    }))
+                ));
+        }
+    }
+}
+
+async fn user_session_revoke_get() -> impl Responder {
+    info!("user_session_revoke_get()");
+    return HttpResponse::Ok().body("Service is up. version: 1.0.0.0.dev");
+}
+
+/// revoke a session of the current user
+#[utoipa::path(
+    post,
+    path = "/sessions/revoke",
+    request_body = UserSessionRevokeRequest,
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = []))
+)]
+async fn user_session_revoke_post(
+    users: web::Data<Users>,
+    user: CurrentUser,
+    params: web::Json<UserSessionRevokeRequest>
+) -> impl Responder {
+    info!("user_session_revoke_post()");
+    debug!("params: {:?}", params);
+
+    match users.user_session_revoke(
+        &user.id(),
+        &params.session_id
+    ).await {
+        Err(e) => {
+            error!("unable to revoke session: {:?}", e);
+            return error_response(e, "an error occured while trying to revoke session");
+        }
         Ok(_) => {
             return HttpResponse::Ok()
                 .json(ApiResponse::new(
                     true,
-                    &"Successfully set default tenant",
+                    "successfully revoked session",
                     None
                 ));
         }
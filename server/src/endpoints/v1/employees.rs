@@ -0,0 +1,214 @@
+use log::{
+    info,
+    debug,
+    error
+};
+
+use actix_web::{
+    HttpResponse,
+    Responder,
+    ResponseError,
+    web
+};
+use serde::{
+    Serialize,
+    Deserialize
+};
+use serde_json::json;
+use validator::Validate;
+use utoipa::{
+    OpenApi,
+    ToSchema
+};
+
+use crate::endpoints::{
+    ApiResponse,
+    error_response,
+    default_options,
+    default_service
+};
+use crate::classes::routes::{
+    self,
+    Routes
+};
+use crate::endpoints::v1::PageQuery;
+use crate::classes::user::CurrentUser;
+use crate::classes::extractors::validated::Validated;
+use crate::classes::validators;
+use crate::classes::guards::permission::Permission;
+
+use common::Error;
+use hr::HrError;
+use hr::employees::{
+    Employees,
+    PAGE_SIZE_MAX
+};
+use people::people::People;
+
+
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+struct EmployeeAddRequest {
+    #[schema(value_type = String, format = "uuid")]
+    pub people_id: uuid::Uuid,
+    #[validate(custom(function = "validators::not_blank"), length(max = 100, message = "must be at most 100 characters"))]
+    pub given_name: String,
+    #[serde(default)]
+    pub middle_name: String,
+    #[validate(custom(function = "validators::not_blank"), length(max = 100, message = "must be at most 100 characters"))]
+    pub family_name: String,
+    #[serde(default)]
+    pub prefix: String,
+    #[serde(default)]
+    pub suffix: String,
+    #[validate(range(min = 1, message = "must be a valid id"))]
+    pub gender_id: i16,
+    #[validate(range(min = 1, message = "must be a valid id"))]
+    pub ethnicity_id: i16,
+    #[validate(range(min = 1, message = "must be a valid id"))]
+    pub marital_state_id: i16
+}
+
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    employees_get,
+    employees_post
+))]
+pub struct ApiDoc;
+
+
+pub fn config(cfg: &mut Routes) {
+    cfg
+        .service(
+            routes::resource("/hr/employees")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::get()
+                    .guard(Permission::new("hr.employees.read"))
+                    .to(employees_get)
+                )
+                .route(routes::post()
+                    .guard(Permission::new("hr.employees.add"))
+                    .to(employees_post)
+                )
+                .default_service(web::to(default_service))
+        )
+    ;
+}
+
+
+/// list a page of employees
+#[utoipa::path(
+    get,
+    path = "/hr/employees",
+    params(PageQuery),
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 422, description = "request is not valid", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = ["hr.employees.read"]), ("api_key" = ["hr.employees.read"]))
+)]
+async fn employees_get(
+    user: CurrentUser,
+    employees: web::Data<Employees>,
+    query: web::Query<PageQuery>
+) -> impl Responder {
+    info!("v1::employees_get()");
+    debug!("query: {:?}", query);
+
+    match employees.page(&user.tenant_id(), &query.page, &query.size).await {
+        Err(HrError::ValidationError) => {
+            return Error::ValidationError(
+                format!("page must be at least 1 and size between 1 and {}", PAGE_SIZE_MAX)
+            ).error_response();
+        }
+        Err(e) => {
+            error!("unable to fetch employee records: {:?}", e);
+            return Error::Internal(String::from("unable to fetch employee records"))
+                .error_response();
+        }
+        Ok(employees) => {
+            return HttpResponse::Ok()
+                .json(ApiResponse::new(
+                    true,
+                    "successfully retrieved employee records",
+                    Some(json!({
+                        "page": query.page,
+                        "size": query.size,
+                        "employees": employees
+                    }))
+                ));
+        }
+    }
+}
+
+/// add an employee
+#[utoipa::path(
+    post,
+    path = "/hr/employees",
+    request_body = EmployeeAddRequest,
+    responses(
+        (status = 201, description = "created", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 422, description = "request is not valid", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = ["hr.employees.add"]), ("api_key" = ["hr.employees.add"]))
+)]
+async fn employees_post(
+    user: CurrentUser,
+    people: web::Data<People>,
+    employees: web::Data<Employees>,
+    params: Validated<EmployeeAddRequest>
+) -> impl Responder {
+    info!("v1::employees_post()");
+    debug!("params: {:?}", params);
+
+    let tenant_id = user.tenant_id();
+    // employees of people without a record get one added in the same
+    // transaction
+    let result = if people.by_id(&params.people_id).await.is_ok() {
+        employees.add(
+            &tenant_id,
+            &params.people_id,
+            &params.people_id
+        ).await
+    } else {
+        employees.add_with_people(
+            &tenant_id,
+            &params.people_id,
+            &common::hr::people::People::new(
+                &params.people_id,
+                &true,
+                &params.given_name,
+                &params.middle_name,
+                &params.family_name,
+                &params.prefix,
+                &params.suffix,
+                &params.gender_id,
+                &params.ethnicity_id,
+                &params.marital_state_id
+            )
+        ).await
+    };
+
+    match result {
+        Err(e) => {
+            error!("unable to add employee record: {:?}", e);
+            return error_response(e, "unable to add employee record");
+        }
+        Ok(_) => {
+            return HttpResponse::Created()
+                .json(ApiResponse::new(
+                    true,
+                    "added employee record",
+                    Some(json!({
+                        "id": params.people_id
+                    }))
+                ));
+        }
+    }
+}
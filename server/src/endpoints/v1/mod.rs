@@ -0,0 +1,42 @@
+// resource oriented routes served under /api/v1. the legacy post endpoints
+// stay in place until the ui has moved over
+pub mod tenants;
+pub mod organizations;
+pub mod employees;
+pub mod roles;
+
+use crate::classes::routes::Routes;
+use serde::{
+    Serialize,
+    Deserialize
+};
+use utoipa::IntoParams;
+
+
+/// page of a collection, pages start at 1
+#[derive(Debug, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PageQuery {
+    #[serde(default = "default_page")]
+    pub page: u32,
+    #[serde(default = "default_size")]
+    pub size: u32
+}
+
+fn default_page() -> u32 {
+    return 1;
+}
+
+fn default_size() -> u32 {
+    return 25;
+}
+
+
+pub fn config(cfg: &mut Routes) {
+    cfg
+        .configure(tenants::config)
+        .configure(organizations::config)
+        .configure(employees::config)
+        .configure(roles::config)
+    ;
+}
@@ -0,0 +1,223 @@
+use log::{
+    info,
+    debug
+};
+
+use actix_web::{
+    HttpResponse,
+    http::header,
+    web
+};
+use serde::{
+    Serialize,
+    Deserialize
+};
+use serde_json::json;
+use validator::Validate;
+use utoipa::{
+    OpenApi,
+    ToSchema
+};
+
+use crate::endpoints::{
+    ApiResponse,
+    default_options,
+    default_service
+};
+use crate::classes::routes::{
+    self,
+    Routes
+};
+use crate::classes::user::CurrentUser;
+use crate::classes::extractors::validated::Validated;
+use crate::classes::validators;
+use crate::classes::guards::permission::Permission;
+
+use common::Error;
+use tenants::organizations::Organizations;
+
+
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+struct OrganizationAddRequest {
+    #[schema(value_type = String, format = "uuid")]
+    pub id: uuid::Uuid,
+    #[validate(custom(function = "validators::not_blank"), length(max = 100, message = "must be at most 100 characters"))]
+    pub name: String,
+    #[serde(default)]
+    #[validate(length(max = 1000, message = "must be at most 1000 characters"))]
+    pub description: String
+}
+
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    organizations_get,
+    organizations_post,
+    organization_get,
+    organization_delete
+))]
+pub struct ApiDoc;
+
+
+pub fn config(cfg: &mut Routes) {
+    cfg
+        .service(
+            routes::resource("/organizations")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::get()
+                    .guard(Permission::new("organizations.read"))
+                    .to(organizations_get)
+                )
+                .route(routes::post()
+                    .guard(Permission::new("organizations.add"))
+                    .to(organizations_post)
+                )
+                .default_service(web::to(default_service))
+        )
+        .service(
+            routes::resource("/organizations/{id}")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::get()
+                    .guard(Permission::new("organizations.read"))
+                    .to(organization_get)
+                )
+                .route(routes::delete()
+                    .guard(Permission::new("organizations.remove"))
+                    .to(organization_delete)
+                )
+                .default_service(web::to(default_service))
+        )
+    ;
+}
+
+
+/// list organizations of the current tenant
+#[utoipa::path(
+    get,
+    path = "/organizations",
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse),
+        (status = 503, description = "service unavailable", body = ApiResponse)
+    ),
+    security(("bearer" = ["organizations.read"]), ("api_key" = ["organizations.read"]))
+)]
+async fn organizations_get(
+    user: CurrentUser,
+    organizations: web::Data<Organizations>
+) -> Result<HttpResponse, Error> {
+    info!("v1::organizations_get()");
+
+    let organizations = organizations.fetch(&user.tenant_id()).await?;
+    return Ok(HttpResponse::Ok()
+        .json(ApiResponse::new(
+            true,
+            "successfully retrieved organizations",
+            Some(json!({
+                "organizations": organizations
+            }))
+        )));
+}
+
+/// add an organization
+#[utoipa::path(
+    post,
+    path = "/organizations",
+    request_body = OrganizationAddRequest,
+    responses(
+        (status = 201, description = "created", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 409, description = "conflict", body = ApiResponse),
+        (status = 422, description = "request is not valid", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse),
+        (status = 503, description = "service unavailable", body = ApiResponse)
+    ),
+    security(("bearer" = ["organizations.add"]), ("api_key" = ["organizations.add"]))
+)]
+async fn organizations_post(
+    user: CurrentUser,
+    organizations: web::Data<Organizations>,
+    params: Validated<OrganizationAddRequest>
+) -> Result<HttpResponse, Error> {
+    info!("v1::organizations_post()");
+    debug!("params: {:?}", params);
+
+    organizations.add(
+        &user.tenant_id(),
+        &params.id,
+        &params.name,
+        &params.description
+    ).await?;
+
+    return Ok(HttpResponse::Created()
+        .insert_header((header::LOCATION, format!("/api/v1/organizations/{}", params.id)))
+        .json(ApiResponse::new(
+            true,
+            "successfully added organization",
+            Some(json!({
+                "id": params.id
+            }))
+        )));
+}
+
+/// retrieve an organization
+#[utoipa::path(
+    get,
+    path = "/organizations/{id}",
+    params(("id" = String, Path, format = "uuid")),
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 404, description = "not found", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse),
+        (status = 503, description = "service unavailable", body = ApiResponse)
+    ),
+    security(("bearer" = ["organizations.read"]), ("api_key" = ["organizations.read"]))
+)]
+async fn organization_get(
+    user: CurrentUser,
+    organizations: web::Data<Organizations>,
+    path: web::Path<uuid::Uuid>
+) -> Result<HttpResponse, Error> {
+    info!("v1::organization_get()");
+
+    let organization = organizations.get(&user.tenant_id(), &path.into_inner()).await?;
+    return Ok(HttpResponse::Ok()
+        .json(ApiResponse::new(
+            true,
+            "successfully retrieved organization",
+            Some(json!({
+                "organization": organization
+            }))
+        )));
+}
+
+/// remove an organization
+#[utoipa::path(
+    delete,
+    path = "/organizations/{id}",
+    params(("id" = String, Path, format = "uuid")),
+    responses(
+        (status = 204, description = "no content"),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 404, description = "not found", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse),
+        (status = 503, description = "service unavailable", body = ApiResponse)
+    ),
+    security(("bearer" = ["organizations.remove"]), ("api_key" = ["organizations.remove"]))
+)]
+async fn organization_delete(
+    user: CurrentUser,
+    organizations: web::Data<Organizations>,
+    path: web::Path<uuid::Uuid>
+) -> Result<HttpResponse, Error> {
+    info!("v1::organization_delete()");
+
+    organizations.remove(&user.tenant_id(), &path.into_inner()).await?;
+    return Ok(HttpResponse::NoContent().finish());
+}
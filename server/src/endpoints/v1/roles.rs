@@ -0,0 +1,317 @@
+use log::{
+    info,
+    debug
+};
+
+use actix_web::{
+    HttpResponse,
+    Responder,
+    http::header,
+    web
+};
+use serde::{
+    Serialize,
+    Deserialize
+};
+use serde_json::json;
+use validator::Validate;
+use utoipa::{
+    OpenApi,
+    ToSchema
+};
+
+use crate::endpoints::{
+    ApiResponse,
+    default_options,
+    default_service
+};
+use crate::classes::routes::{
+    self,
+    Routes
+};
+use crate::endpoints::roles::roles_error_response;
+use crate::classes::user::CurrentUser;
+use crate::classes::extractors::validated::Validated;
+use crate::classes::validators;
+use crate::classes::guards::permission::Permission;
+
+use roles::roles::Roles;
+
+
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+struct RoleAddRequest {
+    #[validate(custom(function = "validators::not_blank"), length(max = 100, message = "must be at most 100 characters"))]
+    pub name: String,
+    #[serde(default)]
+    #[validate(length(max = 1000, message = "must be at most 1000 characters"))]
+    pub description: String
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+struct RolePatchRequest {
+    #[validate(custom(function = "validators::not_blank"), length(max = 100, message = "must be at most 100 characters"))]
+    pub name: Option<String>,
+    #[validate(length(max = 1000, message = "must be at most 1000 characters"))]
+    pub description: Option<String>,
+    pub active: Option<bool>
+}
+
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    roles_get,
+    roles_post,
+    role_get,
+    role_patch,
+    role_delete
+))]
+pub struct ApiDoc;
+
+
+pub fn config(cfg: &mut Routes) {
+    cfg
+        .service(
+            routes::resource("/roles")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::get()
+                    .guard(Permission::new("roles.read"))
+                    .to(roles_get)
+                )
+                .route(routes::post()
+                    .guard(Permission::new("roles.add"))
+                    .to(roles_post)
+                )
+                .default_service(web::to(default_service))
+        )
+        .service(
+            routes::resource("/roles/{id}")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::get()
+                    .guard(Permission::new("roles.read"))
+                    .to(role_get)
+                )
+                .route(routes::patch()
+                    .guard(Permission::new("roles.update"))
+                    .to(role_patch)
+                )
+                .route(routes::delete()
+                    .guard(Permission::new("roles.remove"))
+                    .to(role_delete)
+                )
+                .default_service(web::to(default_service))
+        )
+    ;
+}
+
+
+/// list roles of the current tenant
+#[utoipa::path(
+    get,
+    path = "/roles",
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 404, description = "not found", body = ApiResponse),
+        (status = 409, description = "conflict", body = ApiResponse),
+        (status = 422, description = "request is not valid", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = ["roles.read"]), ("api_key" = ["roles.read"]))
+)]
+async fn roles_get(
+    user: CurrentUser,
+    roles: web::Data<Roles>
+) -> impl Responder {
+    info!("v1::roles_get()");
+
+    match roles.roles_fetch(&user.tenant_id()).await {
+        Err(e) => {
+            return roles_error_response(e, "an error occured while trying to retrieve roles");
+        }
+        Ok(roles) => {
+            return HttpResponse::Ok()
+                .json(ApiResponse::new(
+                    true,
+                    "successfully retrieved roles",
+                    Some(json!({
+                        "roles": roles
+                    }))
+                ));
+        }
+    }
+}
+
+/// add a role
+#[utoipa::path(
+    post,
+    path = "/roles",
+    request_body = RoleAddRequest,
+    responses(
+        (status = 201, description = "created", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 404, description = "not found", body = ApiResponse),
+        (status = 409, description = "conflict", body = ApiResponse),
+        (status = 422, description = "request is not valid", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = ["roles.add"]), ("api_key" = ["roles.add"]))
+)]
+async fn roles_post(
+    user: CurrentUser,
+    roles: web::Data<Roles>,
+    params: Validated<RoleAddRequest>
+) -> impl Responder {
+    info!("v1::roles_post()");
+    debug!("params: {:?}", params);
+
+    match roles.role_add(
+        &user.tenant_id(),
+        &params.name,
+        &params.description
+    ).await {
+        Err(e) => {
+            return roles_error_response(e, "an error occured while trying to add the role");
+        }
+        Ok(role) => {
+            return HttpResponse::Created()
+                .insert_header((header::LOCATION, format!("/api/v1/roles/{}", role.id())))
+                .json(ApiResponse::new(
+                    true,
+                    "successfully added role",
+                    Some(json!({
+                        "role": role
+                    }))
+                ));
+        }
+    }
+}
+
+/// retrieve a role
+#[utoipa::path(
+    get,
+    path = "/roles/{id}",
+    params(("id" = String, Path, format = "uuid")),
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 404, description = "not found", body = ApiResponse),
+        (status = 409, description = "conflict", body = ApiResponse),
+        (status = 422, description = "request is not valid", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = ["roles.read"]), ("api_key" = ["roles.read"]))
+)]
+async fn role_get(
+    user: CurrentUser,
+    roles: web::Data<Roles>,
+    path: web::Path<uuid::Uuid>
+) -> impl Responder {
+    info!("v1::role_get()");
+
+    match roles.role_get(&user.tenant_id(), &path.into_inner()).await {
+        Err(e) => {
+            return roles_error_response(e, "an error occured while trying to retrieve the role");
+        }
+        Ok(role) => {
+            return HttpResponse::Ok()
+                .json(ApiResponse::new(
+                    true,
+                    "successfully retrieved role",
+                    Some(json!({
+                        "role": role
+                    }))
+                ));
+        }
+    }
+}
+
+/// update fields of a role
+#[utoipa::path(
+    patch,
+    path = "/roles/{id}",
+    request_body = RolePatchRequest,
+    params(("id" = String, Path, format = "uuid")),
+    responses(
+        (status = 204, description = "no content"),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 404, description = "not found", body = ApiResponse),
+        (status = 409, description = "conflict", body = ApiResponse),
+        (status = 422, description = "request is not valid", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = ["roles.update"]), ("api_key" = ["roles.update"]))
+)]
+async fn role_patch(
+    user: CurrentUser,
+    roles: web::Data<Roles>,
+    path: web::Path<uuid::Uuid>,
+    params: Validated<RolePatchRequest>
+) -> impl Responder {
+    info!("v1::role_patch()");
+    debug!("params: {:?}", params);
+
+    let tenant_id = user.tenant_id();
+    let role_id = path.into_inner();
+    let role = match roles.role_get(&tenant_id, &role_id).await {
+        Err(e) => {
+            return roles_error_response(e, "an error occured while trying to update the role");
+        }
+        Ok(role) => role
+    };
+
+    if params.name.is_some() || params.description.is_some() {
+        if let Err(e) = roles.role_update(
+            &tenant_id,
+            &role_id,
+            &params.name.clone().unwrap_or(role.name()),
+            &params.description.clone().unwrap_or(role.description())
+        ).await {
+            return roles_error_response(e, "an error occured while trying to update the role");
+        }
+    }
+
+    if let Some(active) = params.active {
+        if let Err(e) = roles.role_set_active(&tenant_id, &role_id, &active).await {
+            return roles_error_response(e, "an error occured while trying to update the role");
+        }
+    }
+
+    return HttpResponse::NoContent().finish();
+}
+
+/// remove a role
+#[utoipa::path(
+    delete,
+    path = "/roles/{id}",
+    params(("id" = String, Path, format = "uuid")),
+    responses(
+        (status = 204, description = "no content"),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 404, description = "not found", body = ApiResponse),
+        (status = 409, description = "conflict", body = ApiResponse),
+        (status = 422, description = "request is not valid", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse)
+    ),
+    security(("bearer" = ["roles.remove"]), ("api_key" = ["roles.remove"]))
+)]
+async fn role_delete(
+    user: CurrentUser,
+    roles: web::Data<Roles>,
+    path: web::Path<uuid::Uuid>
+) -> impl Responder {
+    info!("v1::role_delete()");
+
+    match roles.role_remove(&user.tenant_id(), &path.into_inner()).await {
+        Err(e) => {
+            return roles_error_response(e, "an error occured while trying to remove the role");
+        }
+        Ok(_) => {
+            return HttpResponse::NoContent().finish();
+        }
+    }
+}
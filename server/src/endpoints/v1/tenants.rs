@@ -0,0 +1,286 @@
+use log::{
+    info,
+    debug,
+    error
+};
+
+use actix_web::{
+    HttpResponse,
+    Responder,
+    http::header,
+    web
+};
+use serde::{
+    Serialize,
+    Deserialize
+};
+use serde_json::json;
+use validator::Validate;
+use utoipa::{
+    OpenApi,
+    ToSchema
+};
+
+use crate::endpoints::{
+    ApiResponse,
+    default_options,
+    default_service
+};
+use crate::classes::routes::{
+    self,
+    Routes
+};
+use crate::classes::user::CurrentUser;
+use crate::classes::extractors::validated::Validated;
+use crate::classes::validators;
+use crate::classes::guards::permission::Permission;
+
+use common::Error;
+use tenants::tenants::Tenants;
+use roles::roles::Roles;
+
+
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+struct TenantAddRequest {
+    #[schema(value_type = String, format = "uuid")]
+    pub id: uuid::Uuid,
+    #[validate(custom(function = "validators::not_blank"), length(max = 100, message = "must be at most 100 characters"))]
+    pub name: String,
+    #[validate(custom(function = "validators::slug"))]
+    pub slug: String,
+    #[serde(default)]
+    #[validate(length(max = 1000, message = "must be at most 1000 characters"))]
+    pub description: String
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+struct TenantPatchRequest {
+    #[validate(custom(function = "validators::not_blank"), length(max = 100, message = "must be at most 100 characters"))]
+    pub name: Option<String>,
+    #[validate(custom(function = "validators::slug"))]
+    pub slug: Option<String>,
+    #[validate(length(max = 1000, message = "must be at most 1000 characters"))]
+    pub description: Option<String>
+}
+
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    tenants_get,
+    tenants_post,
+    tenant_get,
+    tenant_patch
+))]
+pub struct ApiDoc;
+
+
+pub fn config(cfg: &mut Routes) {
+    cfg
+        .service(
+            routes::resource("/tenants")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::get()
+                    .guard(Permission::new("tenants.read"))
+                    .to(tenants_get)
+                )
+                .route(routes::post()
+                    .guard(Permission::new("tenants.add"))
+                    .to(tenants_post)
+                )
+                .default_service(web::to(default_service))
+        )
+        .service(
+            routes::resource("/tenants/{id}")
+                .route(routes::method(http::Method::OPTIONS).to(default_options))
+                .route(routes::get()
+                    .guard(Permission::new("tenants.read"))
+                    .to(tenant_get)
+                )
+                .route(routes::patch()
+                    .guard(Permission::new("tenants.update"))
+                    .to(tenant_patch)
+                )
+                .default_service(web::to(default_service))
+        )
+    ;
+}
+
+
+fn tenant_not_found() -> Error {
+    return Error::NotFound(String::from("tenant not found"));
+}
+
+
+/// tenants the user is a member of
+#[utoipa::path(
+    get,
+    path = "/tenants",
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse)
+    ),
+    security(("bearer" = ["tenants.read"]), ("api_key" = ["tenants.read"]))
+)]
+async fn tenants_get(
+    user: CurrentUser
+) -> impl Responder {
+    info!("v1::tenants_get()");
+
+    return HttpResponse::Ok()
+        .json(ApiResponse::new(
+            true,
+            "successfully retrieved tenants",
+            Some(json!({
+                "tenants": user.tenants()
+            }))
+        ));
+}
+
+/// add a tenant, the user becomes its owner
+#[utoipa::path(
+    post,
+    path = "/tenants",
+    request_body = TenantAddRequest,
+    responses(
+        (status = 201, description = "created", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 409, description = "conflict", body = ApiResponse),
+        (status = 422, description = "request is not valid", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse),
+        (status = 503, description = "service unavailable", body = ApiResponse)
+    ),
+    security(("bearer" = ["tenants.add"]), ("api_key" = ["tenants.add"]))
+)]
+async fn tenants_post(
+    user: CurrentUser,
+    data: web::Data<data::Data>,
+    tenants: web::Data<Tenants>,
+    roles: web::Data<Roles>,
+    params: Validated<TenantAddRequest>
+) -> Result<HttpResponse, Error> {
+    info!("v1::tenants_post()");
+    debug!("params: {:?}", params);
+
+    // the tenant and its roles are added together or not at all
+    let uow = data.begin().await?;
+    if let Err(e) = tenants.tenant_add_in(
+        &uow,
+        params.id,
+        &params.name,
+        &params.slug,
+        &params.description
+    ).await {
+        if let Err(e) = uow.rollback().await {
+            error!("unable to roll back tenant: {:?}", e);
+        }
+        return Err(e);
+    }
+
+    // the user adding the tenant becomes its owner
+    if let Err(e) = roles.tenant_roles_seed(&uow, &params.id, &user.id()).await {
+        error!("unable to add tenant roles: {:?}", e);
+        if let Err(e) = uow.rollback().await {
+            error!("unable to roll back tenant: {:?}", e);
+        }
+        return Err(Error::Internal(String::from("an error occured while trying to add the tenant roles")));
+    }
+    uow.commit().await?;
+
+    return Ok(HttpResponse::Created()
+        .insert_header((header::LOCATION, format!("/api/v1/tenants/{}", params.id)))
+        .json(ApiResponse::new(
+            true,
+            "successfully added tenant",
+            Some(json!({
+                "id": params.id
+            }))
+        )));
+}
+
+/// retrieve a tenant
+#[utoipa::path(
+    get,
+    path = "/tenants/{id}",
+    params(("id" = String, Path, format = "uuid")),
+    responses(
+        (status = 200, description = "success", body = ApiResponse),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 404, description = "not found", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse),
+        (status = 503, description = "service unavailable", body = ApiResponse)
+    ),
+    security(("bearer" = ["tenants.read"]), ("api_key" = ["tenants.read"]))
+)]
+async fn tenant_get(
+    user: CurrentUser,
+    tenants: web::Data<Tenants>,
+    path: web::Path<uuid::Uuid>
+) -> Result<HttpResponse, Error> {
+    info!("v1::tenant_get()");
+
+    let tenant_id = path.into_inner();
+    // tenants the user is not a member of are indistinguishable from missing ones
+    if !user.tenants().iter().any(|t| t.id() == tenant_id) {
+        return Err(tenant_not_found());
+    }
+
+    let tenant = tenants.tenant_by_id(&tenant_id).await?;
+    return Ok(HttpResponse::Ok()
+        .json(ApiResponse::new(
+            true,
+            "successfully retrieved tenant",
+            Some(json!({
+                "tenant": tenant
+            }))
+        )));
+}
+
+/// update fields of the current tenant
+#[utoipa::path(
+    patch,
+    path = "/tenants/{id}",
+    request_body = TenantPatchRequest,
+    params(("id" = String, Path, format = "uuid")),
+    responses(
+        (status = 204, description = "no content"),
+        (status = 401, description = "not authenticated", body = ApiResponse),
+        (status = 403, description = "missing permission", body = ApiResponse),
+        (status = 404, description = "not found", body = ApiResponse),
+        (status = 409, description = "conflict", body = ApiResponse),
+        (status = 422, description = "request is not valid", body = ApiResponse),
+        (status = 500, description = "server error", body = ApiResponse),
+        (status = 503, description = "service unavailable", body = ApiResponse)
+    ),
+    security(("bearer" = ["tenants.update"]), ("api_key" = ["tenants.update"]))
+)]
+async fn tenant_patch(
+    user: CurrentUser,
+    tenants: web::Data<Tenants>,
+    path: web::Path<uuid::Uuid>,
+    params: Validated<TenantPatchRequest>
+) -> Result<HttpResponse, Error> {
+    info!("v1::tenant_patch()");
+    debug!("params: {:?}", params);
+
+    let tenant_id = path.into_inner();
+    if !user.tenants().iter().any(|t| t.id() == tenant_id) {
+        return Err(tenant_not_found());
+    }
+    // permissions are granted for the current tenant only
+    if tenant_id != user.tenant_id() {
+        return Err(Error::Forbidden(String::from("tenant is not the current tenant")));
+    }
+
+    tenants.tenant_by_id(&tenant_id).await?;
+    tenants.tenant_patch(
+        &tenant_id,
+        params.name.as_deref(),
+        params.slug.as_deref(),
+        params.description.as_deref()
+    ).await?;
+
+    return Ok(HttpResponse::NoContent().finish());
+}
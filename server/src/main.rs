@@ -3,6 +3,8 @@ extern crate log;
 mod classes;
 mod middleware;
 mod endpoints;
+mod openapi;
+mod tls;
 
 // use std::sync::Arc;
 use std::io::{
@@ -12,6 +14,7 @@ use std::io::{
 
 use log::{
     info,
+    warn,
     error,
     debug
 };
@@ -26,7 +29,10 @@ use configuration::{
     ProviderType,
     ApplicationConfiguration
 };
+use data::pg::migrations::SchemaStatus;
 use tokenizer::Tokenizer;
+use utoipa::OpenApi;
+use utoipa_swagger_ui::SwaggerUi;
 
 
 
@@ -35,117 +41,213 @@ async fn main() -> std::io::Result<()> {
     env_logger::init();
     info!("starting up...");
 
-    if let Some(cfg) = ApplicationConfiguration::get() {
-        debug!("configuration: {:?}", cfg);
-        // tokenizer
-        let tokenizer = Tokenizer::new(&cfg.jwt.secret);
-
-        // mailer
-        let mailer = mailer::Mailer::new(
-            &cfg.mailer.host,
-            &cfg.mailer.user,
-            &cfg.mailer.password
-        );
-
-        let data = data::Data::new(cfg.clone());
-
-        // auth module
-        let bind_host = cfg.bind_host.clone();
-        let bind_port = cfg.bind_port.clone();
-
-        let auth = auth::auth::Auth::new(
-            cfg.clone(),
-            mailer.clone(),
-            tokenizer.clone(),
-            data.clone()
-        );
-
-        let users = users::users::Users::new(
-            cfg.clone(),
-            mailer.clone(),
-            tokenizer.clone(),
-            data.clone()
-        );
-
-        let currencies = util::currencies::Currencies::new(
-            cfg.clone(),
-            data.clone()
-        );
-
-        let countries = util::countries::Countries::new(
-            cfg.clone(),
-            data.clone()
-        );
-
-        let people = people::people::People::new(
-            cfg.clone(),
-            data.clone()
-        );
-
-        let tenants = tenants::tenants::Tenants::new(
-            cfg.clone(),
-            data.clone()
-        );
-
-        let organizations = tenants::organizations::Organizations::new(
-            cfg.clone(),
-            data.clone()
-        );
-
-        let hr = hr::Hr::new(
-            cfg.clone(),
-            people.clone(),
-            data.clone()
-        );
-        let employees = hr.employees();
-
-
-        let server = HttpServer::new(move || {
-            App::new()
-                .app_data(web::Data::new(cfg.clone()))
-                .app_data(web::Data::new(mailer.clone()))
-                .app_data(web::Data::new(tokenizer.clone()))
-                .app_data(web::Data::new(data.clone()))
-                .app_data(web::Data::new(auth.clone()))
-                .app_data(web::Data::new(users.clone()))
-                .app_data(web::Data::new(currencies.clone()))
-                .app_data(web::Data::new(countries.clone()))
-                .app_data(web::Data::new(people.clone()))
-                .app_data(web::Data::new(tenants.clone()))
-                .app_data(web::Data::new(organizations.clone() ))
-
-                // .app_data(web::Data::new(hr.clone()))
-                .app_data(web::Data::new(employees.clone()))
-                
-                .wrap(crate::middleware::cors::CORS::new())
-                .wrap(crate::middleware::auth::AuthUser::new(&cfg))
-
-                .service(web::scope("/status").configure(crate::endpoints::status::config))
-                .service(web::scope("/countries").configure(crate::endpoints::common::countries::config))
-                .service(web::scope("/currencies").configure(crate::endpoints::common::currencies::config))
-                .service(web::scope("/auth").configure(crate::endpoints::auth::config))
-                .service(web::scope("/user").configure(crate::endpoints::user::config))
-                // .service(web::scope("/clients").configure(crate::endpoints::clients::client::config))
-
-                .service(web::scope("/tenants").configure(crate::endpoints::tenants::tenants::config))
-                .service(web::scope("/organizations").configure(crate::endpoints::tenants::organizations::config))
-
-                .service(web::scope("accounting/accounts").configure(crate::endpoints::accounting::accounts::config))
-
-                // .service(web::scope("/crms/people").configure(crate::endpoints::crms::people::config))
-
-                .service(web::scope("/hr/employees").configure(crate::endpoints::hr::employees::config))
-        })
-        .workers(2)
-        .bind(format!("{}:{}", bind_host, bind_port))?
-        .run();
-    
-        info!("listening on http://{}:{}", bind_host, bind_port);
-        return server.await;
-    } else {
-        error!("no configuration loaded");
+    let cfg = match ApplicationConfiguration::load() {
+        Err(e) => {
+            for problem in e.problems() {
+                error!("configuration: {}", problem);
+            }
+            return Err(Error::new(ErrorKind::InvalidInput, "invalid configuration"));
+        }
+        Ok(cfg) => cfg
+    };
+    debug!("configuration: {:?}", cfg);
+
+    // tokenizer
+    let tokenizer = match Tokenizer::from_configuration(&cfg.jwt) {
+        Err(e) => {
+            error!("unable to initialize tokenizer: {}", e);
+            return Err(Error::new(ErrorKind::Other, "unable to initialize tokenizer"));
+        }
+        Ok(tokenizer) => tokenizer
+    };
+
+    // mailer
+    let mailer = mailer::Mailer::new(
+        &cfg.mailer.host,
+        &cfg.mailer.user,
+        &cfg.mailer.password
+    );
+
+    let data = data::Data::new(cfg.clone());
+
+    // --migrate applies pending migrations and exits. --baseline <n>
+    // adopts a database whose schema matches version n but is unversioned
+    if std::env::args().any(|arg| arg == "--migrate") {
+        match data.migrate(baseline_arg()?).await {
+            Err(e) => {
+                error!("unable to migrate database schema: {:?}", e);
+                return Err(Error::new(ErrorKind::Other, "unable to migrate database schema"));
+            }
+            Ok(applied) => {
+                info!("applied migrations: {:?}", applied);
+                return Ok(());
+            }
+        }
     }
 
-    info!("exiting");
-    return Ok(());
+    // refuse to serve with a schema older than the embedded migrations
+    match data.schema_status().await {
+        Err(e) => {
+            error!("unable to check database schema version: {:?}", e);
+            return Err(Error::new(ErrorKind::Other, "unable to check database schema version"));
+        }
+        Ok(SchemaStatus::Behind { current, latest }) => {
+            error!("database schema version {} is behind {}, run with --migrate", current, latest);
+            if current == 0 {
+                error!("a database created with the ideas-postgres scripts is adopted with --migrate --baseline 5");
+            }
+            return Err(Error::new(ErrorKind::Other, "database schema is behind"));
+        }
+        Ok(SchemaStatus::Ahead { current, latest }) => {
+            warn!("database schema version {} is ahead of {}", current, latest);
+        }
+        Ok(SchemaStatus::Current(version)) => {
+            info!("database schema version {}", version);
+        }
+    }
+
+    // auth module
+    let bind_host = cfg.bind_host.clone();
+    let bind_port = cfg.bind_port.clone();
+    let listener_tls = cfg.tls.clone();
+    let metrics_host = cfg.metrics.bind_host.clone();
+    let metrics_port = cfg.metrics.bind_port.clone();
+
+    let auth = auth::auth::Auth::new(
+        cfg.clone(),
+        mailer.clone(),
+        tokenizer.clone(),
+        data.clone()
+    );
+
+    // permissions cached by users and discarded by roles when they change
+    let permissions = common::cache::PermissionCache::new();
+
+    let users = users::users::Users::new(
+        cfg.clone(),
+        mailer.clone(),
+        tokenizer.clone(),
+        data.clone(),
+        permissions.clone()
+    );
+
+    let currencies = util::currencies::Currencies::new(
+        cfg.clone(),
+        data.clone()
+    );
+
+    let countries = util::countries::Countries::new(
+        cfg.clone(),
+        data.clone()
+    );
+
+    let people = people::people::People::new(
+        cfg.clone(),
+        data.clone()
+    );
+
+    let tenants = tenants::tenants::Tenants::new(
+        cfg.clone(),
+        data.clone()
+    );
+
+    let organizations = tenants::organizations::Organizations::new(
+        cfg.clone(),
+        data.clone()
+    );
+
+    let api_keys = tenants::api_keys::ApiKeys::new(
+        cfg.clone(),
+        data.clone()
+    );
+
+    let roles = roles::roles::Roles::new(
+        cfg.clone(),
+        data.clone(),
+        permissions.clone()
+    );
+
+    let hr = hr::Hr::new(
+        cfg.clone(),
+        people.clone(),
+        data.clone()
+    );
+    let employees = hr.employees();
+
+
+    let openapi = crate::openapi::ApiDoc::openapi();
+
+    // metrics are served apart from the api, on a listener that is not
+    // exposed to its clients
+    let metrics_data = data.clone();
+    let metrics = HttpServer::new(move || {
+        App::new()
+            .app_data(web::Data::new(metrics_data.clone()))
+            .configure(crate::endpoints::metrics_config)
+    })
+    .workers(1);
+    info!("serving metrics on http://{}:{}/metrics", metrics_host, metrics_port);
+    let metrics = metrics.bind(format!("{}:{}", metrics_host, metrics_port))?;
+
+    let server = HttpServer::new(move || {
+        App::new()
+            .app_data(web::Data::new(cfg.clone()))
+            .app_data(web::Data::new(mailer.clone()))
+            .app_data(web::Data::new(tokenizer.clone()))
+            .app_data(web::Data::new(data.clone()))
+            .app_data(web::Data::new(auth.clone()))
+            .app_data(web::Data::new(users.clone()))
+            .app_data(web::Data::new(currencies.clone()))
+            .app_data(web::Data::new(countries.clone()))
+            .app_data(web::Data::new(people.clone()))
+            .app_data(web::Data::new(tenants.clone()))
+            .app_data(web::Data::new(organizations.clone() ))
+            .app_data(web::Data::new(api_keys.clone()))
+            .app_data(web::Data::new(roles.clone()))
+
+            // .app_data(web::Data::new(hr.clone()))
+            .app_data(web::Data::new(employees.clone()))
+            
+            .wrap(crate::middleware::cors::CORS::new())
+            .wrap(crate::middleware::auth::AuthUser::new(&cfg))
+            .wrap(crate::middleware::metrics::Metrics::new())
+
+            .service(SwaggerUi::new("/swagger-ui/{_:.*}").url("/openapi.json", openapi.clone()))
+
+            .configure(crate::endpoints::config)
+    })
+    .workers(2);
+
+    let server = match &listener_tls {
+        None => {
+            info!("listening on http://{}:{}", bind_host, bind_port);
+            server.bind(format!("{}:{}", bind_host, bind_port))?
+        }
+        Some(tls) => {
+            info!("listening on https://{}:{}", bind_host, bind_port);
+            server.bind_rustls_0_23(format!("{}:{}", bind_host, bind_port), crate::tls::server_config(&tls)?)?
+        }
+    };
+    return futures::future::try_join(server.run(), metrics.run()).await.map(|_| ());
+}
+
+/// version given with --baseline
+fn baseline_arg() -> std::io::Result<Option<i32>> {
+    let args: Vec<String> = std::env::args().collect();
+    match args.iter().position(|arg| arg == "--baseline") {
+        None => {
+            return Ok(None);
+        }
+        Some(i) => {
+            match args.get(i + 1).and_then(|version| version.parse::<i32>().ok()) {
+                None => {
+                    error!("--baseline requires a migration version");
+                    return Err(Error::new(ErrorKind::InvalidInput, "invalid baseline"));
+                }
+                Some(version) => {
+                    return Ok(Some(version));
+                }
+            }
+        }
+    }
 }
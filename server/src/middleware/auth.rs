@@ -4,8 +4,15 @@ use log::{
     debug,
     error
 };
-use tokenizer::Tokenizer;
+use tokenizer::{
+    Tokenizer,
+    TokenError
+};
 use users::users::Users;
+use tenants::{
+    tenants::Tenants,
+    api_keys::ApiKeys
+};
 
 use std::default;
 use std::rc::Rc;
@@ -17,6 +24,7 @@ use http::{
     method::Method,
     header
 };
+use actix_http::header::HeaderValue;
 
 use actix_web::{
     HttpMessage,
@@ -104,21 +112,86 @@ where
             debug!("AuthMiddleware::call() [2]");
 
             let mut user = CurrentUser::anonymous();
-            if request.method() == Method::POST {
+            let mut token_error: Option<TokenError> = None;
+            // cors preflight requests never carry credentials
+            if request.method() != Method::OPTIONS {
                 let mut token_value = String::from("");
                 let mut email = String::from("");
                 let mut tenant_id: uuid::Uuid = uuid::Uuid::nil();
+                let mut session_id: uuid::Uuid = uuid::Uuid::nil();
+
+                let mut api_key_value = String::from("");
 
                 if let Some(header_value) = request.headers().get(header::AUTHORIZATION) {
-                    token_value = header_value.to_str().unwrap().replace("Bearer", "").trim().to_owned();
+                    // api keys are presented as "Authorization: ApiKey <key>"
+                    match header_value.to_str().unwrap_or("").trim().split_once(' ') {
+                        Some((scheme, value)) if scheme.eq_ignore_ascii_case("ApiKey") => {
+                            api_key_value = value.trim().to_owned();
+                        }
+                        Some((scheme, value)) if scheme.eq_ignore_ascii_case("Bearer") => {
+                            token_value = value.trim().to_owned();
+                        }
+                        _ => {}
+                    }
+                }
+
+                if !api_key_value.is_empty() {
+                    if let Some(api_keys) = request.app_data::<web::Data<ApiKeys>>() {
+                        match api_keys.authenticate(&api_key_value).await {
+                            Err(e) => {
+                                error!("unable to authenticate api key: {:?}", e);
+                            }
+                            Ok(None) => {
+                                info!("rejected invalid api key");
+                                token_error = Some(TokenError::InvalidToken);
+                            }
+                            Ok(Some(api_key)) => {
+                                let mut tenants: Vec<Tenant> = Vec::new();
+                                if let Some(t) = request.app_data::<web::Data<Tenants>>() {
+                                    match t.tenant_by_id(&api_key.tenant_id()).await {
+                                        Err(e) => {
+                                            error!("unable to retrieve api key tenant: {:?}", e);
+                                        }
+                                        Ok(tenant) => {
+                                            tenants.push(tenant);
+                                        }
+                                    }
+                                }
+                                user = CurrentUser::api_key(&api_key, tenants);
+                            }
+                        }
+                    } else {
+                        error!("api keys not found");
+                    }
                 }
 
                 if !token_value.is_empty() {
                     if let Some(tokenizer) = request.app_data::<web::Data<Tokenizer>>() {
-                        if tokenizer.is_valid(&token_value) {
-                            if let Ok(claims) = tokenizer.get_claims(&token_value) {
+                        match tokenizer.validate(&token_value) {
+                            Err(e) => {
+                                match e {
+                                    TokenError::Expired => {
+                                        info!("rejected expired token");
+                                    }
+                                    TokenError::NotYetValid => {
+                                        info!("rejected token that is not yet valid");
+                                    }
+                                    TokenError::InvalidAudience => {
+                                        info!("rejected token with an invalid audience");
+                                    }
+                                    TokenError::InvalidIssuer => {
+                                        info!("rejected token with an invalid issuer");
+                                    }
+                                    _ => {
+                                        info!("rejected invalid token: {}", e);
+                                    }
+                                }
+                                token_error = Some(e);
+                            }
+                            Ok(claims) => {
                                 email = claims.email().clone();
                                 tenant_id = claims.tenant().clone();
+                                session_id = claims.session().clone();
                             }
                         }
                     } else {
@@ -132,31 +205,52 @@ where
                             Err(e) => {
                                 error!("unable to retrieve user data: {:?}", e);
                             }
+                            Ok(user_data) if !user_data.active() => {
+                                info!("rejected token of inactive user");
+                                token_error = Some(TokenError::Revoked);
+                            }
                             Ok(user_data) => {
                                 let user_id = user_data.id();
 
-                                let mut tenants: Vec<Tenant> = Vec::new();
-                                if let Ok(mut result) = users.user_tenants(&user_id).await {
-                                    // debug!("result: {:?}", result);
-                                    tenants.append(&mut result);
-                                }
+                                if matches!(users.session_active(&session_id).await, Ok(true)) {
+                                    let mut tenants: Vec<Tenant> = Vec::new();
+                                    if let Ok(mut result) = users.user_tenants(&user_id).await {
+                                        // debug!("result: {:?}", result);
+                                        tenants.append(&mut result);
+                                    }
+
+                                    let mut default_tenant_id: uuid::Uuid = uuid::Uuid::nil();
+                                    if tenant_id.is_nil() {
+                                        if let Ok(result) = users.user_tenant_default(&user_id).await {
+                                            debug!("result: {:?}", result);
+                                            default_tenant_id = result.id();
+                                        }
+                                    } else {
+                                        default_tenant_id = tenant_id;
+                                    }
 
-                                let mut default_tenant_id: uuid::Uuid = uuid::Uuid::nil();
-                                if tenant_id.is_nil() {
-                                    if let Ok(result) = users.user_tenant_default(&user_id).await {
-                                        debug!("result: {:?}", result);
-                                        default_tenant_id = result.id();
+                                    let mut permissions: Vec<String> = Vec::new();
+                                    match users.user_permissions(&user_id, &default_tenant_id).await {
+                                        Err(e) => {
+                                            error!("unable to retrieve user permissions: {:?}", e);
+                                        }
+                                        Ok(mut result) => {
+                                            permissions.append(&mut result);
+                                        }
                                     }
+
+                                    user = CurrentUser::new(
+                                        &user_data.id(),
+                                        &user_data.email(),
+                                        &default_tenant_id,
+                                        &session_id,
+                                        tenants,
+                                        permissions
+                                    );
                                 } else {
-                                    default_tenant_id = tenant_id;
+                                    info!("rejected token of revoked session");
+                                    token_error = Some(TokenError::Revoked);
                                 }
-
-                                user = CurrentUser::new(
-                                    &user_data.id(),
-                                    &user_data.email(),
-                                    &default_tenant_id,
-                                    tenants
-                                );
                             }
                         }
                     } else {
@@ -172,8 +266,15 @@ where
                     error!("an error occured while handling the request: {:?}", e);
                     return Err(e);
                 }
-                Ok(service_result) => {
+                Ok(mut service_result) => {
                     // debug!("AuthMiddleware::call() result: {:?}", service_result);
+                    if let Some(e) = token_error {
+                        // RFC 6750 section 3.1
+                        let challenge = format!("Bearer error=\"invalid_token\", error_description=\"{}\"", e);
+                        if let Ok(value) = HeaderValue::from_str(&challenge) {
+                            service_result.headers_mut().insert(header::WWW_AUTHENTICATE, value);
+                        }
+                    }
                     return Ok(service_result);
                 }
             }
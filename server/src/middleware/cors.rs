@@ -95,7 +95,7 @@ where
             }
 
             if !res.headers().contains_key(ACCESS_CONTROL_ALLOW_METHODS) {
-                res.headers_mut().insert(ACCESS_CONTROL_ALLOW_METHODS, HeaderValue::from_static("POST, OPTIONS"));
+                res.headers_mut().insert(ACCESS_CONTROL_ALLOW_METHODS, HeaderValue::from_static("GET, POST, PUT, PATCH, DELETE, OPTIONS"));
             }
 
             if !res.headers().contains_key(ACCESS_CONTROL_ALLOW_HEADERS) {
@@ -0,0 +1,131 @@
+use log::{
+    debug
+};
+
+use std::task::{ Context, Poll };
+use std::future::{ ready, Ready };
+use std::time::Instant;
+use futures::future::LocalBoxFuture;
+
+use actix_web::{
+    error::Error,
+    http::Method,
+    dev::{
+        Service, 
+        Transform, 
+        ServiceRequest, 
+        ServiceResponse
+    }
+};
+
+
+/// route label of requests that match no resource
+const UNMATCHED: &str = "unmatched";
+
+/// method label of requests using an extension method
+const OTHER: &str = "other";
+
+
+/// count requests and record their latency by method, route pattern and
+/// status
+pub struct Metrics {}
+
+pub struct MetricsMiddleware<S> {
+    service: S,
+}
+
+
+/// label of a request method. any token is accepted as a method, so
+/// extension methods share one label to keep the number of series bounded
+fn method_label(method: &Method) -> &'static str {
+    match *method {
+        Method::GET => "GET",
+        Method::HEAD => "HEAD",
+        Method::POST => "POST",
+        Method::PUT => "PUT",
+        Method::PATCH => "PATCH",
+        Method::DELETE => "DELETE",
+        Method::OPTIONS => "OPTIONS",
+        Method::CONNECT => "CONNECT",
+        Method::TRACE => "TRACE",
+        _ => OTHER
+    }
+}
+
+
+impl Metrics {
+    pub fn new() -> Self {
+        return Metrics {};
+    }
+}
+
+
+impl <S, B> Transform<S, ServiceRequest> for Metrics
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = MetricsMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        return ready(Ok(MetricsMiddleware {
+            service
+        }));
+    }
+}
+
+
+impl <S, B> Service<ServiceRequest> for MetricsMiddleware<S> 
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&self, context: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        return self.service.poll_ready(context);
+    }
+
+    fn call(&self, request: ServiceRequest) -> Self::Future {
+        let start = Instant::now();
+        let method = method_label(request.method());
+        // the pattern, not the path, keeps the number of series bounded
+        let route = request.match_pattern().unwrap_or_else(|| String::from(UNMATCHED));
+        let fut = self.service.call(request);
+
+        return Box::pin(async move {
+            let result = fut.await;
+
+            let status = match &result {
+                Err(e) => e.as_response_error().status_code(),
+                Ok(res) => res.status()
+            };
+            debug!("MetricsMiddleware::call() {} {} {}", method, route, status);
+            ::metrics::http_request(method, &route, status.as_u16(), start.elapsed().as_secs_f64());
+
+            return result;
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_method_label() {
+        assert_eq!(method_label(&Method::GET), "GET");
+        assert_eq!(method_label(&Method::DELETE), "DELETE");
+        assert_eq!(method_label(&Method::from_bytes(b"PROPFIND").unwrap()), OTHER);
+        assert_eq!(method_label(&Method::from_bytes(b"X-RANDOM-1234").unwrap()), OTHER);
+    }
+}
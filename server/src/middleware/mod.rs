@@ -1,2 +1,3 @@
 pub mod cors;
-pub mod auth;
\ No newline at end of file
+pub mod auth;
+pub mod metrics;
\ No newline at end of file
@@ -0,0 +1,165 @@
+use utoipa::{
+    Modify,
+    OpenApi,
+    openapi::security::{
+        ApiKey,
+        ApiKeyValue,
+        HttpAuthScheme,
+        HttpBuilder,
+        SecurityScheme
+    }
+};
+
+use crate::endpoints;
+
+
+/// openapi document generated from the endpoint annotations. every scope
+/// in endpoints::SCOPES is nested here under the same path
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "ideas", description = "ideas api server"),
+    nest(
+        (path = "/status", api = endpoints::status::ApiDoc, tags = ["status"]),
+        (path = "/countries", api = endpoints::common::countries::ApiDoc, tags = ["common"]),
+        (path = "/currencies", api = endpoints::common::currencies::ApiDoc, tags = ["common"]),
+        (path = "/auth", api = endpoints::auth::ApiDoc, tags = ["auth"]),
+        (path = "/user", api = endpoints::user::ApiDoc, tags = ["user"]),
+        (path = "/tenants", api = endpoints::tenants::tenants::ApiDoc, tags = ["tenants"]),
+        (path = "/organizations", api = endpoints::tenants::organizations::ApiDoc, tags = ["organizations"]),
+        (path = "/api-keys", api = endpoints::tenants::api_keys::ApiDoc, tags = ["api keys"]),
+        (path = "/roles", api = endpoints::roles::ApiDoc, tags = ["roles"]),
+        (path = "/accounting/accounts", api = endpoints::accounting::accounts::ApiDoc, tags = ["accounting"]),
+        (path = "/hr/employees", api = endpoints::hr::employees::ApiDoc, tags = ["hr"]),
+        (path = "/api/v1", api = endpoints::v1::tenants::ApiDoc, tags = ["v1 tenants"]),
+        (path = "/api/v1", api = endpoints::v1::organizations::ApiDoc, tags = ["v1 organizations"]),
+        (path = "/api/v1", api = endpoints::v1::employees::ApiDoc, tags = ["v1 hr"]),
+        (path = "/api/v1", api = endpoints::v1::roles::ApiDoc, tags = ["v1 roles"])
+    ),
+    modifiers(&SecuritySchemes)
+)]
+pub struct ApiDoc;
+
+
+/// access tokens are sent as "Authorization: Bearer <token>" and api keys
+/// as "Authorization: ApiKey <key>". security requirements list the
+/// permission an endpoint requires
+struct SecuritySchemes;
+
+impl Modify for SecuritySchemes {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                "bearer",
+                SecurityScheme::Http(
+                    HttpBuilder::new()
+                        .scheme(HttpAuthScheme::Bearer)
+                        .bearer_format("JWT")
+                        .build()
+                )
+            );
+            components.add_security_scheme(
+                "api_key",
+                SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::with_description(
+                    "Authorization",
+                    "ApiKey <key>"
+                )))
+            );
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web::{
+        App,
+        http::{
+            Method,
+            StatusCode
+        },
+        test::{
+            TestRequest,
+            call_service,
+            init_service,
+            read_body_json
+        }
+    };
+    use utoipa::openapi::path::HttpMethod;
+    use utoipa_swagger_ui::SwaggerUi;
+
+    use crate::classes::routes::Routes;
+
+    fn method(method: &HttpMethod) -> Method {
+        match method {
+            HttpMethod::Get => Method::GET,
+            HttpMethod::Post => Method::POST,
+            HttpMethod::Put => Method::PUT,
+            HttpMethod::Delete => Method::DELETE,
+            HttpMethod::Options => Method::OPTIONS,
+            HttpMethod::Head => Method::HEAD,
+            HttpMethod::Patch => Method::PATCH,
+            HttpMethod::Trace => Method::TRACE
+        }
+    }
+
+    /// every route served must be documented with its method. preflight
+    /// requests and placeholders are not part of the api
+    #[test]
+    fn test_routes_documented() {
+        let mut served = Vec::new();
+        for (prefix, config) in endpoints::SCOPES {
+            let mut routes = Routes::new(prefix);
+            routes.configure(config);
+            for entry in routes.entries() {
+                if entry.method != Method::OPTIONS && !entry.placeholder {
+                    served.push((entry.method.clone(), entry.path.clone()));
+                }
+            }
+        }
+        assert!(!served.is_empty());
+
+        let mut documented = Vec::new();
+        for (path, item) in ApiDoc::openapi().paths.paths {
+            for (m, operation) in [
+                (HttpMethod::Get, &item.get),
+                (HttpMethod::Post, &item.post),
+                (HttpMethod::Put, &item.put),
+                (HttpMethod::Delete, &item.delete),
+                (HttpMethod::Patch, &item.patch)
+            ] {
+                if operation.is_some() {
+                    documented.push((method(&m), path.trim_end_matches('/').to_string()));
+                }
+            }
+        }
+
+        let missing: Vec<&(Method, String)> = served.iter()
+            .filter(|r| !documented.contains(r))
+            .collect();
+        assert!(missing.is_empty(), "routes without documentation: {:?}", missing);
+        let stale: Vec<&(Method, String)> = documented.iter()
+            .filter(|r| !served.contains(r))
+            .collect();
+        assert!(stale.is_empty(), "documented routes not served: {:?}", stale);
+    }
+
+    #[actix_web::test]
+    async fn test_openapi_served() {
+        let app = init_service(
+            App::new().service(SwaggerUi::new("/swagger-ui/{_:.*}").url("/openapi.json", ApiDoc::openapi()))
+        ).await;
+
+        let request = TestRequest::get().uri("/openapi.json").to_request();
+        let response = call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value = read_body_json(response).await;
+        assert!(body["paths"]["/api/v1/tenants/{id}"]["patch"].is_object());
+        assert!(body["components"]["securitySchemes"]["bearer"].is_object());
+
+        let request = TestRequest::get().uri("/swagger-ui/").to_request();
+        let response = call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
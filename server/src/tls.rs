@@ -0,0 +1,67 @@
+use log::{
+    info,
+    error
+};
+use std::fs::File;
+use std::io::{
+    BufReader,
+    Error,
+    ErrorKind
+};
+use std::sync::Arc;
+
+use rustls::{
+    ServerConfig,
+    crypto::ring,
+    pki_types::{
+        CertificateDer,
+        PrivateKeyDer
+    }
+};
+
+use configuration::ListenerTls;
+
+
+/// build the tls configuration of the http listener
+pub fn server_config(tls: &ListenerTls) -> std::io::Result<ServerConfig> {
+    info!("tls::server_config()");
+
+    let chain = certificates(&tls.cert_file)?;
+    let key = private_key(&tls.key_file)?;
+    match ServerConfig::builder_with_provider(Arc::new(ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .and_then(|builder| builder.with_no_client_auth().with_single_cert(chain, key)) {
+            Err(e) => {
+                error!("unable to use certificate {}: {:?}", tls.cert_file, e);
+                return Err(Error::new(ErrorKind::InvalidData, "unable to use listener certificate"));
+            }
+            Ok(config) => {
+                return Ok(config);
+            }
+        }
+}
+
+/// read the certificate chain of a PEM file
+fn certificates(path: &str) -> std::io::Result<Vec<CertificateDer<'static>>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let certs = rustls_pemfile::certs(&mut reader).collect::<Result<Vec<_>, _>>()?;
+    if certs.is_empty() {
+        error!("no certificate in {}", path);
+        return Err(Error::new(ErrorKind::InvalidData, "no certificate found"));
+    }
+    return Ok(certs);
+}
+
+/// read the first private key of a PEM file
+fn private_key(path: &str) -> std::io::Result<PrivateKeyDer<'static>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    match rustls_pemfile::private_key(&mut reader)? {
+        None => {
+            error!("no private key in {}", path);
+            return Err(Error::new(ErrorKind::InvalidData, "no private key found"));
+        }
+        Some(key) => {
+            return Ok(key);
+        }
+    }
+}
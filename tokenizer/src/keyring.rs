@@ -0,0 +1,335 @@
+use log::{
+    error
+};
+
+use std::fs;
+
+use ed25519_dalek::{
+    SigningKey,
+    pkcs8::{
+        EncodePrivateKey,
+        EncodePublicKey,
+        spki::der::pem::LineEnding
+    }
+};
+use rand_core::OsRng;
+
+use base64::{
+    Engine,
+    engine::general_purpose::URL_SAFE_NO_PAD
+};
+use jsonwebtoken::{
+    Algorithm,
+    DecodingKey,
+    DecodingKeyKind,
+    EncodingKey,
+    jwk::{
+        AlgorithmParameters,
+        CommonParameters,
+        EllipticCurve,
+        Jwk,
+        JwkSet,
+        KeyAlgorithm,
+        OctetKeyPairParameters,
+        OctetKeyPairType,
+        PublicKeyUse,
+        RSAKeyParameters,
+        RSAKeyType
+    }
+};
+use rsa::{
+    RsaPublicKey,
+    pkcs1::DecodeRsaPublicKey,
+    traits::PublicKeyParts
+};
+
+use configuration::{
+    JWT,
+    JWTKey
+};
+
+use crate::TokenError;
+
+
+/// a signing or verification key identified by its kid
+#[derive(Clone)]
+pub struct Key {
+    kid: String,
+    algorithm: Algorithm,
+    encoding: Option<EncodingKey>,
+    decoding: DecodingKey,
+    jwk: Option<Jwk>
+}
+
+impl std::fmt::Debug for Key {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return f.debug_struct("Key")
+            .field("kid", &self.kid)
+            .field("algorithm", &self.algorithm)
+            .field("signing", &self.encoding.is_some())
+            .finish();
+    }
+}
+
+impl Key {
+
+    pub fn kid(&self) -> String {
+        return self.kid.clone();
+    }
+
+    pub fn algorithm(&self) -> Algorithm {
+        return self.algorithm;
+    }
+
+    pub fn encoding(&self) -> Option<&EncodingKey> {
+        return self.encoding.as_ref();
+    }
+
+    pub fn decoding(&self) -> &DecodingKey {
+        return &self.decoding;
+    }
+
+    /// public key in jwk format. shared secrets are never published
+    pub fn jwk(&self) -> Option<Jwk> {
+        return self.jwk.clone();
+    }
+}
+
+
+/// set of keys a Tokenizer accepts when verifying tokens
+#[derive(Debug, Clone, Default)]
+pub struct Keyring {
+    keys: Vec<Key>
+}
+
+impl Keyring {
+
+    pub fn new() -> Self {
+        return Self {
+            keys: Vec::new()
+        };
+    }
+
+    /// build a keyring from the jwt configuration. if no keys are
+    /// configured, the shared secret is used as an HS256 key with kid "default"
+    pub fn from_configuration(cfg: &JWT) -> Result<Self, TokenError> {
+        let mut keyring = Self::new();
+
+        if cfg.keys.is_empty() {
+            match &cfg.secret {
+                None => {
+                    error!("no jwt keys or secret configured");
+                    return Err(TokenError::KeyError(String::from("no keys configured")));
+                }
+                Some(secret) => {
+                    keyring.add_secret("default", secret);
+                }
+            }
+        }
+
+        for k in &cfg.keys {
+            keyring.add_configured(k)?;
+        }
+
+        return Ok(keyring);
+    }
+
+    fn add_configured(&mut self, key: &JWTKey) -> Result<(), TokenError> {
+        let read = |path: &Option<String>| -> Result<Option<Vec<u8>>, TokenError> {
+            match path {
+                None => {
+                    return Ok(None);
+                }
+                Some(path) => {
+                    match fs::read(path) {
+                        Err(e) => {
+                            error!("unable to read key file {}: {:?}", path, e);
+                            return Err(TokenError::KeyError(format!("unable to read key file {}", path)));
+                        }
+                        Ok(contents) => {
+                            return Ok(Some(contents));
+                        }
+                    }
+                }
+            }
+        };
+
+        match key.algorithm {
+            configuration::KeyAlgorithm::HS256 => {
+                match &key.secret {
+                    None => {
+                        return Err(TokenError::KeyError(format!("key {} is missing a secret", key.kid)));
+                    }
+                    Some(secret) => {
+                        self.add_secret(&key.kid, secret);
+                        return Ok(());
+                    }
+                }
+            }
+            configuration::KeyAlgorithm::RS256 | configuration::KeyAlgorithm::EdDSA => {
+                let algorithm = if matches!(key.algorithm, configuration::KeyAlgorithm::RS256) {
+                    Algorithm::RS256
+                } else {
+                    Algorithm::EdDSA
+                };
+
+                let private_pem = read(&key.private_key)?;
+                match read(&key.public_key)? {
+                    None => {
+                        return Err(TokenError::KeyError(format!("key {} is missing a public key", key.kid)));
+                    }
+                    Some(public_pem) => {
+                        return self.add_pem(
+                            &key.kid,
+                            algorithm,
+                            private_pem.as_deref(),
+                            &public_pem
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// add an HS256 shared secret
+    pub fn add_secret(&mut self, kid: &str, secret: &str) {
+        self.keys.push(Key {
+            kid: String::from(kid),
+            algorithm: Algorithm::HS256,
+            encoding: Some(EncodingKey::from_secret(secret.as_bytes())),
+            decoding: DecodingKey::from_secret(secret.as_bytes()),
+            jwk: None
+        });
+    }
+
+    /// generate an EdDSA key pair, returned as PEM encoded private and
+    /// public key
+    pub fn generate_eddsa() -> Result<(String, String), TokenError> {
+        let key = SigningKey::generate(&mut OsRng);
+        let private_pem = match key.to_pkcs8_pem(LineEnding::LF) {
+            Err(e) => {
+                error!("unable to encode private key: {:?}", e);
+                return Err(TokenError::KeyError(String::from("unable to encode private key")));
+            }
+            Ok(pem) => pem
+        };
+        let public_pem = match key.verifying_key().to_public_key_pem(LineEnding::LF) {
+            Err(e) => {
+                error!("unable to encode public key: {:?}", e);
+                return Err(TokenError::KeyError(String::from("unable to encode public key")));
+            }
+            Ok(pem) => pem
+        };
+        return Ok((private_pem.to_string(), public_pem));
+    }
+
+    /// add an RS256 or EdDSA key pair. keys without a private key can
+    /// only be used to verify tokens, e.g. keys that are being retired
+    pub fn add_pem(
+        &mut self,
+        kid: &str,
+        algorithm: Algorithm,
+        private_pem: Option<&[u8]>,
+        public_pem: &[u8]
+    ) -> Result<(), TokenError> {
+        let (encoding, decoding) = match algorithm {
+            Algorithm::RS256 => {
+                let encoding = private_pem.map(EncodingKey::from_rsa_pem);
+                (encoding, DecodingKey::from_rsa_pem(public_pem))
+            }
+            Algorithm::EdDSA => {
+                let encoding = private_pem.map(EncodingKey::from_ed_pem);
+                (encoding, DecodingKey::from_ed_pem(public_pem))
+            }
+            _ => {
+                return Err(TokenError::KeyError(format!("unsupported algorithm for key {}: {:?}", kid, algorithm)));
+            }
+        };
+
+        let encoding = match encoding.transpose() {
+            Err(e) => {
+                error!("unable to parse private key {}: {:?}", kid, e);
+                return Err(TokenError::KeyError(format!("unable to parse private key {}", kid)));
+            }
+            Ok(encoding) => encoding
+        };
+        let decoding = match decoding {
+            Err(e) => {
+                error!("unable to parse public key {}: {:?}", kid, e);
+                return Err(TokenError::KeyError(format!("unable to parse public key {}", kid)));
+            }
+            Ok(decoding) => decoding
+        };
+
+        let jwk = Self::public_jwk(kid, algorithm, &decoding)?;
+        self.keys.push(Key {
+            kid: String::from(kid),
+            algorithm: algorithm,
+            encoding: encoding,
+            decoding: decoding,
+            jwk: Some(jwk)
+        });
+        return Ok(());
+    }
+
+    fn public_jwk(
+        kid: &str,
+        algorithm: Algorithm,
+        decoding: &DecodingKey
+    ) -> Result<Jwk, TokenError> {
+        let public_key = match decoding.kind() {
+            DecodingKeyKind::SecretOrDer(der) => der,
+            DecodingKeyKind::RsaModulusExponent { .. } => {
+                return Err(TokenError::KeyError(format!("unexpected key format for key {}", kid)));
+            }
+        };
+
+        let (key_algorithm, parameters) = match algorithm {
+            Algorithm::RS256 => {
+                match RsaPublicKey::from_pkcs1_der(public_key) {
+                    Err(e) => {
+                        error!("unable to parse public key {}: {:?}", kid, e);
+                        return Err(TokenError::KeyError(format!("unable to parse public key {}", kid)));
+                    }
+                    Ok(rsa) => {
+                        (KeyAlgorithm::RS256, AlgorithmParameters::RSA(RSAKeyParameters {
+                            key_type: RSAKeyType::RSA,
+                            n: URL_SAFE_NO_PAD.encode(rsa.n().to_bytes_be()),
+                            e: URL_SAFE_NO_PAD.encode(rsa.e().to_bytes_be())
+                        }))
+                    }
+                }
+            }
+            _ => {
+                (KeyAlgorithm::EdDSA, AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+                    key_type: OctetKeyPairType::OctetKeyPair,
+                    curve: EllipticCurve::Ed25519,
+                    x: URL_SAFE_NO_PAD.encode(public_key)
+                }))
+            }
+        };
+
+        return Ok(Jwk {
+            common: CommonParameters {
+                public_key_use: Some(PublicKeyUse::Signature),
+                key_algorithm: Some(key_algorithm),
+                key_id: Some(String::from(kid)),
+                ..Default::default()
+            },
+            algorithm: parameters
+        });
+    }
+
+    pub fn get(&self, kid: &str) -> Option<&Key> {
+        return self.keys.iter().find(|k| k.kid == kid);
+    }
+
+    /// public keys in jwks format
+    pub fn jwks(&self) -> JwkSet {
+        return JwkSet {
+            keys: self.keys.iter()
+                .filter_map(|k| k.jwk())
+                .collect()
+        };
+    }
+}
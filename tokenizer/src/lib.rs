@@ -1,3 +1,5 @@
+pub mod keyring;
+
 use log::{
     info,
     error,
@@ -6,27 +8,49 @@ use log::{
 
 use chrono::{prelude::*, Duration};
 
-use hmac::{
-    Hmac,
-    Mac
+use jsonwebtoken::{
+    Header,
+    Validation,
+    decode,
+    decode_header,
+    encode,
+    errors::ErrorKind,
+    jwk::JwkSet
 };
-
-use jwt::{
-    SignWithKey,
-    VerifyWithKey,
-    error
+use serde::{
+    Serialize,
+    Deserialize
+};
+use sha2::{
+    Sha256,
+    Digest
 };
-use sha2::Sha256;
-use std::collections::BTreeMap;
 
+use configuration::JWT;
 
+use crate::keyring::Keyring;
 
-#[derive(Debug)]
+
+/// allowed clock skew (in seconds) when checking exp and nbf
+const LEEWAY_SECONDS: u64 = 30;
+
+/// number of seconds an mfa pending token remains valid
+const MFA_PENDING_LIFETIME_SECONDS: i64 = 300;
+
+
+/// registered and private claims carried by an access token
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Claims {
     email: String,
+    #[serde(rename = "tid")]
     tenant_id: uuid::Uuid,
-    issued: chrono::DateTime<Utc>,
-    expiry: chrono::DateTime<Utc>
+    sid: uuid::Uuid,
+    iss: String,
+    aud: String,
+    jti: uuid::Uuid,
+    iat: i64,
+    nbf: i64,
+    exp: i64
 }
 
 impl Claims {
@@ -34,14 +58,22 @@ impl Claims {
     pub fn new(
         email: &str,
         tenant_id: &uuid::Uuid,
+        session_id: &uuid::Uuid,
+        issuer: &str,
+        audience: &str,
         issued: &chrono::DateTime<Utc>,
         expiry: &chrono::DateTime<Utc>
     ) -> Self {
         return Self {
             email: String::from(email),
             tenant_id: tenant_id.clone(),
-            issued: issued.clone(),
-            expiry: expiry.clone()
+            sid: session_id.clone(),
+            iss: String::from(issuer),
+            aud: String::from(audience),
+            jti: uuid::Uuid::new_v4(),
+            iat: issued.timestamp(),
+            nbf: issued.timestamp(),
+            exp: expiry.timestamp()
         };
     }
 
@@ -53,32 +85,121 @@ impl Claims {
         return self.tenant_id.clone();
     }
 
+    /// session the token was issued for
+    pub fn session(&self) -> uuid::Uuid {
+        return self.sid.clone();
+    }
+
+    pub fn issuer(&self) -> String {
+        return self.iss.clone();
+    }
+
+    pub fn audience(&self) -> String {
+        return self.aud.clone();
+    }
+
+    pub fn jti(&self) -> uuid::Uuid {
+        return self.jti.clone();
+    }
+
     pub fn issued_at(&self) -> chrono::DateTime<Utc> {
-        return self.issued;
+        return Utc.timestamp_opt(self.iat, 0).unwrap();
+    }
+
+    pub fn not_before(&self) -> chrono::DateTime<Utc> {
+        return Utc.timestamp_opt(self.nbf, 0).unwrap();
     }
 
     pub fn expiry(&self) -> chrono::DateTime<Utc> {
-        return self.expiry;
+        return Utc.timestamp_opt(self.exp, 0).unwrap();
     }
 }
 
 
+#[derive(Debug)]
 pub enum TokenError {
     HashError,
     SigningError,
-    ToBeImplementedError
+    InvalidToken,
+    Expired,
+    NotYetValid,
+    InvalidIssuer,
+    InvalidAudience,
+    Revoked,
+    KeyError(String)
+}
+
+impl std::fmt::Display for TokenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TokenError::HashError => write!(f, "unable to generate key"),
+            TokenError::SigningError => write!(f, "unable to sign token"),
+            TokenError::InvalidToken => write!(f, "token is malformed or has an invalid signature"),
+            TokenError::Expired => write!(f, "token has expired"),
+            TokenError::NotYetValid => write!(f, "token is not yet valid"),
+            TokenError::InvalidIssuer => write!(f, "token was issued by an unknown issuer"),
+            TokenError::InvalidAudience => write!(f, "token is not intended for this audience"),
+            TokenError::Revoked => write!(f, "token has been revoked"),
+            TokenError::KeyError(message) => write!(f, "key error: {}", message)
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct Tokenizer {
-    secret: String
+    keyring: Keyring,
+    signing_kid: String,
+    issuer: String,
+    audience: String,
+    lifetime: Duration
 }
 
 impl Tokenizer {
-    pub fn new(secret: &str) -> Self {
-        return Self {
-            secret: String::from(secret)
+
+    /// lifetime is the number of seconds a generated token remains valid.
+    /// tokens are signed using the key identified by signing_kid
+    pub fn new(
+        keyring: Keyring,
+        signing_kid: &str,
+        issuer: &str,
+        audience: &str,
+        lifetime: i64
+    ) -> Result<Self, TokenError> {
+        match keyring.get(signing_kid) {
+            None => {
+                error!("signing key not found: {}", signing_kid);
+                return Err(TokenError::KeyError(format!("signing key {} not found", signing_kid)));
+            }
+            Some(key) if key.encoding().is_none() => {
+                error!("signing key has no private key: {}", signing_kid);
+                return Err(TokenError::KeyError(format!("signing key {} has no private key", signing_kid)));
+            }
+            Some(_) => {
+                return Ok(Self {
+                    keyring: keyring,
+                    signing_kid: String::from(signing_kid),
+                    issuer: String::from(issuer),
+                    audience: String::from(audience),
+                    lifetime: Duration::seconds(lifetime)
+                });
+            }
+        }
+    }
+
+    pub fn from_configuration(cfg: &JWT) -> Result<Self, TokenError> {
+        let keyring = Keyring::from_configuration(cfg)?;
+        let signing_kid = match &cfg.signing_key {
+            Some(kid) => kid.clone(),
+            None if cfg.keys.is_empty() => String::from("default"),
+            None => cfg.keys[0].kid.clone()
         };
+        return Self::new(
+            keyring,
+            &signing_kid,
+            &cfg.issuer,
+            &cfg.audience,
+            cfg.expiry
+        );
     }
 
     /**
@@ -86,126 +207,205 @@ impl Tokenizer {
      * TODO: implement some simple encryption for obfuscation
      */
     pub fn generate(
+        &self,
+        email: &str,
+        tenant_id: &uuid::Uuid,
+        session_id: &uuid::Uuid
+    ) -> Result<String, TokenError> {
+        let now = Utc::now();
+        let claims = Claims::new(
+            email,
+            tenant_id,
+            session_id,
+            &self.issuer,
+            &self.audience,
+            &now,
+            &(now + self.lifetime)
+        );
+        return self.sign(&claims);
+    }
+
+    fn sign(
+        &self,
+        claims: &Claims
+    ) -> Result<String, TokenError> {
+        let key = match self.keyring.get(&self.signing_kid) {
+            None => {
+                error!("signing key not found: {}", self.signing_kid);
+                return Err(TokenError::HashError);
+            }
+            Some(key) => key
+        };
+        let encoding = match key.encoding() {
+            None => {
+                error!("signing key has no private key: {}", self.signing_kid);
+                return Err(TokenError::HashError);
+            }
+            Some(encoding) => encoding
+        };
+
+        let mut header = Header::new(key.algorithm());
+        header.kid = Some(key.kid());
+
+        match encode(&header, claims, encoding) {
+            Err(e) => {
+                error!("unable to sign claims: {:?}", e);
+                return Err(TokenError::SigningError);
+            }
+            Ok(token) => {
+                return Ok(token);
+            }
+        }
+    }
+
+    /// generate a short-lived token issued after the password has been
+    /// verified for a user with two-factor authentication. the token is
+    /// issued for a separate audience so it cannot be used as an access token.
+    /// its jti identifies it when it is exchanged, so it is only used once
+    pub fn generate_mfa_pending(
         &self,
         email: &str,
         tenant_id: &uuid::Uuid
     ) -> Result<String, TokenError> {
-        let mut claims = BTreeMap::new();
+        let now = Utc::now();
+        let claims = Claims::new(
+            email,
+            tenant_id,
+            &uuid::Uuid::nil(),
+            &self.issuer,
+            &self.mfa_audience(),
+            &now,
+            &(now + Duration::seconds(MFA_PENDING_LIFETIME_SECONDS))
+        );
+        return self.sign(&claims);
+    }
+
+    /// verify a token generated by generate_mfa_pending
+    pub fn validate_mfa_pending(
+        &self,
+        token: &str
+    ) -> Result<Claims, TokenError> {
+        info!("Tokenizer::validate_mfa_pending()");
+        return self.verify(token, &self.mfa_audience());
+    }
 
-        claims.insert("email", email);
+    fn mfa_audience(&self) -> String {
+        return format!("{}:mfa", self.audience);
+    }
 
-        let tid = tenant_id.to_string();
-        claims.insert("tid", tid.as_str());
+    /// verify the signature and the registered claims of a token. the
+    /// token is verified using the key identified by its kid header
+    pub fn validate(
+        &self,
+        token: &str
+    ) -> Result<Claims, TokenError> {
+        info!("Tokenizer::validate()");
+        return self.verify(token, &self.audience);
+    }
 
-        // iss (issuer)
+    /// verify a token, counting rejected tokens by reason
+    fn verify(
+        &self,
+        token: &str,
+        audience: &str
+    ) -> Result<Claims, TokenError> {
+        let result = self.verify_claims(token, audience);
+        if let Err(e) = &result {
+            let reason = match e {
+                TokenError::Expired => "expired",
+                TokenError::NotYetValid => "not_yet_valid",
+                TokenError::InvalidIssuer => "invalid_issuer",
+                TokenError::InvalidAudience => "invalid_audience",
+                _ => "invalid_token"
+            };
+            metrics::token_validation_failure(reason);
+        }
+        return result;
+    }
 
-        // iat (issued at)
-        let iat = Utc::now().to_rfc3339();
-        claims.insert("iat", iat.as_str());
+    fn verify_claims(
+        &self,
+        token: &str,
+        audience: &str
+    ) -> Result<Claims, TokenError> {
 
-        // exp (expiry)
-        let exp = (Utc::now() + Duration::hours(1)).to_rfc3339();
-        claims.insert("exp", exp.as_str());
+        if token.is_empty() {
+            error!("cannot verify an empty token");
+            return Err(TokenError::InvalidToken);
+        }
 
-        match <Hmac<Sha256>>::new_from_slice(self.secret.as_bytes()) {
+        let header = match decode_header(token) {
             Err(e) => {
-                error!("unable to generate key: {:?}", e);
-                return Err(TokenError::HashError);
+                error!("unable to decode token header: {:?}", e);
+                return Err(TokenError::InvalidToken);
+            }
+            Ok(header) => header
+        };
+        let key = match header.kid.as_deref().and_then(|kid| self.keyring.get(kid)) {
+            None => {
+                error!("token signed with an unknown key: {:?}", header.kid);
+                return Err(TokenError::InvalidToken);
             }
-            Ok(key) => {
-                match claims.sign_with_key(&key) {
-                    Err(e) => {
-                        error!("unable to sign claims: {:?}", e);
-                        return Err(TokenError::SigningError);
+            Some(key) => key
+        };
+
+        let mut validation = Validation::new(key.algorithm());
+        validation.leeway = LEEWAY_SECONDS;
+        validation.validate_nbf = true;
+        validation.set_issuer(&[&self.issuer]);
+        validation.set_audience(&[audience]);
+
+        match decode::<Claims>(token, key.decoding(), &validation) {
+            Err(e) => {
+                debug!("unable to verify token: {:?}", e);
+                match e.kind() {
+                    ErrorKind::ExpiredSignature => {
+                        return Err(TokenError::Expired);
                     }
-                    Ok(token) => {
-                        return Ok(token);
+                    ErrorKind::ImmatureSignature => {
+                        return Err(TokenError::NotYetValid);
+                    }
+                    ErrorKind::InvalidIssuer => {
+                        return Err(TokenError::InvalidIssuer);
+                    }
+                    ErrorKind::InvalidAudience => {
+                        return Err(TokenError::InvalidAudience);
+                    }
+                    _ => {
+                        return Err(TokenError::InvalidToken);
                     }
                 }
             }
+            Ok(data) => {
+                debug!("claims: {:?}", data.claims);
+                return Ok(data.claims);
+            }
         }
     }
 
+    /// public keys that can be used to verify tokens, in jwks format
+    pub fn jwks(&self) -> JwkSet {
+        return self.keyring.jwks();
+    }
+
     pub fn is_valid(
         &self,
         token: &str
     ) -> bool {
         info!("Tokenizer::is_valid()");
-
-        if token.is_empty() {
-            error!("cannot verify an empty token");
-            return false;
-        } else {
-            match <Hmac<Sha256>>::new_from_slice(
-                self.secret.as_bytes()
-            ) {
-                Err(e) => {
-                    error!("unable to generate key: {:?}", e);
-                    return false;
-                }
-                Ok(key) => {
-                    debug!("key: {:?}", key);
-                    let result: Result<BTreeMap<String, String>, error::Error> = token.verify_with_key(&key);
-                    match result {
-                        Err(e) => {
-                            error!("unable to verify token: {:?}", e);
-                            return false;
-                        }
-                        Ok(_) => {
-                            return true;
-                        }
-                    }
-                }
-            }
-        }
+        return self.validate(token).is_ok();
     }
 
     pub fn get_claims(&self, token: &str) -> Result<Claims, TokenError> {
-        if let Ok(key) = <Hmac<Sha256>>::new_from_slice(
-            self.secret.as_bytes()
-        ) {
-            let result: Result<BTreeMap<String, String>, error::Error> = token.verify_with_key(&key);
-            if let Ok(claims) = result {
-                debug!("claims: {:?}", claims);
-
-                let mut tenant_id = uuid::Uuid::nil();
-                if let Some(tid) = claims.get("tid") {
-                    if let Ok(tid) = uuid::Uuid::parse_str(tid) {
-                        tenant_id = tid;
-                    } else {
-                        error!("unable to parse tenant_id from claim");
-                    }
-                }
-
-                let mut issued_at = Utc::now();
-                if let Some(iat) = claims.get("iat") {
-                    if let Ok(iat) = chrono::DateTime::parse_from_rfc3339(iat) {
-                        issued_at = iat.with_timezone(&chrono::Utc);
-                    }
-                }
-
-                let mut expiry = Utc::now();
-                if let Some(exp) = claims.get("exp") {
-                    if let Ok(exp) = chrono::DateTime::parse_from_rfc3339(exp) {
-                        expiry = exp.with_timezone(&chrono::Utc);
-                    }
-                }
+        return self.validate(token);
+    }
 
-                return Ok(Claims::new(
-                    claims.get("email").unwrap(),
-                    &tenant_id,
-                    &issued_at,
-                    &expiry
-                    
-                ));
-            } else {
-                error!("unable to get claims");
-                return Err(TokenError::ToBeImplementedError);
-            }
-        } else {
-            error!("unable to get claims");
-            return Err(TokenError::ToBeImplementedError);
-        }
+    /// hex encoded sha-256 digest of an opaque token, for storing server-side
+    pub fn digest(token: &str) -> String {
+        let hash = Sha256::digest(token.as_bytes());
+        return hash.iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
     }
 }
 
@@ -215,13 +415,24 @@ impl Tokenizer {
 mod tests {
     use super::*;
 
+    use jsonwebtoken::Algorithm;
+
+    fn hmac_tokenizer(secret: &str, audience: &str) -> Tokenizer {
+        let mut keyring = Keyring::new();
+        keyring.add_secret("default", secret);
+        return Tokenizer::new(keyring, "default", "ideas", audience, 3600).ok().unwrap();
+    }
+
+    fn tokenizer() -> Tokenizer {
+        return hmac_tokenizer("testing", "ideas-ui");
+    }
+
     #[test]
     fn test_generate() {
         let tenant_id: uuid::Uuid = uuid::Uuid::nil();
-        let tokenizer = Tokenizer::new("testing");
-        match tokenizer.generate("testing@mailinator.com", &tenant_id) {
+        match tokenizer().generate("testing@mailinator.com", &tenant_id, &uuid::Uuid::nil()) {
             Err(e) => {
-                assert!(false, "unable to generate token");
+                assert!(false, "unable to generate token: {:?}", e);
             }
             Ok(_) => {
                 assert!(true);
@@ -231,7 +442,199 @@ mod tests {
 
     #[test]
     fn test_validate() {
-        let tokenizer = Tokenizer::new("testing");
-        assert!(true);
+        let tenant_id = uuid::Uuid::new_v4();
+        let session_id = uuid::Uuid::new_v4();
+        let tokenizer = tokenizer();
+        let token = tokenizer.generate("testing@mailinator.com", &tenant_id, &session_id).ok().unwrap();
+        match tokenizer.validate(&token) {
+            Err(e) => {
+                assert!(false, "token should be valid: {:?}", e);
+            }
+            Ok(claims) => {
+                assert_eq!(claims.email(), "testing@mailinator.com");
+                assert_eq!(claims.tenant(), tenant_id);
+                assert_eq!(claims.session(), session_id);
+                assert!(claims.expiry() > claims.issued_at());
+            }
+        }
+    }
+
+    #[test]
+    fn test_validate_expired() {
+        let tokenizer = tokenizer();
+        let issued = Utc::now() - Duration::hours(2);
+        let claims = Claims::new(
+            "testing@mailinator.com",
+            &uuid::Uuid::nil(),
+            &uuid::Uuid::nil(),
+            "ideas",
+            "ideas-ui",
+            &issued,
+            &(issued + Duration::hours(1))
+        );
+        let token = tokenizer.sign(&claims).ok().unwrap();
+        assert!(matches!(tokenizer.validate(&token), Err(TokenError::Expired)));
+    }
+
+    #[test]
+    fn test_validate_not_yet_valid() {
+        let tokenizer = tokenizer();
+        let issued = Utc::now() + Duration::hours(1);
+        let claims = Claims::new(
+            "testing@mailinator.com",
+            &uuid::Uuid::nil(),
+            &uuid::Uuid::nil(),
+            "ideas",
+            "ideas-ui",
+            &issued,
+            &(issued + Duration::hours(1))
+        );
+        let token = tokenizer.sign(&claims).ok().unwrap();
+        assert!(matches!(tokenizer.validate(&token), Err(TokenError::NotYetValid)));
+    }
+
+    #[test]
+    fn test_validate_audience() {
+        let other = hmac_tokenizer("testing", "other-service");
+        let token = other.generate("testing@mailinator.com", &uuid::Uuid::nil(), &uuid::Uuid::nil()).ok().unwrap();
+        assert!(matches!(tokenizer().validate(&token), Err(TokenError::InvalidAudience)));
+    }
+
+    #[test]
+    fn test_digest() {
+        let digest = Tokenizer::digest("refresh");
+        assert_eq!(digest.len(), 64);
+        assert_eq!(digest, Tokenizer::digest("refresh"));
+        assert_ne!(digest, Tokenizer::digest("refresh2"));
+    }
+
+    #[test]
+    fn test_validate_signature() {
+        let other = hmac_tokenizer("other secret", "ideas-ui");
+        let token = other.generate("testing@mailinator.com", &uuid::Uuid::nil(), &uuid::Uuid::nil()).ok().unwrap();
+        assert!(matches!(tokenizer().validate(&token), Err(TokenError::InvalidToken)));
+    }
+
+    #[test]
+    fn test_mfa_pending() {
+        let tenant_id = uuid::Uuid::new_v4();
+        let tokenizer = tokenizer();
+
+        let token = tokenizer.generate_mfa_pending("testing@mailinator.com", &tenant_id).ok().unwrap();
+        match tokenizer.validate_mfa_pending(&token) {
+            Err(e) => {
+                assert!(false, "mfa pending token should be valid: {:?}", e);
+            }
+            Ok(claims) => {
+                assert_eq!(claims.email(), "testing@mailinator.com");
+                assert_eq!(claims.tenant(), tenant_id);
+
+                let other = tokenizer.generate_mfa_pending("testing@mailinator.com", &tenant_id).ok().unwrap();
+                let other = tokenizer.validate_mfa_pending(&other).ok().unwrap();
+                assert_ne!(claims.jti(), other.jti());
+            }
+        }
+
+        // mfa pending tokens and access tokens are not interchangeable
+        assert!(matches!(tokenizer.validate(&token), Err(TokenError::InvalidAudience)));
+        let access = tokenizer.generate("testing@mailinator.com", &tenant_id, &uuid::Uuid::nil()).ok().unwrap();
+        assert!(matches!(tokenizer.validate_mfa_pending(&access), Err(TokenError::InvalidAudience)));
+    }
+
+    fn keyring() -> Keyring {
+        let mut keyring = Keyring::new();
+        keyring.add_secret("hmac", "testing");
+        keyring.add_pem(
+            "rsa",
+            Algorithm::RS256,
+            Some(include_bytes!("../testdata/rsa.pem")),
+            include_bytes!("../testdata/rsa.pub.pem")
+        ).ok().unwrap();
+        keyring.add_pem(
+            "ed25519",
+            Algorithm::EdDSA,
+            Some(include_bytes!("../testdata/ed25519.pem")),
+            include_bytes!("../testdata/ed25519.pub.pem")
+        ).ok().unwrap();
+        return keyring;
+    }
+
+    #[test]
+    fn test_asymmetric() {
+        for kid in ["rsa", "ed25519"] {
+            let tokenizer = Tokenizer::new(keyring(), kid, "ideas", "ideas-ui", 3600).ok().unwrap();
+            let token = tokenizer.generate("testing@mailinator.com", &uuid::Uuid::nil(), &uuid::Uuid::nil()).ok().unwrap();
+            assert_eq!(decode_header(&token).ok().unwrap().kid, Some(String::from(kid)));
+            assert!(tokenizer.validate(&token).is_ok(), "{} token should be valid", kid);
+        }
+    }
+
+    #[test]
+    fn test_rotation() {
+        // tokens signed with the previous key remain valid after the signing key changes
+        let previous = Tokenizer::new(keyring(), "rsa", "ideas", "ideas-ui", 3600).ok().unwrap();
+        let token = previous.generate("testing@mailinator.com", &uuid::Uuid::nil(), &uuid::Uuid::nil()).ok().unwrap();
+
+        let current = Tokenizer::new(keyring(), "ed25519", "ideas", "ideas-ui", 3600).ok().unwrap();
+        assert!(current.validate(&token).is_ok());
+
+        // and are rejected once the previous key is removed from the keyring
+        let mut keyring = Keyring::new();
+        keyring.add_pem(
+            "ed25519",
+            Algorithm::EdDSA,
+            Some(include_bytes!("../testdata/ed25519.pem")),
+            include_bytes!("../testdata/ed25519.pub.pem")
+        ).ok().unwrap();
+        let retired = Tokenizer::new(keyring, "ed25519", "ideas", "ideas-ui", 3600).ok().unwrap();
+        assert!(matches!(retired.validate(&token), Err(TokenError::InvalidToken)));
+    }
+
+    #[test]
+    fn test_generate_eddsa() {
+        let (private_pem, public_pem) = Keyring::generate_eddsa().ok().unwrap();
+        let mut keyring = Keyring::new();
+        keyring.add_pem(
+            "generated",
+            Algorithm::EdDSA,
+            Some(private_pem.as_bytes()),
+            public_pem.as_bytes()
+        ).ok().unwrap();
+
+        let tokenizer = Tokenizer::new(keyring, "generated", "ideas", "ideas-ui", 3600).ok().unwrap();
+        let token = tokenizer.generate("testing@mailinator.com", &uuid::Uuid::nil(), &uuid::Uuid::nil()).ok().unwrap();
+        assert!(tokenizer.validate(&token).is_ok());
+    }
+
+    #[test]
+    fn test_verification_only_key() {
+        let mut keyring = Keyring::new();
+        keyring.add_pem(
+            "rsa",
+            Algorithm::RS256,
+            None,
+            include_bytes!("../testdata/rsa.pub.pem")
+        ).ok().unwrap();
+        assert!(matches!(
+            Tokenizer::new(keyring, "rsa", "ideas", "ideas-ui", 3600),
+            Err(TokenError::KeyError(_))
+        ));
+    }
+
+    #[test]
+    fn test_jwks() {
+        let tokenizer = Tokenizer::new(keyring(), "rsa", "ideas", "ideas-ui", 3600).ok().unwrap();
+        let jwks = tokenizer.jwks();
+
+        // shared secrets must never be published
+        assert_eq!(jwks.keys.len(), 2);
+        assert!(jwks.find("hmac").is_none());
+
+        // published keys can be used to verify tokens
+        let token = tokenizer.generate("testing@mailinator.com", &uuid::Uuid::nil(), &uuid::Uuid::nil()).ok().unwrap();
+        let key = jsonwebtoken::DecodingKey::from_jwk(jwks.find("rsa").unwrap()).ok().unwrap();
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.set_audience(&["ideas-ui"]);
+        assert!(decode::<Claims>(&token, &key, &validation).is_ok());
     }
 }